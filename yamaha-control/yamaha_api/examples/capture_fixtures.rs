@@ -0,0 +1,89 @@
+//! Enregistre les réponses d'un amplificateur réel dans `tests/fixtures/<modèle>/`
+//!
+//! ```text
+//! cargo run -p yamaha_api --example capture_fixtures -- 192.168.1.126 [dossier]
+//! ```
+//!
+//! Les identifiants propres à l'appareil (numéro de série, identifiants, UUID) sont
+//! remplacés par des valeurs neutres avant l'écriture.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use yamaha_api::AmpAddress;
+
+/// Endpoints enregistrés et nom du fichier produit
+const ENDPOINTS: &[(&str, &str)] = &[
+    ("getDeviceInfo", "system/getDeviceInfo"),
+    ("getFeatures", "system/getFeatures"),
+    ("getStatus", "main/getStatus"),
+    ("getSignalInfo", "main/getSignalInfo"),
+];
+
+/// Champs anonymisés et valeur de remplacement
+const REDACTED: &[(&str, &str)] = &[
+    ("device_id", "00A0DE000000"),
+    ("system_id", "00000000"),
+    ("serial_number", "Y000000XX"),
+    ("uuid", "00000000-0000-0000-0000-000000000000"),
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let Some(host) = args.next() else {
+        eprintln!("usage: capture_fixtures <host[:port]> [output_dir]");
+        std::process::exit(2);
+    };
+    let address: AmpAddress = host.parse()?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+
+    let mut responses = Vec::new();
+    for (name, path) in ENDPOINTS {
+        let url = format!(
+            "http://{}/YamahaExtendedControl/v1/{path}",
+            address.authority()
+        );
+        let mut body: Value = client.get(&url).send()?.error_for_status()?.json()?;
+        redact(&mut body);
+        responses.push((name, body));
+    }
+
+    let model = responses[0].1["model_name"]
+        .as_str()
+        .ok_or("getDeviceInfo returned no model_name")?
+        .to_lowercase();
+    let dir = match args.next() {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(&model),
+    };
+    std::fs::create_dir_all(&dir)?;
+
+    for (name, body) in responses {
+        let file = dir.join(format!("{name}.json"));
+        std::fs::write(&file, serde_json::to_string_pretty(&body)? + "\n")?;
+        println!("{}", file.display());
+    }
+    Ok(())
+}
+
+/// Remplace récursivement les identifiants propres à l'appareil
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match REDACTED.iter().find(|(name, _)| name == key) {
+                    Some((_, replacement)) if value.is_string() => {
+                        *value = Value::String(replacement.to_string());
+                    }
+                    _ => redact(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
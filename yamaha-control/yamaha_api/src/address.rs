@@ -0,0 +1,278 @@
+use crate::error::{ParseEnumError, YamahaError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+/// Port HTTP par défaut de l'API Yamaha Extended Control
+pub const DEFAULT_PORT: u16 = 80;
+
+/// Hôte d'un amplificateur : adresse IP (v4 ou v6) ou nom d'hôte
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    Ip(IpAddr),
+    Name(String),
+}
+
+impl From<IpAddr> for Host {
+    fn from(ip: IpAddr) -> Self {
+        Host::Ip(ip)
+    }
+}
+
+impl From<Ipv4Addr> for Host {
+    fn from(ip: Ipv4Addr) -> Self {
+        Host::Ip(IpAddr::V4(ip))
+    }
+}
+
+impl From<Ipv6Addr> for Host {
+    fn from(ip: Ipv6Addr) -> Self {
+        Host::Ip(IpAddr::V6(ip))
+    }
+}
+
+impl From<&str> for Host {
+    /// Interprète la chaîne comme une adresse IP si possible, sinon comme un nom d'hôte
+    ///
+    /// La chaîne ne porte pas de port : `hôte:port` se convertit en [`AmpAddress`].
+    fn from(s: &str) -> Self {
+        let trimmed = s.trim_start_matches('[').trim_end_matches(']');
+        match trimmed.parse::<IpAddr>() {
+            Ok(ip) => Host::Ip(ip),
+            Err(_) => Host::Name(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for Host {
+    fn from(s: String) -> Self {
+        Host::from(s.as_str())
+    }
+}
+
+impl fmt::Display for Host {
+    /// Affiche l'hôte tel qu'il apparaît dans une URL (IPv6 entre crochets)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Host::Ip(IpAddr::V6(ip)) => write!(f, "[{}]", ip),
+            Host::Ip(ip) => write!(f, "{}", ip),
+            Host::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Adresse complète d'un amplificateur : hôte et port HTTP
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AmpAddress {
+    /// Adresse IP ou nom d'hôte
+    pub host: Host,
+    /// Port HTTP de l'API
+    pub port: u16,
+}
+
+impl AmpAddress {
+    /// Crée une adresse à partir d'un hôte et d'un port
+    pub fn new(host: impl Into<Host>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+        }
+    }
+
+    /// Partie `hôte[:port]` de l'URL, le port étant omis s'il vaut 80
+    pub fn authority(&self) -> String {
+        if self.port == DEFAULT_PORT {
+            self.host.to_string()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// Adresse IP, si l'hôte n'est pas un nom à résoudre
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.host {
+            Host::Ip(ip) => Some(ip),
+            Host::Name(_) => None,
+        }
+    }
+
+    /// Résout l'adresse de manière asynchrone
+    ///
+    /// # Returns
+    /// * `Result<SocketAddr, YamahaError>` - Première adresse trouvée pour l'hôte
+    pub async fn resolve(&self) -> Result<SocketAddr, YamahaError> {
+        match &self.host {
+            Host::Ip(ip) => Ok(SocketAddr::new(*ip, self.port)),
+            Host::Name(name) => tokio::net::lookup_host((name.as_str(), self.port))
+                .await
+                .map_err(|source| self.resolve_error(Some(source)))?
+                .next()
+                .ok_or_else(|| self.resolve_error(None)),
+        }
+    }
+
+    /// Résout l'adresse de manière synchrone
+    ///
+    /// # Returns
+    /// * `Result<SocketAddr, YamahaError>` - Première adresse trouvée pour l'hôte
+    pub fn resolve_blocking(&self) -> Result<SocketAddr, YamahaError> {
+        match &self.host {
+            Host::Ip(ip) => Ok(SocketAddr::new(*ip, self.port)),
+            Host::Name(name) => (name.as_str(), self.port)
+                .to_socket_addrs()
+                .map_err(|source| self.resolve_error(Some(source)))?
+                .next()
+                .ok_or_else(|| self.resolve_error(None)),
+        }
+    }
+
+    fn resolve_error(&self, source: Option<std::io::Error>) -> YamahaError {
+        YamahaError::Resolve {
+            host: self.host.to_string(),
+            source,
+        }
+    }
+}
+
+impl From<Host> for AmpAddress {
+    fn from(host: Host) -> Self {
+        AmpAddress::new(host, DEFAULT_PORT)
+    }
+}
+
+impl From<IpAddr> for AmpAddress {
+    fn from(host: IpAddr) -> Self {
+        AmpAddress::new(host, DEFAULT_PORT)
+    }
+}
+
+impl From<Ipv4Addr> for AmpAddress {
+    fn from(host: Ipv4Addr) -> Self {
+        AmpAddress::new(host, DEFAULT_PORT)
+    }
+}
+
+impl From<Ipv6Addr> for AmpAddress {
+    fn from(host: Ipv6Addr) -> Self {
+        AmpAddress::new(host, DEFAULT_PORT)
+    }
+}
+
+impl From<&str> for AmpAddress {
+    /// Interprète la chaîne comme [`FromStr`], port éventuel inclus ; une chaîne invalide
+    /// est conservée comme nom d'hôte sur le port par défaut
+    fn from(s: &str) -> Self {
+        s.parse()
+            .unwrap_or_else(|_| AmpAddress::new(s, DEFAULT_PORT))
+    }
+}
+
+impl From<String> for AmpAddress {
+    fn from(s: String) -> Self {
+        AmpAddress::from(s.as_str())
+    }
+}
+
+impl fmt::Display for AmpAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.authority())
+    }
+}
+
+impl FromStr for AmpAddress {
+    type Err = ParseEnumError;
+
+    /// Accepte `hôte`, `hôte:port`, `ipv4`, `ipv4:port`, `ipv6` et `[ipv6]:port`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseEnumError::new("AmpAddress", s);
+        if s.is_empty() {
+            return Err(invalid());
+        }
+        if let Ok(ip) = s.parse::<IpAddr>() {
+            return Ok(AmpAddress::from(ip));
+        }
+        if let Ok(addr) = s.parse::<SocketAddr>() {
+            return Ok(AmpAddress::new(addr.ip(), addr.port()));
+        }
+        if let Some(inner) = s.strip_prefix('[') {
+            return match inner.strip_suffix(']').map(str::parse::<Ipv6Addr>) {
+                Some(Ok(ip)) => Ok(AmpAddress::from(ip)),
+                _ => Err(invalid()),
+            };
+        }
+
+        match s.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() => {
+                let port = port.parse::<u16>().map_err(|_| invalid())?;
+                Ok(AmpAddress::new(Host::Name(host.to_string()), port))
+            }
+            Some(_) => Err(invalid()),
+            None => Ok(AmpAddress::from(Host::Name(s.to_string()))),
+        }
+    }
+}
+
+impl Serialize for AmpAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.authority())
+    }
+}
+
+impl<'de> Deserialize<'de> for AmpAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authority_brackets_ipv6_and_omits_default_port() {
+        let v4 = AmpAddress::from(Ipv4Addr::new(192, 168, 1, 126));
+        assert_eq!(v4.authority(), "192.168.1.126");
+
+        let v6 = AmpAddress::new("fd00::126".parse::<IpAddr>().unwrap(), 8080);
+        assert_eq!(v6.authority(), "[fd00::126]:8080");
+
+        let name = AmpAddress::from("livingroom-amp.lan");
+        assert_eq!(name.authority(), "livingroom-amp.lan");
+        assert_eq!(name.ip(), None);
+    }
+
+    #[test]
+    fn parse_accepts_every_notation() {
+        let cases = [
+            ("192.168.1.126", "192.168.1.126", 80),
+            ("192.168.1.126:8080", "192.168.1.126", 8080),
+            ("fd00::126", "[fd00::126]", 80),
+            ("[fd00::126]", "[fd00::126]", 80),
+            ("[fd00::126]:8080", "[fd00::126]", 8080),
+            ("livingroom-amp.lan", "livingroom-amp.lan", 80),
+            ("livingroom-amp.lan:8080", "livingroom-amp.lan", 8080),
+        ];
+        for (input, host, port) in cases {
+            let addr: AmpAddress = input.parse().unwrap();
+            assert_eq!(addr.host.to_string(), host, "{input}");
+            assert_eq!(addr.port, port, "{input}");
+            assert_eq!(addr.to_string().parse::<AmpAddress>().unwrap(), addr);
+        }
+
+        assert_eq!(
+            AmpAddress::from("livingroom-amp.lan:8080"),
+            AmpAddress::new("livingroom-amp.lan", 8080)
+        );
+        assert_eq!(
+            AmpAddress::from(String::from("[fd00::126]:8080")).authority(),
+            "[fd00::126]:8080"
+        );
+        assert_eq!(AmpAddress::from("amp.lan:http").port, DEFAULT_PORT);
+
+        assert!("".parse::<AmpAddress>().is_err());
+        assert!("amp.lan:http".parse::<AmpAddress>().is_err());
+        assert!("[fd00::126".parse::<AmpAddress>().is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::async_api::YamahaAmpAsync;
+use crate::error::YamahaError;
+use crate::events::{AmpEvent, EventListener};
+use crate::json_data::{GetNetusbPlayInfo, GetSignalInfo, GetStatus, GetTunerPlayInfo};
+use crate::model::Zone;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Délai maximum entre deux requêtes pour conserver l'abonnement aux événements UDP
+///
+/// L'amplificateur cesse d'envoyer des événements 10 minutes après la dernière requête.
+pub const EVENT_KEEPALIVE: Duration = Duration::from_secs(5 * 60);
+
+/// Dernier état connu d'un amplificateur
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AmpSnapshot {
+    /// Statut de chaque zone suivie
+    pub zones: HashMap<Zone, GetStatus>,
+    /// Lecture réseau/USB en cours, si disponible sur l'appareil
+    pub netusb: Option<GetNetusbPlayInfo>,
+    /// Lecture tuner en cours, si disponible sur l'appareil
+    pub tuner: Option<GetTunerPlayInfo>,
+    /// Format du signal reçu par la zone principale, si disponible sur l'appareil
+    pub signal: Option<GetSignalInfo>,
+    /// Date de la dernière mise à jour
+    pub updated_at: Option<SystemTime>,
+    /// Dernière erreur rencontrée lors d'un rafraîchissement
+    pub last_error: Option<String>,
+}
+
+impl AmpSnapshot {
+    /// Statut de la zone demandée, s'il est connu
+    pub fn zone(&self, zone: &Zone) -> Option<&GetStatus> {
+        self.zones.get(zone)
+    }
+
+    /// Statut de la zone principale, s'il est connu
+    pub fn main(&self) -> Option<&GetStatus> {
+        self.zone(&Zone::Main)
+    }
+}
+
+/// Miroir de l'état d'un amplificateur, partagé entre plusieurs abonnés
+///
+/// L'état est rafraîchi par interrogation périodique ([`AmpState::spawn_polling`]) ou
+/// par les événements UDP de l'appareil ([`AmpState::spawn_events`]). Chaque mise à jour
+/// est diffusée aux récepteurs obtenus via [`AmpState::subscribe`].
+#[derive(Debug)]
+pub struct AmpState {
+    amp: YamahaAmpAsync,
+    zones: Vec<Zone>,
+    sender: watch::Sender<AmpSnapshot>,
+    events_received: AtomicBool,
+}
+
+impl AmpState {
+    /// Crée un miroir suivant uniquement la zone principale
+    pub fn new(amp: YamahaAmpAsync) -> Self {
+        Self::with_zones(amp, vec![Zone::Main])
+    }
+
+    /// Crée un miroir suivant les zones spécifiées
+    pub fn with_zones(amp: YamahaAmpAsync, zones: Vec<Zone>) -> Self {
+        let (sender, _) = watch::channel(AmpSnapshot::default());
+        Self {
+            amp,
+            zones,
+            sender,
+            events_received: AtomicBool::new(false),
+        }
+    }
+
+    /// Indique si un événement UDP de cet amplificateur a déjà été appliqué
+    ///
+    /// Permet d'espacer l'interrogation périodique une fois les événements confirmés.
+    pub fn has_received_events(&self) -> bool {
+        self.events_received.load(Ordering::Relaxed)
+    }
+
+    /// Amplificateur suivi
+    pub fn amp(&self) -> &YamahaAmpAsync {
+        &self.amp
+    }
+
+    /// Zones suivies
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    /// Crée un récepteur notifié à chaque changement d'état
+    pub fn subscribe(&self) -> watch::Receiver<AmpSnapshot> {
+        self.sender.subscribe()
+    }
+
+    /// Copie du dernier état connu
+    pub fn snapshot(&self) -> AmpSnapshot {
+        self.sender.borrow().clone()
+    }
+
+    /// Modifie localement le statut d'une zone, par exemple après une commande envoyée
+    ///
+    /// N'a aucun effet si le statut de la zone n'est pas encore connu.
+    pub fn modify_zone(&self, zone: &Zone, modify: impl FnOnce(&mut GetStatus)) {
+        self.sender
+            .send_if_modified(|snapshot| match snapshot.zones.get_mut(zone) {
+                Some(status) => {
+                    modify(status);
+                    snapshot.updated_at = Some(SystemTime::now());
+                    true
+                }
+                None => false,
+            });
+    }
+
+    /// Rafraîchit le statut de toutes les zones et les informations de lecture
+    ///
+    /// Les informations de lecture sont facultatives : une erreur les concernant n'est
+    /// pas remontée, la valeur correspondante devient simplement `None`.
+    pub async fn refresh(&self) -> Result<(), YamahaError> {
+        let mut result = Ok(());
+        for zone in &self.zones {
+            if let Err(e) = self.refresh_zone(zone).await {
+                result = Err(e);
+            }
+        }
+        self.refresh_netusb().await;
+        self.refresh_tuner().await;
+        self.refresh_signal().await;
+        self.record(&result);
+        result
+    }
+
+    /// Rafraîchit le statut d'une zone
+    pub async fn refresh_zone(&self, zone: &Zone) -> Result<(), YamahaError> {
+        let status: GetStatus = self.amp.fetch(&format!("{zone}/getStatus")).await?;
+        self.sender.send_modify(|snapshot| {
+            snapshot.zones.insert(zone.clone(), status);
+            snapshot.updated_at = Some(SystemTime::now());
+        });
+        Ok(())
+    }
+
+    /// Rafraîchit les informations de lecture réseau/USB
+    pub async fn refresh_netusb(&self) {
+        let info = self
+            .amp
+            .get_netusb_play_info()
+            .await
+            .ok()
+            .and_then(|json| serde_json::from_value::<GetNetusbPlayInfo>(json).ok());
+        self.sender.send_modify(|snapshot| snapshot.netusb = info);
+    }
+
+    /// Rafraîchit les informations de lecture du tuner
+    pub async fn refresh_tuner(&self) {
+        let info = self
+            .amp
+            .get_tuner_play_info()
+            .await
+            .ok()
+            .and_then(|json| serde_json::from_value::<GetTunerPlayInfo>(json).ok());
+        self.sender.send_modify(|snapshot| snapshot.tuner = info);
+    }
+
+    /// Rafraîchit le format du signal reçu par la zone principale
+    pub async fn refresh_signal(&self) {
+        let info = self
+            .amp
+            .get_signal_info()
+            .await
+            .ok()
+            .and_then(|json| serde_json::from_value::<GetSignalInfo>(json).ok());
+        self.sender.send_modify(|snapshot| snapshot.signal = info);
+    }
+
+    /// Applique un événement UDP reçu de l'amplificateur
+    ///
+    /// Les valeurs transmises dans l'événement sont appliquées directement ; lorsque
+    /// l'appareil signale un changement sans le détailler (`status_updated`,
+    /// `signal_info_updated`, `play_info_updated`), la partie concernée est rechargée.
+    pub async fn apply_event(&self, event: &AmpEvent) -> Result<(), YamahaError> {
+        if !self.is_from_this_amp(event) {
+            return Ok(());
+        }
+        self.events_received.store(true, Ordering::Relaxed);
+
+        let mut result = Ok(());
+        for zone in &self.zones {
+            let Some(changes) = event.payload.get(zone.as_str()) else {
+                continue;
+            };
+            if (is_flag_set(changes, "status_updated") || !self.merge_zone(zone, changes))
+                && let Err(e) = self.refresh_zone(zone).await
+            {
+                result = Err(e);
+            }
+        }
+
+        if event
+            .payload
+            .get(Zone::Main.as_str())
+            .is_some_and(|changes| is_flag_set(changes, "signal_info_updated"))
+        {
+            self.refresh_signal().await;
+        }
+
+        if let Some(changes) = event.payload.get("netusb") {
+            if is_flag_set(changes, "play_info_updated") {
+                self.refresh_netusb().await;
+            } else if let Some(play_time) = changes.get("play_time").and_then(|t| t.as_i64()) {
+                self.sender
+                    .send_if_modified(|snapshot| match &mut snapshot.netusb {
+                        Some(netusb) => {
+                            netusb.play_time = play_time;
+                            true
+                        }
+                        None => false,
+                    });
+            }
+        }
+
+        if let Some(changes) = event.payload.get("tuner")
+            && is_flag_set(changes, "play_info_updated")
+        {
+            self.refresh_tuner().await;
+        }
+
+        self.record(&result);
+        result
+    }
+
+    /// Lance une tâche rafraîchissant l'état à intervalle régulier
+    pub fn spawn_polling(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let _ = state.refresh().await;
+            }
+        })
+    }
+
+    /// Lance une tâche appliquant les événements UDP reçus sur `listener`
+    ///
+    /// L'amplificateur doit avoir été construit avec [`crate::EventSettings`] pointant
+    /// vers le port de `listener`. L'état est rafraîchi complètement au démarrage puis
+    /// toutes les [`EVENT_KEEPALIVE`], ce qui renouvelle l'abonnement.
+    pub fn spawn_events(self: &Arc<Self>, listener: EventListener) -> JoinHandle<()> {
+        let state = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut keepalive = tokio::time::interval(EVENT_KEEPALIVE);
+            loop {
+                tokio::select! {
+                    _ = keepalive.tick() => {
+                        let _ = state.refresh().await;
+                    }
+                    event = listener.recv() => match event {
+                        Ok(event) => {
+                            let _ = state.apply_event(&event).await;
+                        }
+                        Err(e) => {
+                            state.record(&Err(e));
+                            tokio::time::sleep(Duration::from_secs(1)).await;
+                        }
+                    },
+                }
+            }
+        })
+    }
+
+    fn is_from_this_amp(&self, event: &AmpEvent) -> bool {
+        match (&event.device_id, self.amp.info.device_id.as_str()) {
+            (Some(id), known) if !known.is_empty() => id == known,
+            _ => self
+                .amp
+                .address
+                .ip()
+                .is_none_or(|ip| ip == event.source.ip()),
+        }
+    }
+
+    /// Fusionne les valeurs d'un événement dans le statut connu d'une zone
+    ///
+    /// Renvoie `false` si le statut n'est pas encore connu ou si la fusion échoue.
+    fn merge_zone(&self, zone: &Zone, changes: &serde_json::Value) -> bool {
+        let Some(changes) = changes.as_object() else {
+            return false;
+        };
+        self.sender.send_if_modified(|snapshot| {
+            let Some(status) = snapshot.zones.get_mut(zone) else {
+                return false;
+            };
+            let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(&*status) else {
+                return false;
+            };
+            for (key, value) in changes {
+                if let Some(field) = fields.get_mut(key) {
+                    *field = value.clone();
+                }
+            }
+            match serde_json::from_value::<GetStatus>(serde_json::Value::Object(fields)) {
+                Ok(merged) if merged != *status => {
+                    *status = merged;
+                    snapshot.updated_at = Some(SystemTime::now());
+                    true
+                }
+                _ => false,
+            }
+        });
+        self.sender.borrow().zones.contains_key(zone)
+    }
+
+    fn record(&self, result: &Result<(), YamahaError>) {
+        let error = result.as_ref().err().map(|e| e.to_string());
+        self.sender.send_if_modified(|snapshot| {
+            if snapshot.last_error == error {
+                return false;
+            }
+            snapshot.last_error = error;
+            true
+        });
+    }
+}
+
+fn is_flag_set(changes: &serde_json::Value, flag: &str) -> bool {
+    changes.get(flag).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::YamahaAmpBuilder;
+    use crate::common_api::DeviceInfo;
+    use serde_json::json;
+    use std::net::Ipv4Addr;
+
+    fn state() -> AmpState {
+        let amp = YamahaAmpBuilder::new(Ipv4Addr::new(192, 168, 1, 126))
+            .device_info(DeviceInfo {
+                device_id: "00A0DE123456".into(),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        AmpState::new(amp)
+    }
+
+    fn event(payload: serde_json::Value) -> AmpEvent {
+        AmpEvent {
+            source: "192.168.1.126:41100".parse().unwrap(),
+            device_id: payload["device_id"].as_str().map(str::to_string),
+            payload,
+        }
+    }
+
+    #[test]
+    fn merge_zone_applies_event_values() {
+        let state = state();
+        assert!(!state.merge_zone(&Zone::Main, &json!({ "volume": 62 })));
+
+        state.sender.send_modify(|snapshot| {
+            snapshot.zones.insert(Zone::Main, GetStatus::default());
+        });
+        let mut receiver = state.subscribe();
+        receiver.mark_unchanged();
+
+        assert!(state.merge_zone(
+            &Zone::Main,
+            &json!({ "volume": 62, "mute": true, "power": "on" })
+        ));
+        assert!(receiver.has_changed().unwrap());
+
+        let snapshot = state.snapshot();
+        let main = snapshot.main().unwrap();
+        assert_eq!(main.volume, 62);
+        assert!(main.mute);
+        assert_eq!(main.power, crate::model::PowerState::On);
+    }
+
+    #[test]
+    fn events_from_other_devices_are_ignored() {
+        let state = state();
+        assert!(state.is_from_this_amp(&event(json!({ "device_id": "00A0DE123456" }))));
+        assert!(!state.is_from_this_amp(&event(json!({ "device_id": "00A0DE654321" }))));
+        assert!(state.is_from_this_amp(&event(json!({ "main": { "volume": 1 } }))));
+    }
+}
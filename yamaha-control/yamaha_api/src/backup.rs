@@ -0,0 +1,575 @@
+use crate::async_api::YamahaAmpAsync;
+use crate::common_api::DeviceInfo;
+use crate::error::YamahaError;
+use crate::json_data::{GetFeatures, GetStatus};
+use crate::model::Zone;
+use crate::scene::{Scene, SceneCall};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version du format des sauvegardes produites par cette bibliothèque
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Configuration complète d'un appareil, enregistrée dans un document JSON versionné
+///
+/// Les sections sont conservées telles que renvoyées par l'appareil. Une section absente
+/// (`None`) n'est pas prise en charge par l'appareil d'origine et n'est pas restaurée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    /// Version du format, voir [`BACKUP_VERSION`]
+    pub version: u32,
+    /// Appareil d'origine
+    pub device: DeviceInfo,
+    /// Date de la sauvegarde, en secondes depuis l'epoch Unix
+    pub created_at: u64,
+    /// Réponse de `getStatus` pour chaque zone, par identifiant de zone
+    pub zones: BTreeMap<String, Value>,
+    /// Noms des zones, entrées et programmes sonores (`system/getNameText`), par identifiant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_text: Option<BTreeMap<String, String>>,
+    /// Présélections réseau (`netusb/getPresetInfo`), dans l'ordre des numéros
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub netusb_presets: Option<Vec<Value>>,
+    /// Présélections communes du tuner (`tuner/getPresetInfo?band=common`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuner_presets: Option<Vec<Value>>,
+    /// Réception en cours du tuner (`tuner/getPlayInfo`), rétablie après l'enregistrement
+    /// des présélections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuner_play_info: Option<Map<String, Value>>,
+    /// Horloge et réveil (`clock/getSettings`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<Map<String, Value>>,
+    /// Réglages système (`system/getFuncStatus`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub functions: Option<Map<String, Value>>,
+}
+
+/// Appel d'un setter YXC effectué lors d'une restauration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreCall {
+    /// Chemin de l'endpoint (ex: `system/setNameText`)
+    pub path: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl RestoreCall {
+    fn new(path: impl Into<String>, params: &[(&str, String)]) -> Self {
+        Self {
+            path: path.into(),
+            params: params
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    fn zone(zone: &Zone, call: SceneCall) -> Self {
+        Self::new(call.path(zone), &call.params())
+    }
+
+    async fn send(&self, amp: &YamahaAmpAsync) -> Result<(), YamahaError> {
+        let params: Vec<(&str, &str)> = self
+            .params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        amp.raw_get(&self.path, &params).await?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for RestoreCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)?;
+        for (i, (key, value)) in self.params.iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            write!(f, "{separator}{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Différences entre une sauvegarde et l'état actuel d'un appareil
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestorePlan {
+    /// Appels rétablissant la sauvegarde, dans l'ordre
+    pub calls: Vec<RestoreCall>,
+    /// Différences que l'API ne permet pas de rétablir, à reporter à la main
+    pub manual: Vec<String>,
+}
+
+impl RestorePlan {
+    /// Indique si l'appareil correspond déjà à la sauvegarde
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty() && self.manual.is_empty()
+    }
+}
+
+impl Backup {
+    /// Lit la configuration complète de l'amplificateur
+    ///
+    /// Les sections refusées par l'appareil (`response_code` différent de 0) sont
+    /// considérées comme non prises en charge ; les autres erreurs interrompent la lecture.
+    pub async fn capture(amp: &YamahaAmpAsync) -> Result<Self, YamahaError> {
+        let device: DeviceInfo = amp.fetch("system/getDeviceInfo").await?;
+        let features: GetFeatures = amp.fetch("system/getFeatures").await?;
+        let mut zones = BTreeMap::new();
+        for zone in &features.zone {
+            let status = amp.get_zone_status(Zone::from_str_lossy(&zone.id)).await?;
+            zones.insert(zone.id.clone(), status);
+        }
+
+        let name_text =
+            optional(amp.raw_get("system/getNameText", &[]).await)?.map(|names| name_texts(&names));
+        let netusb_presets =
+            optional(amp.raw_get("netusb/getPresetInfo", &[]).await)?.map(preset_info);
+        let tuner_presets = optional(
+            amp.raw_get("tuner/getPresetInfo", &[("band", "common")])
+                .await,
+        )?
+        .map(preset_info);
+        let tuner_play_info = optional(amp.raw_get("tuner/getPlayInfo", &[]).await)?.map(object);
+        let clock = optional(amp.raw_get("clock/getSettings", &[]).await)?.map(object);
+        let functions = optional(amp.raw_get("system/getFuncStatus", &[]).await)?.map(object);
+
+        Ok(Self {
+            version: BACKUP_VERSION,
+            device,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            zones,
+            name_text,
+            netusb_presets,
+            tuner_presets,
+            tuner_play_info,
+            clock,
+            functions,
+        })
+    }
+
+    /// Décode une sauvegarde, en refusant les formats plus récents que [`BACKUP_VERSION`]
+    pub fn from_json(text: &str) -> Result<Self, YamahaError> {
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+
+        let header: Header = serde_json::from_str(text)?;
+        if header.version > BACKUP_VERSION {
+            return Err(YamahaError::Other(format!(
+                "Backup format version {} is newer than the supported version {BACKUP_VERSION}",
+                header.version
+            )));
+        }
+        Ok(serde_json::from_str(text)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, YamahaError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Liste les différences avec `current`, l'état actuel de l'appareil
+    ///
+    /// Les réglages système, les noms, l'horloge et les présélections du tuner viennent en
+    /// premier, puis les zones dans l'ordre des appels de [`Scene::plan`].
+    ///
+    /// Une présélection du tuner ne s'enregistre qu'en réglant d'abord sa fréquence
+    /// (`setFreq` puis `storePreset`) : le plan se termine alors par un `setFreq` vers la
+    /// station écoutée, ou la signale dans `manual` si elle ne peut pas être rétablie.
+    ///
+    /// # Returns
+    /// * `RestorePlan` - Vide si l'appareil correspond déjà à la sauvegarde
+    pub fn plan(&self, current: &Backup) -> RestorePlan {
+        let mut plan = self.plan_settings(current);
+        for (zone, scene, status) in self.zone_scenes(current, &mut plan.manual) {
+            plan.calls.extend(
+                scene
+                    .plan(&status)
+                    .into_iter()
+                    .map(|call| RestoreCall::zone(&zone, call)),
+            );
+        }
+        plan
+    }
+
+    /// Restaure la sauvegarde sur l'amplificateur, en n'effectuant que les appels
+    /// nécessaires
+    ///
+    /// Le statut de chaque zone est relu après un changement d'alimentation, comme pour
+    /// [`Scene::apply`]. Les présélections du tuner sont enregistrées en changeant de
+    /// station ; la station écoutée est ensuite rétablie (voir [`Backup::plan`]).
+    ///
+    /// # Returns
+    /// * `Result<RestorePlan, YamahaError>` - Appels effectués et différences restant à
+    ///   reporter à la main
+    pub async fn restore(&self, amp: &YamahaAmpAsync) -> Result<RestorePlan, YamahaError> {
+        let current = Self::capture(amp).await?;
+        let mut plan = self.plan_settings(&current);
+        for call in &plan.calls {
+            call.send(amp).await?;
+        }
+        for (zone, scene, _) in self.zone_scenes(&current, &mut plan.manual) {
+            let calls = scene.apply(amp, &zone).await?;
+            plan.calls
+                .extend(calls.into_iter().map(|call| RestoreCall::zone(&zone, call)));
+        }
+        Ok(plan)
+    }
+
+    /// Zones de la sauvegarde présentes sur l'appareil, avec leur statut actuel
+    fn zone_scenes(
+        &self,
+        current: &Backup,
+        manual: &mut Vec<String>,
+    ) -> Vec<(Zone, Scene, GetStatus)> {
+        let mut scenes = Vec::new();
+        for (id, status) in &self.zones {
+            let Some(now) = current.zones.get(id) else {
+                manual.push(format!("zone {id}: not available on this device"));
+                continue;
+            };
+            let decoded = serde_json::from_value::<GetStatus>(status.clone())
+                .ok()
+                .zip(serde_json::from_value::<GetStatus>(now.clone()).ok());
+            let Some((wanted, now)) = decoded else {
+                manual.push(format!("zone {id}: unreadable status"));
+                continue;
+            };
+            let zone = Zone::from_str_lossy(id);
+            scenes.push((zone.clone(), Scene::from_status(id, zone, &wanted), now));
+        }
+        scenes
+    }
+
+    fn plan_settings(&self, current: &Backup) -> RestorePlan {
+        let mut plan = RestorePlan::default();
+        let calls = &mut plan.calls;
+        let manual = &mut plan.manual;
+
+        if let Some(functions) = section(&self.functions, &current.functions, "system", manual) {
+            for (key, wanted) in self.functions.iter().flatten() {
+                match functions.get(key) {
+                    None => manual.push(format!("system {key}: not available on this device")),
+                    Some(now) if now == wanted => {}
+                    Some(_) => match (system_setter(key), wanted) {
+                        (Some((function, "enable")), Value::Bool(enable)) => {
+                            calls.push(RestoreCall::new(
+                                format!("system/{function}"),
+                                &[("enable", enable.to_string())],
+                            ))
+                        }
+                        (Some((function, param)), Value::Number(value)) if param != "enable" => {
+                            calls.push(RestoreCall::new(
+                                format!("system/{function}"),
+                                &[(param, value.to_string())],
+                            ))
+                        }
+                        _ => manual.push(format!("system {key}: set to {wanted}")),
+                    },
+                }
+            }
+        }
+
+        if let Some(names) = section(&self.name_text, &current.name_text, "names", manual) {
+            for (id, text) in self.name_text.iter().flatten() {
+                match names.get(id) {
+                    None => manual.push(format!("name of {id}: not available on this device")),
+                    Some(now) if now == text => {}
+                    Some(_) => calls.push(RestoreCall::new(
+                        "system/setNameText",
+                        &[("id", id.clone()), ("text", text.clone())],
+                    )),
+                }
+            }
+        }
+
+        if let Some(clock) = section(&self.clock, &current.clock, "clock", manual) {
+            for (key, wanted) in self.clock.iter().flatten() {
+                if clock.get(key) == Some(wanted) {
+                    continue;
+                }
+                match (key.as_str(), wanted) {
+                    ("auto_sync", Value::Bool(enable)) => calls.push(RestoreCall::new(
+                        "clock/setAutoSync",
+                        &[("enable", enable.to_string())],
+                    )),
+                    ("format", Value::String(format)) => calls.push(RestoreCall::new(
+                        "clock/setClockFormat",
+                        &[("format", format.clone())],
+                    )),
+                    _ => manual.push(format!("clock {key}: set to {wanted}")),
+                }
+            }
+        }
+
+        if let Some(presets) = section(
+            &self.netusb_presets,
+            &current.netusb_presets,
+            "netusb presets",
+            manual,
+        ) {
+            for (i, wanted) in self.netusb_presets.iter().flatten().enumerate() {
+                if presets.get(i) != Some(wanted) {
+                    let text = wanted.get("text").and_then(Value::as_str).unwrap_or("");
+                    let input = wanted.get("input").and_then(Value::as_str).unwrap_or("");
+                    manual.push(match text {
+                        "" => format!("netusb preset {}: clear", i + 1),
+                        _ => format!("netusb preset {}: store \"{text}\" ({input})", i + 1),
+                    });
+                }
+            }
+        }
+
+        if let Some(presets) = section(
+            &self.tuner_presets,
+            &current.tuner_presets,
+            "tuner presets",
+            manual,
+        ) {
+            let mut retuned = false;
+            for (i, wanted) in self.tuner_presets.iter().flatten().enumerate() {
+                if presets.get(i) == Some(wanted) {
+                    continue;
+                }
+                let band = wanted.get("band").and_then(Value::as_str).unwrap_or("");
+                let number = wanted.get("number").and_then(Value::as_i64).unwrap_or(0);
+                let num = (i + 1).to_string();
+                if matches!(band, "fm" | "am") && number > 0 && presets.len() > i {
+                    calls.push(RestoreCall::new(
+                        "tuner/setFreq",
+                        &[
+                            ("band", band.to_string()),
+                            ("tuning", "direct".to_string()),
+                            ("num", number.to_string()),
+                        ],
+                    ));
+                    calls.push(RestoreCall::new("tuner/storePreset", &[("num", num)]));
+                    retuned = true;
+                } else {
+                    manual.push(format!("tuner preset {num}: clear"));
+                }
+            }
+            if retuned {
+                match current.tuner_play_info.as_ref().and_then(station) {
+                    Some((band, freq)) => calls.push(RestoreCall::new(
+                        "tuner/setFreq",
+                        &[
+                            ("band", band.to_string()),
+                            ("tuning", "direct".to_string()),
+                            ("num", freq.to_string()),
+                        ],
+                    )),
+                    None => manual.push("tuner: tune back to the previous station".to_string()),
+                }
+            }
+        }
+
+        plan
+    }
+}
+
+/// Section de l'appareil à comparer, `None` si la sauvegarde ne la contient pas ou si
+/// l'appareil ne la prend pas en charge (signalé dans `manual`)
+fn section<'a, T>(
+    wanted: &Option<T>,
+    current: &'a Option<T>,
+    name: &str,
+    manual: &mut Vec<String>,
+) -> Option<&'a T> {
+    match (wanted, current) {
+        (Some(_), None) => {
+            manual.push(format!("{name}: not available on this device"));
+            None
+        }
+        (Some(_), current) => current.as_ref(),
+        (None, _) => None,
+    }
+}
+
+/// Setters YXC des réglages de `system/getFuncStatus` : clé, fonction et paramètre
+///
+/// Les réglages absents de cette table sont signalés comme différences à reporter à la
+/// main plutôt que restaurés par un appel deviné.
+const SYSTEM_SETTERS: &[(&str, &str, &str)] = &[
+    ("auto_power_standby", "setAutoPowerStandby", "enable"),
+    ("ir_sensor", "setIrSensor", "enable"),
+    ("speaker_a", "setSpeakerA", "enable"),
+    ("speaker_b", "setSpeakerB", "enable"),
+    ("dimmer", "setDimmer", "value"),
+    ("zone_b_volume_sync", "setZoneBVolumeSync", "enable"),
+    ("hdmi_out_1", "setHdmiOut1", "enable"),
+    ("hdmi_out_2", "setHdmiOut2", "enable"),
+    ("hdmi_out_3", "setHdmiOut3", "enable"),
+    ("party_mode", "setPartyMode", "enable"),
+    ("speaker_pattern", "setSpeakerPattern", "num"),
+];
+
+/// Setter `system/set*` d'un réglage de `getFuncStatus` et paramètre portant sa valeur
+///
+/// # Returns
+/// * `Option<(&str, &str)>` - Fonction et paramètre, `None` pour un réglage inconnu
+pub(crate) fn system_setter(key: &str) -> Option<(&'static str, &'static str)> {
+    SYSTEM_SETTERS
+        .iter()
+        .find(|(name, _, _)| *name == key)
+        .map(|&(_, function, param)| (function, param))
+}
+
+/// Les sections refusées par l'appareil ne sont pas prises en charge
+fn optional(result: Result<Value, YamahaError>) -> Result<Option<Value>, YamahaError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(YamahaError::Device { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+fn preset_info(value: Value) -> Vec<Value> {
+    match object(value).remove("preset_info") {
+        Some(Value::Array(presets)) => presets,
+        _ => Vec::new(),
+    }
+}
+
+/// Bande et fréquence écoutées d'après `tuner/getPlayInfo`, `None` hors FM/AM
+fn station(play_info: &Map<String, Value>) -> Option<(&str, i64)> {
+    let band = play_info.get("band")?.as_str()?;
+    if !matches!(band, "fm" | "am") {
+        return None;
+    }
+    let freq = play_info.get(band)?.get("freq")?.as_i64()?;
+    Some((band, freq))
+}
+
+/// Aplatit les listes `id`/`text` de `getNameText`
+fn name_texts(value: &Value) -> BTreeMap<String, String> {
+    value
+        .as_object()
+        .into_iter()
+        .flat_map(|lists| lists.values())
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|item| {
+            let id = item.get("id")?.as_str()?;
+            let text = item.get("text")?.as_str()?;
+            Some((id.to_string(), text.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn backup() -> Backup {
+        Backup {
+            version: BACKUP_VERSION,
+            device: DeviceInfo::default(),
+            created_at: 0,
+            zones: BTreeMap::new(),
+            name_text: Some(BTreeMap::from([("hdmi1".into(), "Apple TV".into())])),
+            netusb_presets: Some(vec![json!({ "input": "net_radio", "text": "FIP" })]),
+            tuner_presets: Some(vec![json!({ "band": "fm", "number": 101700 })]),
+            tuner_play_info: Some(object(json!({
+                "band": "am",
+                "am": { "freq": 999 },
+                "fm": { "freq": 94500 }
+            }))),
+            clock: Some(object(json!({ "auto_sync": true, "alarm_on": true }))),
+            functions: Some(object(json!({
+                "hdmi_out_1": true,
+                "speaker_pattern": 2,
+                "hdmi_standby_through": "auto"
+            }))),
+        }
+    }
+
+    #[test]
+    fn system_setters_come_from_the_yxc_table() {
+        assert_eq!(system_setter("hdmi_out_1"), Some(("setHdmiOut1", "enable")));
+        assert_eq!(
+            system_setter("speaker_pattern"),
+            Some(("setSpeakerPattern", "num"))
+        );
+        assert_eq!(system_setter("dimmer"), Some(("setDimmer", "value")));
+        assert_eq!(system_setter("hdmi_standby_through"), None);
+        assert_eq!(system_setter("surround_ai"), None);
+    }
+
+    #[test]
+    fn plan_only_lists_the_differences() {
+        let wanted = backup();
+        assert!(wanted.plan(&wanted).is_empty());
+
+        let mut current = backup();
+        current.name_text = Some(BTreeMap::from([("hdmi1".into(), "HDMI1".into())]));
+        current.netusb_presets = Some(vec![json!({ "input": "unknown", "text": "" })]);
+        current.tuner_presets = Some(vec![json!({ "band": "unknown", "number": 0 })]);
+        current.clock = Some(object(json!({ "auto_sync": false, "alarm_on": false })));
+        current.functions = Some(object(json!({
+            "hdmi_out_1": false,
+            "speaker_pattern": 1,
+            "hdmi_standby_through": "off"
+        })));
+
+        let plan = wanted.plan(&current);
+        let calls: Vec<String> = plan.calls.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            calls,
+            [
+                "system/setHdmiOut1?enable=true",
+                "system/setSpeakerPattern?num=2",
+                "system/setNameText?id=hdmi1&text=Apple TV",
+                "clock/setAutoSync?enable=true",
+                "tuner/setFreq?band=fm&tuning=direct&num=101700",
+                "tuner/storePreset?num=1",
+                "tuner/setFreq?band=am&tuning=direct&num=999",
+            ]
+        );
+        assert_eq!(
+            plan.manual,
+            [
+                "system hdmi_standby_through: set to \"auto\"",
+                "clock alarm_on: set to true",
+                "netusb preset 1: store \"FIP\" (net_radio)",
+            ]
+        );
+
+        current.tuner_play_info = Some(object(json!({ "band": "dab" })));
+        let plan = wanted.plan(&current);
+        assert!(
+            plan.manual
+                .contains(&"tuner: tune back to the previous station".to_string())
+        );
+
+        current.tuner_presets = None;
+        let plan = wanted.plan(&current);
+        assert!(
+            plan.manual
+                .contains(&"tuner presets: not available on this device".to_string())
+        );
+    }
+
+    #[test]
+    fn newer_formats_are_rejected() {
+        let mut newer = backup();
+        newer.version = BACKUP_VERSION + 1;
+        let text = newer.to_json().unwrap();
+        assert!(Backup::from_json(&text).is_err());
+
+        let text = backup().to_json().unwrap();
+        assert_eq!(Backup::from_json(&text).unwrap(), backup());
+    }
+}
@@ -1,9 +1,12 @@
-use crate::common_api::{ApiResponse, DeviceInfo, YamahaAmpBase, YamahaApi};
-use crate::error::{YamahaError, YamahaErrorCode};
+use crate::common_api::{DeviceInfo, YamahaAmpBase, YamahaApi, parse_response};
+use crate::error::{RequestContext, YamahaError};
+use crate::json_data::GetStatus;
+use crate::limits::VolumePolicy;
 use crate::model::Zone;
-use crate::{Input, PowerState, SoundProgram};
+use crate::retry::RetryPolicy;
+use crate::{Input, LinkControl, PowerState, SoundProgram, SurrDecoderType, ToneControlMode};
 use reqwest::blocking::Client as BlockingClient;
-use std::net::Ipv4Addr;
+use serde::de::DeserializeOwned;
 use std::ops::Deref;
 
 impl Deref for YamahaAmpBlocking {
@@ -21,38 +24,142 @@ pub struct YamahaAmpBlocking(YamahaAmpBase<BlockingClient>);
 impl YamahaApi for YamahaAmpBlocking {}
 
 impl YamahaAmpBlocking {
-    /// Crée une nouvelle instance à partir d'une découverte réseau
-    pub(crate) fn from_discovery(ip: Ipv4Addr, json: serde_json::Value) -> Self {
-        Self(YamahaAmpBase {
-            ip,
-            client: BlockingClient::new(),
-            info: serde_json::from_value::<DeviceInfo>(json).unwrap_or_default(),
-        })
+    /// Crée une nouvelle instance à partir de sa structure de base
+    pub(crate) fn from_base(base: YamahaAmpBase<BlockingClient>) -> Self {
+        Self(base)
     }
 
-    /// Effectue une requête HTTP GET synchrone vers l'amplificateur
+    /// Met à jour les informations sur le périphérique
+    pub(crate) fn set_info(&mut self, info: DeviceInfo) {
+        self.0.info = info;
+    }
+
+    /// Remplace les limites de volume de cet amplificateur
+    pub fn with_volume_policy(mut self, policy: VolumePolicy) -> Self {
+        self.0.volume_policy = Some(policy);
+        self
+    }
+
+    /// Ignore les limites de volume, pour une opération d'administration délibérée
+    ///
+    /// Les requêtes sont alors envoyées telles quelles, sans relire le statut des zones.
+    pub fn with_volume_override(mut self, enabled: bool) -> Self {
+        self.0.volume_override = enabled;
+        self
+    }
+
+    /// Remplace la politique de relance des requêtes de cet amplificateur
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.0.retry = retry;
+        self
+    }
+
+    /// Effectue une requête HTTP GET synchrone vers l'amplificateur, en appliquant la
+    /// politique de relance
     ///
     /// # Arguments
-    /// * `url` - L'URL complète de la requête
+    /// * `path` - Chemin de l'endpoint (ex: `main/setVolume`)
+    /// * `params` - Paramètres de la requête, encodés par [`YamahaApi::build_path`]
     ///
     /// # Returns
     /// * `Result<T, YamahaError>` - Le résultat désérialisé ou une erreur
-    fn request<T: for<'de> serde::Deserialize<'de>>(&self, url: String) -> Result<T, YamahaError> {
-        let response = self
-            .0
-            .client
-            .get(url)
-            .send()
-            .map_err(YamahaError::Http)?
-            .json::<ApiResponse<T>>()
-            .map_err(YamahaError::Http)?;
-
-        match (response.response_code, response.data) {
-            (0, Some(data)) => Ok(data),
-            (code, _) => Err(YamahaError::YamahaErrorCode(YamahaErrorCode::from_code(
-                code,
-            ))),
+    fn request<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T, YamahaError> {
+        let Some(guard) = self.0.volume_guard(path, params) else {
+            return self.send_with_retry(&self.build_path(path, params));
+        };
+        let status_path = format!("{}/getStatus", guard.zone());
+        let status: Option<GetStatus> = match guard.needs_status() {
+            true => Some(self.send_with_retry(&status_path)?),
+            false => None,
+        };
+        guard.check(status.as_ref())?;
+
+        if let Some(cap) = status
+            .as_ref()
+            .and_then(|status| guard.power_on_cap(status))
+        {
+            let volume = cap.to_string();
+            let path = self.build_path(
+                &format!("{}/setVolume", guard.zone()),
+                &[("volume", &volume)],
+            );
+            self.send_with_retry::<serde_json::Value>(&path)?;
         }
+        self.send_with_retry(&self.build_path(path, params))
+    }
+
+    /// Envoie une requête déjà construite, en appliquant la politique de relance
+    fn send_with_retry<T: DeserializeOwned>(&self, path: &str) -> Result<T, YamahaError> {
+        let mut attempt = 1;
+        loop {
+            match self.send(path) {
+                Err(e) if self.0.retry.should_retry(attempt, path, &e) => {
+                    std::thread::sleep(self.0.retry.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Effectue une unique tentative de requête HTTP GET synchrone
+    fn send<T: DeserializeOwned>(&self, path: &str) -> Result<T, YamahaError> {
+        let context = RequestContext::new(&self.0.address, path);
+        let mut request = self.0.client.get(self.endpoint(&self.0.address, path));
+        if let Some(events) = &self.0.events {
+            request = request
+                .header("X-AppName", &events.app_name)
+                .header("X-AppPort", events.port);
+        }
+        let body = request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .map_err(|e| YamahaError::from_reqwest(context.clone(), e))?;
+
+        parse_response(context, &body)
+    }
+
+    /// Appelle un endpoint YXC quelconque, y compris ceux qui ne sont pas encore couverts
+    /// par cette bibliothèque
+    ///
+    /// # Arguments
+    /// * `path` - Chemin de l'endpoint (ex: `system/getFuncStatus`, `zone2/setVolume`)
+    /// * `params` - Paramètres de la requête, encodés automatiquement
+    ///
+    /// # Returns
+    /// * `Result<serde_json::Value, YamahaError>` - La réponse, sans `response_code`
+    pub fn raw_get(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(path, params)
+    }
+
+    /// Interroge un endpoint de lecture et décode directement sa réponse
+    ///
+    /// Une réponse inattendue donne [`YamahaError::Decode`] avec le contexte de la requête.
+    pub(crate) fn fetch<T: DeserializeOwned>(&self, path: &str) -> Result<T, YamahaError> {
+        self.request(path, &[])
+    }
+
+    /// Récupère les informations sur le périphérique (modèle, identifiant, version, ...)
+    pub fn get_device_info(&self) -> Result<serde_json::Value, YamahaError> {
+        self.request("system/getDeviceInfo", &[])
+    }
+
+    /// Renomme une zone, une entrée ou un programme sonore
+    ///
+    /// # Arguments
+    /// * `id` - Identifiant de l'élément à renommer (ex: `main`, `hdmi1`)
+    /// * `text` - Nouveau nom, encodé automatiquement
+    pub fn set_name_text(&self, id: &str, text: &str) -> Result<serde_json::Value, YamahaError> {
+        self.request("system/setNameText", &[("id", id), ("text", text)])
     }
 
     /// Récupère le statut d'une zone spécifique
@@ -60,26 +167,53 @@ impl YamahaAmpBlocking {
     /// # Arguments
     /// * `zone` - La zone pour laquelle récupérer le statut
     pub fn get_zone_status(&self, zone: Zone) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, &format!("{zone}/getStatus"));
-        Self::request(self, url)
+        self.request(&format!("{zone}/getStatus"), &[])
     }
 
     /// Récupère la liste des programmes sonores disponibles pour une zone
     pub fn get_sound_program_list(&self, zone: Zone) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, &format!("{zone}/getSoundProgramList"));
-        Self::request(self, url)
+        self.request(&format!("{zone}/getSoundProgramList"), &[])
     }
 
     /// Récupère le statut de la zone principale
     pub fn get_main_status(&self) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, "main/getStatus");
-        Self::request(self, url)
+        self.request("main/getStatus", &[])
+    }
+
+    /// Récupère les fonctionnalités supportées par l'appareil
+    pub fn get_features(&self) -> Result<serde_json::Value, YamahaError> {
+        self.request("system/getFeatures", &[])
     }
 
     /// Récupère les informations sur le signal audio
     pub fn get_signal_info(&self) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, "main/getSignalInfo");
-        Self::request(self, url)
+        self.request("main/getSignalInfo", &[])
+    }
+
+    /// Récupère les informations de lecture réseau/USB (titre, artiste, progression, ...)
+    pub fn get_netusb_play_info(&self) -> Result<serde_json::Value, YamahaError> {
+        self.request("netusb/getPlayInfo", &[])
+    }
+
+    /// Rappelle un préréglage réseau/USB (radio Internet, serveur, ...) sur une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone basculée sur l'entrée du préréglage
+    /// * `num` - Numéro du préréglage, à partir de 1
+    pub fn recall_netusb_preset(
+        &self,
+        zone: Zone,
+        num: u32,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            "netusb/recallPreset",
+            &[("zone", zone.as_str()), ("num", &num.to_string())],
+        )
+    }
+
+    /// Récupère les informations de lecture du tuner (bande, fréquence, RDS, ...)
+    pub fn get_tuner_play_info(&self) -> Result<serde_json::Value, YamahaError> {
+        self.request("tuner/getPlayInfo", &[])
     }
 
     /// Définit le volume principal
@@ -87,8 +221,7 @@ impl YamahaAmpBlocking {
     /// # Arguments
     /// * `volume` - Niveau de volume (généralement entre -80 et 16)
     pub fn set_volume(&self, volume: i32) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, &format!("main/setVolume?volume={}", volume));
-        Self::request(self, url)
+        self.set_zone_volume(Zone::Main, volume.into())
     }
 
     /// Définit le programme sonore
@@ -96,124 +229,461 @@ impl YamahaAmpBlocking {
         &self,
         program: SoundProgram,
     ) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!("main/setSoundProgram?program={}", program),
-        );
-        Self::request(self, url)
+        self.set_zone_sound_program(Zone::Main, program)
     }
 
     /// Contrôle l'alimentation de l'amplificateur
     pub fn set_power(&self, power_state: PowerState) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!("main/setPower?power={}", power_state.as_str()),
-        );
-        Self::request(self, url)
+        self.set_zone_power(Zone::Main, power_state)
     }
 
     /// Active ou désactive le mode muet
     pub fn set_mute(&self, mute: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!("main/setMute?enable={}", mute.to_string().to_lowercase()),
-        );
-        Self::request(self, url)
+        self.set_zone_mute(Zone::Main, mute)
     }
 
     /// Change la source d'entrée
     pub fn set_input(&self, input: Input) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(&self.0.ip, &format!("main/setInput?input={}", input));
-        Self::request(self, url)
+        self.set_zone_input(Zone::Main, input)
     }
 
     /// Active ou désactive le mode Direct
     pub fn set_direct(&self, direct: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setDirect?enable={}",
-                direct.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_zone_direct(Zone::Main, direct)
     }
 
     /// Active ou désactive le mode Pure Direct
     pub fn set_pure_direct(&self, direct: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setPureDirect?enable={}",
-                direct.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_zone_pure_direct(Zone::Main, direct)
     }
 
     /// Active ou désactive l'amélioration du son
     pub fn set_enhancer(&self, enhance: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setEnhancer?enable={}",
-                enhance.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_zone_enhancer(Zone::Main, enhance)
     }
 
     /// Règle le niveau des dialogues
     pub fn set_dialogue_level(&self, level: i32) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!("main/setDialogueLevel?value={}", level),
-        );
-        Self::request(self, url)
+        self.set_zone_dialogue_level(Zone::Main, level.into())
     }
 
     /// Règle le volume du caisson de basse
     pub fn set_subwoofer_volume(&self, volume: i32) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!("main/setSubwooferVolume?volume={}", volume),
-        );
-        Self::request(self, url)
+        self.set_zone_subwoofer_volume(Zone::Main, volume.into())
     }
 
     /// Active ou désactive l'extension des basses
     pub fn set_bass_extension(&self, extension: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setBassExtension?enable={}",
-                extension.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_zone_bass_extension(Zone::Main, extension)
     }
 
     /// Active ou désactive le mode Extra Bass
     pub fn set_extra_bass(&self, extra_bass: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setExtraBass?enable={}",
-                extra_bass.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_zone_extra_bass(Zone::Main, extra_bass)
     }
 
     /// Active ou désactive le DRC adaptatif
+    pub fn set_adaptive_drc(&self, drc: bool) -> Result<serde_json::Value, YamahaError> {
+        self.set_zone_adaptive_drc(Zone::Main, drc)
+    }
+
+    /// Ancien nom de [`Self::set_adaptive_drc`]
+    #[deprecated(note = "utiliser `set_adaptive_drc`")]
     pub fn set_adaptative_drc(&self, drc: bool) -> Result<serde_json::Value, YamahaError> {
-        let url = self.endpoint(
-            &self.0.ip,
-            &format!(
-                "main/setAdaptativeDrc?enable={}",
-                drc.to_string().to_lowercase()
-            ),
-        );
-        Self::request(self, url)
+        self.set_adaptive_drc(drc)
+    }
+
+    /// Contrôle l'alimentation d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `power` - État d'alimentation voulu
+    pub fn set_zone_power(
+        &self,
+        zone: Zone,
+        power: PowerState,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(&format!("{zone}/setPower"), &[("power", power.as_str())])
+    }
+
+    /// Définit le volume d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `volume` - Niveau de volume, en pas de l'appareil (0 à `max_volume`)
+    pub fn set_zone_volume(
+        &self,
+        zone: Zone,
+        volume: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setVolume"),
+            &[("volume", &volume.to_string())],
+        )
+    }
+
+    /// Monte le volume d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `step` - Nombre de pas, celui de l'appareil si `None`
+    pub fn set_zone_volume_up(
+        &self,
+        zone: Zone,
+        step: Option<u32>,
+    ) -> Result<serde_json::Value, YamahaError> {
+        let step = step.map(|step| step.to_string());
+        let mut params = vec![("volume", "up")];
+        if let Some(step) = &step {
+            params.push(("step", step));
+        }
+        self.request(&format!("{zone}/setVolume"), &params)
+    }
+
+    /// Baisse le volume d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `step` - Nombre de pas, celui de l'appareil si `None`
+    pub fn set_zone_volume_down(
+        &self,
+        zone: Zone,
+        step: Option<u32>,
+    ) -> Result<serde_json::Value, YamahaError> {
+        let step = step.map(|step| step.to_string());
+        let mut params = vec![("volume", "down")];
+        if let Some(step) = &step {
+            params.push(("step", step));
+        }
+        self.request(&format!("{zone}/setVolume"), &params)
+    }
+
+    /// Définit le volume d'une zone en dB
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `db` - Volume en dB (ex: `-40.0`), arrondi au pas de l'appareil
+    pub fn set_zone_volume_db(
+        &self,
+        zone: Zone,
+        db: f64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setActualVolume"),
+            &[("mode", "db"), ("value", &db.to_string())],
+        )
+    }
+
+    /// Active ou désactive le mode muet d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `mute` - `true` pour couper le son
+    pub fn set_zone_mute(&self, zone: Zone, mute: bool) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setMute"),
+            &[("enable", Self::bool_to_string(mute))],
+        )
+    }
+
+    /// Change la source d'entrée d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `input` - Entrée à sélectionner
+    pub fn set_zone_input(
+        &self,
+        zone: Zone,
+        input: Input,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(&format!("{zone}/setInput"), &[("input", input.as_str())])
+    }
+
+    /// Définit le programme sonore d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `program` - Programme sonore à appliquer
+    pub fn set_zone_sound_program(
+        &self,
+        zone: Zone,
+        program: SoundProgram,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setSoundProgram"),
+            &[("program", program.as_str())],
+        )
+    }
+
+    /// Définit le décodeur surround d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `decoder` - Décodeur à utiliser
+    pub fn set_zone_surround_decoder_type(
+        &self,
+        zone: Zone,
+        decoder: SurrDecoderType,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setSurroundDecoderType"),
+            &[("type", decoder.as_str())],
+        )
+    }
+
+    /// Active ou désactive le mode Direct d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `direct` - `true` pour activer le mode
+    pub fn set_zone_direct(
+        &self,
+        zone: Zone,
+        direct: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setDirect"),
+            &[("enable", Self::bool_to_string(direct))],
+        )
+    }
+
+    /// Active ou désactive le mode Pure Direct d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `direct` - `true` pour activer le mode
+    pub fn set_zone_pure_direct(
+        &self,
+        zone: Zone,
+        direct: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setPureDirect"),
+            &[("enable", Self::bool_to_string(direct))],
+        )
+    }
+
+    /// Active ou désactive l'amélioration du son d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `enhance` - `true` pour activer l'amélioration
+    pub fn set_zone_enhancer(
+        &self,
+        zone: Zone,
+        enhance: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setEnhancer"),
+            &[("enable", Self::bool_to_string(enhance))],
+        )
+    }
+
+    /// Règle les graves et les aigus d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `mode` - Mode de réglage
+    /// * `bass` - Niveau des graves
+    /// * `treble` - Niveau des aigus
+    pub fn set_zone_tone_control(
+        &self,
+        zone: Zone,
+        mode: ToneControlMode,
+        bass: i64,
+        treble: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setToneControl"),
+            &[
+                ("mode", mode.as_str()),
+                ("bass", &bass.to_string()),
+                ("treble", &treble.to_string()),
+            ],
+        )
+    }
+
+    /// Règle l'égaliseur d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `mode` - Mode de l'égaliseur (ex: `manual`)
+    /// * `low` - Niveau des graves
+    /// * `mid` - Niveau des médiums
+    /// * `high` - Niveau des aigus
+    pub fn set_zone_equalizer(
+        &self,
+        zone: Zone,
+        mode: &str,
+        low: i64,
+        mid: i64,
+        high: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setEqualizer"),
+            &[
+                ("mode", mode),
+                ("low", &low.to_string()),
+                ("mid", &mid.to_string()),
+                ("high", &high.to_string()),
+            ],
+        )
+    }
+
+    /// Règle le niveau des dialogues d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `level` - Niveau des dialogues
+    pub fn set_zone_dialogue_level(
+        &self,
+        zone: Zone,
+        level: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setDialogueLevel"),
+            &[("value", &level.to_string())],
+        )
+    }
+
+    /// Règle le volume du caisson de basse d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `volume` - Volume du caisson
+    pub fn set_zone_subwoofer_volume(
+        &self,
+        zone: Zone,
+        volume: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setSubwooferVolume"),
+            &[("volume", &volume.to_string())],
+        )
+    }
+
+    /// Règle la balance gauche/droite d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `balance` - Balance, négative vers la gauche
+    pub fn set_zone_balance(
+        &self,
+        zone: Zone,
+        balance: i64,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setBalance"),
+            &[("value", &balance.to_string())],
+        )
+    }
+
+    /// Active ou désactive l'extension des basses d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `extension` - `true` pour activer l'extension
+    pub fn set_zone_bass_extension(
+        &self,
+        zone: Zone,
+        extension: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setBassExtension"),
+            &[("enable", Self::bool_to_string(extension))],
+        )
+    }
+
+    /// Active ou désactive le mode Extra Bass d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `extra_bass` - `true` pour activer le mode
+    pub fn set_zone_extra_bass(
+        &self,
+        zone: Zone,
+        extra_bass: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setExtraBass"),
+            &[("enable", Self::bool_to_string(extra_bass))],
+        )
+    }
+
+    /// Active ou désactive le DRC adaptatif d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `drc` - `true` pour activer le DRC
+    pub fn set_zone_adaptive_drc(
+        &self,
+        zone: Zone,
+        drc: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setAdaptiveDrc"),
+            &[("enable", Self::bool_to_string(drc))],
+        )
+    }
+
+    /// Active ou désactive Clear Voice sur une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `clear_voice` - `true` pour activer Clear Voice
+    pub fn set_zone_clear_voice(
+        &self,
+        zone: Zone,
+        clear_voice: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setClearVoice"),
+            &[("enable", Self::bool_to_string(clear_voice))],
+        )
+    }
+
+    /// Active ou désactive le surround 3D d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `surround_3d` - `true` pour activer le surround 3D
+    pub fn set_zone_surround_3d(
+        &self,
+        zone: Zone,
+        surround_3d: bool,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setSurround3d"),
+            &[("enable", Self::bool_to_string(surround_3d))],
+        )
+    }
+
+    /// Définit le mode de lien (priorité à la vitesse ou à la stabilité) d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `control` - Mode de lien
+    pub fn set_zone_link_control(
+        &self,
+        zone: Zone,
+        control: LinkControl,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(
+            &format!("{zone}/setLinkControl"),
+            &[("control", control.as_str())],
+        )
+    }
+
+    /// Définit la synchronisation audio/vidéo des liens d'une zone
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `delay` - Réglage du délai (ex: `lip_sync`, `audio_sync`)
+    pub fn set_zone_link_audio_delay(
+        &self,
+        zone: Zone,
+        delay: &str,
+    ) -> Result<serde_json::Value, YamahaError> {
+        self.request(&format!("{zone}/setLinkAudioDelay"), &[("delay", delay)])
     }
 }
@@ -0,0 +1,341 @@
+use crate::YamahaAmpBlocking;
+use crate::address::{AmpAddress, DEFAULT_PORT, Host};
+use crate::async_api::YamahaAmpAsync;
+use crate::common_api::{DeviceInfo, YamahaAmpBase};
+use crate::error::YamahaError;
+use crate::limits::VolumePolicy;
+use crate::retry::RetryPolicy;
+use reqwest::Client;
+use reqwest::blocking::Client as BlockingClient;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Délai d'attente par défaut pour une requête complète
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Délai d'attente par défaut pour l'établissement de la connexion
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Paramètres d'abonnement aux événements UDP de l'amplificateur
+///
+/// Lorsqu'ils sont définis, chaque requête envoie les en-têtes `X-AppName` et
+/// `X-AppPort` : l'amplificateur pousse alors ses changements d'état vers ce port UDP
+/// pendant 10 minutes après la dernière requête.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSettings {
+    /// Nom de l'application, au format `Nom/Version`
+    pub app_name: String,
+    /// Port UDP local sur lequel les événements sont attendus
+    pub port: u16,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            app_name: "MusicCast/1.0".into(),
+            port: 41100,
+        }
+    }
+}
+
+/// Constructeur d'amplificateurs [`YamahaAmpAsync`] et [`YamahaAmpBlocking`]
+///
+/// Permet de partager un client HTTP entre plusieurs amplificateurs et de créer une
+/// instance sans requête réseau lorsque les informations du périphérique sont déjà
+/// connues (cache, découverte précédente, ...).
+#[derive(Debug, Clone)]
+pub struct YamahaAmpBuilder {
+    host: Host,
+    port: u16,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    client: Option<Client>,
+    blocking_client: Option<BlockingClient>,
+    retry: RetryPolicy,
+    events: Option<EventSettings>,
+    info: Option<DeviceInfo>,
+    volume_policy: Option<VolumePolicy>,
+    volume_override: bool,
+    resolved: Option<SocketAddr>,
+}
+
+impl YamahaAmpBuilder {
+    /// Crée un constructeur pour l'amplificateur à l'adresse spécifiée
+    ///
+    /// # Arguments
+    /// * `host` - Adresse IPv4, IPv6 ou nom d'hôte (ex: `livingroom-amp.lan`)
+    pub fn new(host: impl Into<Host>) -> Self {
+        Self {
+            host: host.into(),
+            port: DEFAULT_PORT,
+            timeout: None,
+            connect_timeout: None,
+            client: None,
+            blocking_client: None,
+            retry: RetryPolicy::default(),
+            events: None,
+            info: None,
+            volume_policy: None,
+            volume_override: false,
+            resolved: None,
+        }
+    }
+
+    /// Crée un constructeur à partir d'une adresse complète (hôte et port)
+    pub fn from_address(address: AmpAddress) -> Self {
+        Self::new(address.host).port(address.port)
+    }
+
+    /// Port HTTP de l'amplificateur (80 par défaut)
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Délai d'attente maximum pour une requête complète
+    ///
+    /// Ignoré si un client partagé est fourni.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Délai d'attente maximum pour l'établissement de la connexion
+    ///
+    /// Ignoré si un client partagé est fourni.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Client HTTP asynchrone partagé à utiliser
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Client HTTP synchrone partagé à utiliser
+    pub fn blocking_client(mut self, client: BlockingClient) -> Self {
+        self.blocking_client = Some(client);
+        self
+    }
+
+    /// Politique de relance des requêtes, sans relance par défaut
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Active l'abonnement aux événements UDP
+    pub fn events(mut self, events: EventSettings) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Limites de volume à faire respecter par l'amplificateur construit
+    pub fn volume_policy(mut self, policy: VolumePolicy) -> Self {
+        self.volume_policy = Some(policy);
+        self
+    }
+
+    /// Ignore les limites de volume, pour une opération d'administration délibérée
+    pub fn volume_override(mut self, enabled: bool) -> Self {
+        self.volume_override = enabled;
+        self
+    }
+
+    /// Informations déjà connues sur le périphérique, évitant l'appel à `getDeviceInfo`
+    pub fn device_info(mut self, info: DeviceInfo) -> Self {
+        self.info = Some(info);
+        self
+    }
+
+    /// Adresse déjà résolue du nom d'hôte, fixée dans un client dédié au lieu d'une
+    /// nouvelle résolution
+    pub(crate) fn resolved(mut self, addr: SocketAddr) -> Self {
+        self.resolved = Some(addr);
+        self
+    }
+
+    /// Construit l'amplificateur asynchrone sans aucune requête réseau
+    ///
+    /// Si aucune information n'a été fournie via [`Self::device_info`], `info` reste vide.
+    pub fn build(self) -> Result<YamahaAmpAsync, YamahaError> {
+        let client = match self.client.clone() {
+            Some(client) => client,
+            None => self.async_client(self.resolved)?,
+        };
+        Ok(YamahaAmpAsync::from_base(self.into_base(client)))
+    }
+
+    /// Construit l'amplificateur asynchrone, en résolvant le nom d'hôte et en
+    /// interrogeant `getDeviceInfo` si besoin
+    ///
+    /// L'adresse obtenue pour un nom d'hôte est fixée dans un client dédié ; un client
+    /// fourni via [`Self::client`] résout le nom lui-même à chaque connexion.
+    pub async fn connect(self) -> Result<YamahaAmpAsync, YamahaError> {
+        let client = match (&self.client, &self.host) {
+            (Some(client), _) => client.clone(),
+            (None, Host::Name(_)) => match self.resolved {
+                Some(addr) => self.async_client(Some(addr))?,
+                None => self.async_client(Some(self.address().resolve().await?))?,
+            },
+            (None, Host::Ip(_)) => self.async_client(None)?,
+        };
+        let known = self.info.is_some();
+        let mut amp = YamahaAmpAsync::from_base(self.into_base(client));
+        if !known {
+            let info = amp.fetch("system/getDeviceInfo").await?;
+            amp.set_info(info);
+        }
+        Ok(amp)
+    }
+
+    /// Construit l'amplificateur synchrone sans aucune requête réseau
+    ///
+    /// Si aucune information n'a été fournie via [`Self::device_info`], `info` reste vide.
+    pub fn build_blocking(self) -> Result<YamahaAmpBlocking, YamahaError> {
+        let client = match self.blocking_client.clone() {
+            Some(client) => client,
+            None => self.sync_client(self.resolved)?,
+        };
+        Ok(YamahaAmpBlocking::from_base(self.into_base(client)))
+    }
+
+    /// Construit l'amplificateur synchrone, en résolvant le nom d'hôte et en
+    /// interrogeant `getDeviceInfo` si besoin
+    ///
+    /// L'adresse obtenue pour un nom d'hôte est fixée dans un client dédié ; un client
+    /// fourni via [`Self::blocking_client`] résout le nom lui-même à chaque connexion.
+    pub fn connect_blocking(self) -> Result<YamahaAmpBlocking, YamahaError> {
+        let client = match (&self.blocking_client, &self.host) {
+            (Some(client), _) => client.clone(),
+            (None, Host::Name(_)) => match self.resolved {
+                Some(addr) => self.sync_client(Some(addr))?,
+                None => self.sync_client(Some(self.address().resolve_blocking()?))?,
+            },
+            (None, Host::Ip(_)) => self.sync_client(None)?,
+        };
+        let known = self.info.is_some();
+        let mut amp = YamahaAmpBlocking::from_base(self.into_base(client));
+        if !known {
+            let info = amp.fetch("system/getDeviceInfo")?;
+            amp.set_info(info);
+        }
+        Ok(amp)
+    }
+
+    /// Adresse complète de l'amplificateur
+    pub fn address(&self) -> AmpAddress {
+        AmpAddress::new(self.host.clone(), self.port)
+    }
+
+    fn into_base<C>(self, client: C) -> YamahaAmpBase<C> {
+        YamahaAmpBase {
+            address: self.address(),
+            client,
+            info: self.info.unwrap_or_default(),
+            retry: self.retry,
+            events: self.events,
+            volume_policy: self.volume_policy,
+            volume_override: self.volume_override,
+        }
+    }
+
+    fn has_custom_timeouts(&self) -> bool {
+        self.timeout.is_some() || self.connect_timeout.is_some()
+    }
+
+    /// Client asynchrone : partagé par défaut, dédié si des délais spécifiques ou une
+    /// adresse fixée sont demandés
+    ///
+    /// # Arguments
+    /// * `pinned` - Adresse déjà résolue du nom d'hôte, imposée à un client dédié
+    fn async_client(&self, pinned: Option<SocketAddr>) -> Result<Client, YamahaError> {
+        static SHARED: OnceLock<Client> = OnceLock::new();
+
+        let dedicated = self.has_custom_timeouts() || pinned.is_some();
+        if !dedicated && let Some(client) = SHARED.get() {
+            return Ok(client.clone());
+        }
+
+        let mut builder = Client::builder()
+            .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .connect_timeout(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+            .pool_idle_timeout(Some(Duration::from_secs(30)));
+        if let (Host::Name(name), Some(addr)) = (&self.host, pinned) {
+            builder = builder.resolve(name, addr);
+        }
+        let client = builder.build().map_err(YamahaError::Client)?;
+
+        if dedicated {
+            Ok(client)
+        } else {
+            Ok(SHARED.get_or_init(|| client).clone())
+        }
+    }
+
+    /// Client synchrone : partagé par défaut, dédié si des délais spécifiques ou une
+    /// adresse fixée sont demandés
+    ///
+    /// # Arguments
+    /// * `pinned` - Adresse déjà résolue du nom d'hôte, imposée à un client dédié
+    fn sync_client(&self, pinned: Option<SocketAddr>) -> Result<BlockingClient, YamahaError> {
+        static SHARED: OnceLock<BlockingClient> = OnceLock::new();
+
+        let dedicated = self.has_custom_timeouts() || pinned.is_some();
+        if !dedicated && let Some(client) = SHARED.get() {
+            return Ok(client.clone());
+        }
+
+        let mut builder = BlockingClient::builder()
+            .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .connect_timeout(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+            .pool_idle_timeout(Some(Duration::from_secs(30)));
+        if let (Host::Name(name), Some(addr)) = (&self.host, pinned) {
+            builder = builder.resolve(name, addr);
+        }
+        let client = builder.build().map_err(YamahaError::Client)?;
+
+        if dedicated {
+            Ok(client)
+        } else {
+            Ok(SHARED.get_or_init(|| client).clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_api::YamahaApi;
+
+    #[test]
+    fn build_uses_known_device_info_and_port() {
+        let info = DeviceInfo {
+            model: "RX-V6A".into(),
+            device_id: "00A0DE123456".into(),
+            api_version: 2.11,
+        };
+        let amp = YamahaAmpBuilder::new(std::net::Ipv4Addr::new(192, 168, 1, 126))
+            .port(8080)
+            .device_info(info.clone())
+            .retry_policy(RetryPolicy::attempts(2))
+            .build()
+            .unwrap();
+
+        assert_eq!(amp.info, info);
+        assert_eq!(amp.retry, RetryPolicy::attempts(2));
+        assert_eq!(
+            amp.endpoint(&amp.address, "main/getStatus"),
+            "http://192.168.1.126:8080/YamahaExtendedControl/v1/main/getStatus"
+        );
+
+        let amp = YamahaAmpBuilder::new("fd00::126").build().unwrap();
+        assert_eq!(
+            amp.endpoint(&amp.address, "main/getStatus"),
+            "http://[fd00::126]/YamahaExtendedControl/v1/main/getStatus"
+        );
+    }
+}
@@ -1,11 +1,24 @@
-use serde_derive::Deserialize;
-use std::net::Ipv4Addr;
+use crate::address::AmpAddress;
+use crate::builder::EventSettings;
+use crate::error::{RequestContext, YamahaError, YamahaErrorCode};
+use crate::limits::{VolumeGuard, VolumePolicy};
+use crate::retry::RetryPolicy;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
 
 /// Chemin de base pour l'API Yamaha Extended Control
 pub const API_BASE_PATH: &str = "YamahaExtendedControl/v1";
 
+/// Caractères encodés dans les paramètres de requête : tout sauf les caractères non réservés
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
 /// Informations de base sur un périphérique Yamaha
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct DeviceInfo {
     /// Nom du modèle de l'appareil
     #[serde(rename = "model_name")]
@@ -26,40 +39,79 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
 }
 
+/// Décode le corps d'une réponse de l'API et vérifie son `response_code`
+///
+/// # Arguments
+/// * `context` - Contexte de la requête, attaché aux erreurs éventuelles
+/// * `body` - Corps brut de la réponse HTTP
+///
+/// # Returns
+/// * `Result<T, YamahaError>` - Les données désérialisées ou une erreur
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    context: RequestContext,
+    body: &[u8],
+) -> Result<T, YamahaError> {
+    let response = match serde_json::from_slice::<ApiResponse<serde_json::Value>>(body) {
+        Ok(response) => response,
+        Err(source) => return Err(YamahaError::Decode { context, source }),
+    };
+
+    match (response.response_code, response.data) {
+        (0, Some(data)) => {
+            serde_json::from_value(data).map_err(|source| YamahaError::Decode { context, source })
+        }
+        (0, None) => Err(YamahaError::Device {
+            context,
+            code: YamahaErrorCode::InvalidResponse,
+        }),
+        (code, _) => Err(YamahaError::Device {
+            context,
+            code: YamahaErrorCode::from_code(code),
+        }),
+    }
+}
+
 /// Trait définissant les fonctionnalités communes de l'API Yamaha
 pub trait YamahaApi {
     /// Construit l'URL de base pour un endpoint de l'API
     ///
     /// # Arguments
-    /// * `ip` - Adresse IP de l'appareil
+    /// * `address` - Adresse de l'appareil (IPv6 entre crochets, port omis s'il vaut 80)
     /// * `path` - Chemin de l'endpoint
     ///
     /// # Returns
     /// L'URL complète pour l'endpoint
-    fn endpoint(&self, ip: &Ipv4Addr, path: &str) -> String {
-        format!("http://{}/{}/{}", ip, API_BASE_PATH, path)
+    fn endpoint(&self, address: &AmpAddress, path: &str) -> String {
+        format!("http://{}/{}/{}", address.authority(), API_BASE_PATH, path)
     }
 
-    /// Construit une URL complète avec paramètres pour un endpoint de l'API
+    /// Construit le chemin d'un endpoint suivi de ses paramètres encodés
+    ///
+    /// Clés et valeurs sont encodées en pourcentage (UTF-8) : seuls les caractères
+    /// non réservés (`A-Z a-z 0-9 - . _ ~`) sont conservés tels quels.
     ///
     /// # Arguments
-    /// * `ip` - Adresse IP de l'appareil
     /// * `path` - Chemin de l'endpoint
     /// * `params` - Liste des paramètres sous forme de tuples (clé, valeur)
     ///
     /// # Returns
-    /// L'URL complète avec les paramètres
-    fn build_url(&self, ip: &Ipv4Addr, path: &str, params: &[(&str, &str)]) -> String {
-        let base = self.endpoint(ip, path);
+    /// Le chemin, suivi de `?clé=valeur&...` si des paramètres sont fournis
+    fn build_path(&self, path: &str, params: &[(&str, &str)]) -> String {
         if params.is_empty() {
-            return base;
+            return path.to_string();
         }
         let params = params
             .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
+            .map(|(k, v)| {
+                format!(
+                    "{}={}",
+                    utf8_percent_encode(k, QUERY_ENCODE_SET),
+                    utf8_percent_encode(v, QUERY_ENCODE_SET)
+                )
+            })
             .collect::<Vec<_>>()
             .join("&");
-        format!("{}?{}", base, params)
+        format!("{}?{}", path, params)
     }
 
     /// Convertit une valeur booléenne en chaîne de caractères pour l'API
@@ -81,10 +133,141 @@ pub trait YamahaApi {
 /// * `C` - Type du client HTTP (async ou blocking)
 #[derive(Debug)]
 pub struct YamahaAmpBase<C> {
-    /// Adresse IP de l'amplificateur
-    pub ip: Ipv4Addr,
+    /// Adresse de l'amplificateur (hôte et port)
+    pub address: AmpAddress,
     /// Client HTTP pour les requêtes
     pub client: C,
     /// Informations sur le périphérique
     pub info: DeviceInfo,
+    /// Politique de relance des requêtes
+    pub retry: RetryPolicy,
+    /// Abonnement aux événements UDP, si activé
+    pub events: Option<EventSettings>,
+    /// Limites de volume vérifiées avant chaque requête, si définies
+    pub volume_policy: Option<VolumePolicy>,
+    /// Ignore les limites de volume (réservé aux opérations d'administration)
+    pub volume_override: bool,
+}
+
+impl<C> YamahaAmpBase<C> {
+    /// Vérification à effectuer avant d'envoyer une requête, si elle touche au volume
+    /// d'une zone limitée et que les limites ne sont pas ignorées
+    pub(crate) fn volume_guard(&self, path: &str, params: &[(&str, &str)]) -> Option<VolumeGuard> {
+        match (&self.volume_policy, self.volume_override) {
+            (Some(policy), false) => VolumeGuard::new(policy, path, params),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Zone;
+    use std::error::Error;
+
+    fn context() -> RequestContext {
+        RequestContext::new(
+            &AmpAddress::from("192.168.1.126"),
+            "main/setVolume?volume=-40",
+        )
+    }
+
+    #[test]
+    fn context_splits_endpoint_query_and_zone() {
+        let context = context();
+        assert_eq!(context.endpoint, "main/setVolume");
+        assert_eq!(context.query.as_deref(), Some("volume=-40"));
+        assert_eq!(context.zone, Some(Zone::Main));
+
+        let system = RequestContext::new(&AmpAddress::new("fd00::126", 8080), "system/getFeatures");
+        assert_eq!(system.host, "[fd00::126]:8080");
+        assert_eq!(system.zone, None);
+        assert_eq!(system.query, None);
+    }
+
+    #[test]
+    fn parse_response_reports_device_codes() {
+        let err =
+            parse_response::<serde_json::Value>(context(), br#"{"response_code":1}"#).unwrap_err();
+        assert_eq!(err.device_code(), Some(YamahaErrorCode::Initializing));
+        assert!(err.is_retryable());
+        assert_eq!(err.context(), Some(&context()));
+
+        let err =
+            parse_response::<serde_json::Value>(context(), br#"{"response_code":4}"#).unwrap_err();
+        assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidParameter));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn parse_response_reports_decode_failures() {
+        let err = parse_response::<serde_json::Value>(context(), b"<html></html>").unwrap_err();
+        assert!(matches!(err, YamahaError::Decode { .. }));
+        assert!(err.source().is_some());
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn parse_response_strips_response_code() {
+        let data =
+            parse_response::<serde_json::Value>(context(), br#"{"response_code":0,"volume":42}"#)
+                .unwrap();
+        assert_eq!(data, serde_json::json!({ "volume": 42 }));
+    }
+
+    struct Api;
+
+    impl YamahaApi for Api {}
+
+    #[test]
+    fn build_path_without_params_is_unchanged() {
+        assert_eq!(Api.build_path("main/getStatus", &[]), "main/getStatus");
+    }
+
+    #[test]
+    fn build_path_keeps_unreserved_characters() {
+        assert_eq!(
+            Api.build_path(
+                "main/setSoundProgram",
+                &[("program", "sci-fi"), ("volume", "-40.5_~")]
+            ),
+            "main/setSoundProgram?program=sci-fi&volume=-40.5_~"
+        );
+    }
+
+    #[test]
+    fn build_path_encodes_reserved_characters() {
+        assert_eq!(
+            Api.build_path(
+                "system/setNameText",
+                &[("id", "main"), ("text", "Salon & TV")]
+            ),
+            "system/setNameText?id=main&text=Salon%20%26%20TV"
+        );
+        assert_eq!(
+            Api.build_path("system/setNameText", &[("text", "a=b?c#d/e+f%g")]),
+            "system/setNameText?text=a%3Db%3Fc%23d%2Fe%2Bf%25g"
+        );
+    }
+
+    #[test]
+    fn build_path_encodes_unicode_as_utf8() {
+        assert_eq!(
+            Api.build_path("system/setNameText", &[("text", "Séjour")]),
+            "system/setNameText?text=S%C3%A9jour"
+        );
+        assert_eq!(
+            Api.build_path("system/setNameText", &[("text", "リビング 🎵")]),
+            "system/setNameText?text=%E3%83%AA%E3%83%93%E3%83%B3%E3%82%B0%20%F0%9F%8E%B5"
+        );
+    }
+
+    #[test]
+    fn context_keeps_encoded_query() {
+        let path = Api.build_path("system/setNameText", &[("id", "hdmi1"), ("text", "A&B")]);
+        let context = RequestContext::new(&AmpAddress::from("amp.lan"), &path);
+        assert_eq!(context.endpoint, "system/setNameText");
+        assert_eq!(context.query.as_deref(), Some("id=hdmi1&text=A%26B"));
+    }
 }
@@ -1,9 +1,15 @@
-use crate::async_api::YamahaAmpAsync;
-use crate::error::YamahaError;
 use crate::YamahaAmpBlocking;
+use crate::address::{AmpAddress, DEFAULT_PORT, Host};
+use crate::async_api::YamahaAmpAsync;
+use crate::builder::YamahaAmpBuilder;
+use crate::common_api::{API_BASE_PATH, DeviceInfo};
+use crate::error::{RequestContext, YamahaError};
 use futures::stream::{FuturesUnordered, StreamExt};
-use std::net::Ipv4Addr;
-use tokio::time::{timeout, Duration};
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::time::{Duration, timeout};
+
+/// Endpoint utilisé pour identifier un amplificateur
+const DEVICE_INFO_PATH: &str = "system/getDeviceInfo";
 
 /// Configuration pour la découverte des amplificateurs sur le réseau
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +22,8 @@ pub struct DiscoveryConfig {
     pub timeout: Duration,
     /// Nombre maximum de connexions simultanées
     pub max_concurrent: usize,
+    /// Port HTTP à interroger sur chaque adresse
+    pub port: u16,
 }
 
 impl Default for DiscoveryConfig {
@@ -25,6 +33,7 @@ impl Default for DiscoveryConfig {
             mask: 24,
             timeout: Duration::from_millis(500),
             max_concurrent: 50,
+            port: DEFAULT_PORT,
         }
     }
 }
@@ -44,11 +53,16 @@ pub async fn discover_amplifiers(
     let mut found = Vec::new();
 
     let mut tasks = FuturesUnordered::new();
-    let client = build_client(&config)?;
+    let client = build_client(&config, None)?;
 
     for chunk in ips.chunks(config.max_concurrent) {
         for &ip in chunk {
-            tasks.push(try_connect_with_client(ip, client.clone(), config.timeout));
+            tasks.push(try_connect_with_client(
+                AmpAddress::new(ip, config.port),
+                client.clone(),
+                config.timeout,
+                None,
+            ));
         }
 
         while let Some(result) = tasks.next().await {
@@ -65,41 +79,55 @@ pub async fn discover_amplifiers(
 ///
 /// # Arguments
 /// * `config` - Configuration pour le client
+/// * `pinned` - Nom d'hôte et adresse résolue à imposer au client
 ///
 /// # Returns
 /// * `Result<reqwest::Client, YamahaError>` - Client HTTP configuré
-fn build_client(config: &DiscoveryConfig) -> Result<reqwest::Client, YamahaError> {
-    reqwest::Client::builder()
+fn build_client(
+    config: &DiscoveryConfig,
+    pinned: Option<(&str, SocketAddr)>,
+) -> Result<reqwest::Client, YamahaError> {
+    let mut builder = reqwest::Client::builder()
         .timeout(config.timeout)
         .pool_max_idle_per_host(10)
-        .pool_idle_timeout(Some(Duration::from_secs(30)))
-        .build()
-        .map_err(YamahaError::from)
+        .pool_idle_timeout(Some(Duration::from_secs(30)));
+    if let Some((name, addr)) = pinned {
+        builder = builder.resolve(name, addr);
+    }
+    builder.build().map_err(YamahaError::Client)
 }
 
 /// Tente de se connecter à un amplificateur de manière asynchrone
 ///
 /// # Arguments
-/// * `ip` - Adresse IP à tester
+/// * `address` - Adresse à tester
 /// * `client` - Client HTTP à utiliser
 /// * `timeout_duration` - Délai d'attente maximum
+/// * `resolved` - Adresse déjà résolue d'un nom d'hôte, fixée dans l'amplificateur trouvé
 ///
 /// # Returns
 /// * `Result<Option<YamahaAmpAsync>, YamahaError>` - Amplificateur trouvé ou None
 async fn try_connect_with_client(
-    ip: Ipv4Addr,
+    address: AmpAddress,
     client: reqwest::Client,
     timeout_duration: Duration,
+    resolved: Option<SocketAddr>,
 ) -> Result<Option<YamahaAmpAsync>, YamahaError> {
-    let url = format!(
-        "http://{}/YamahaExtendedControl/v1/system/getDeviceInfo",
-        ip
-    );
+    let url = device_info_url(&address);
 
     match timeout(timeout_duration, client.get(&url).send()).await {
         Ok(Ok(resp)) => {
-            let json = resp.json::<serde_json::Value>().await?;
-            Ok(Some(YamahaAmpAsync::from_discovery(ip, json)))
+            let context = RequestContext::new(&address, DEVICE_INFO_PATH);
+            let body = resp
+                .bytes()
+                .await
+                .map_err(|e| YamahaError::from_reqwest(context.clone(), e))?;
+            let info = serde_json::from_slice::<DeviceInfo>(&body)
+                .map_err(|source| YamahaError::Decode { context, source })?;
+            pinned_builder(address, resolved)
+                .device_info(info)
+                .build()
+                .map(Some)
         }
         _ => Ok(None),
     }
@@ -108,23 +136,25 @@ async fn try_connect_with_client(
 /// Tente de se connecter à un amplificateur de manière synchrone
 ///
 /// # Arguments
-/// * `ip` - Adresse IP à tester
+/// * `address` - Adresse à tester
 /// * `client` - Client HTTP synchrone à utiliser
+/// * `resolved` - Adresse déjà résolue d'un nom d'hôte, fixée dans l'amplificateur trouvé
 ///
 /// # Returns
 /// * `Result<Option<YamahaAmpBlocking>, YamahaError>` - Amplificateur trouvé ou None
 fn try_connect_blocking_with_client(
-    ip: Ipv4Addr,
+    address: AmpAddress,
     client: &reqwest::blocking::Client,
+    resolved: Option<SocketAddr>,
 ) -> Result<Option<YamahaAmpBlocking>, YamahaError> {
-    let url = format!(
-        "http://{}/YamahaExtendedControl/v1/system/getDeviceInfo",
-        ip
-    );
+    let url = device_info_url(&address);
 
     match client.get(&url).send() {
-        Ok(resp) => match resp.json::<serde_json::Value>() {
-            Ok(json) => Ok(Some(YamahaAmpBlocking::from_discovery(ip, json))),
+        Ok(resp) => match resp.json::<DeviceInfo>() {
+            Ok(info) => pinned_builder(address, resolved)
+                .device_info(info)
+                .build_blocking()
+                .map(Some),
             Err(_) => Ok(None),
         },
         Err(_) => Ok(None),
@@ -135,13 +165,16 @@ fn try_connect_blocking_with_client(
 ///
 /// # Arguments
 /// * `subnet` - Adresse du sous-réseau
-/// * `mask` - Masque de sous-réseau en notation CIDR
+/// * `mask` - Masque de sous-réseau en notation CIDR, de 1 à 32
 ///
 /// # Returns
-/// Liste des adresses IP à scanner
+/// Liste des adresses IP à scanner, vide si le masque est hors limites
 fn generate_ip_range(subnet: &Ipv4Addr, mask: u8) -> Vec<Ipv4Addr> {
+    if !(1..=32).contains(&mask) {
+        return Vec::new();
+    }
     let start = u32::from(*subnet) & (u32::MAX << (32 - mask));
-    let end = start | (u32::MAX >> mask);
+    let end = start | u32::MAX.checked_shr(mask.into()).unwrap_or(0);
 
     let capacity = (end - start + 1) as usize;
     let mut ips = Vec::with_capacity(capacity);
@@ -169,11 +202,15 @@ pub fn discover_amplifiers_blocking(
 
     let config = config.unwrap_or_default();
     let ips = generate_ip_range(&config.subnet, config.mask);
-    let client = build_blocking_client(&config)?;
+    let client = build_blocking_client(&config, None)?;
 
     Ok(ips
         .into_par_iter()
-        .filter_map(|ip| try_connect_blocking_with_client(ip, &client).ok().flatten())
+        .filter_map(|ip| {
+            try_connect_blocking_with_client(AmpAddress::new(ip, config.port), &client, None)
+                .ok()
+                .flatten()
+        })
         .collect())
 }
 
@@ -181,40 +218,101 @@ pub fn discover_amplifiers_blocking(
 ///
 /// # Arguments
 /// * `config` - Configuration pour le client
+/// * `pinned` - Nom d'hôte et adresse résolue à imposer au client
 ///
 /// # Returns
 /// * `Result<reqwest::blocking::Client, YamahaError>` - Client HTTP synchrone configuré
 fn build_blocking_client(
     config: &DiscoveryConfig,
+    pinned: Option<(&str, SocketAddr)>,
 ) -> Result<reqwest::blocking::Client, YamahaError> {
-    reqwest::blocking::Client::builder()
-        .timeout(config.timeout)
-        .build()
-        .map_err(YamahaError::from)
+    let mut builder = reqwest::blocking::Client::builder().timeout(config.timeout);
+    if let Some((name, addr)) = pinned {
+        builder = builder.resolve(name, addr);
+    }
+    builder.build().map_err(YamahaError::Client)
 }
 
 /// Se connecte directement à un amplificateur de manière asynchrone
 ///
 /// # Arguments
-/// * `ip` - Adresse IP de l'amplificateur
+/// * `address` - Adresse IPv4, IPv6 ou nom d'hôte de l'amplificateur, port éventuel inclus
 ///
 /// # Returns
 /// * `Result<Option<YamahaAmpAsync>, YamahaError>` - Amplificateur trouvé ou None
-pub async fn connect_direct(ip: Ipv4Addr) -> Result<Option<YamahaAmpAsync>, YamahaError> {
+pub async fn connect_direct(
+    address: impl Into<AmpAddress>,
+) -> Result<Option<YamahaAmpAsync>, YamahaError> {
+    let address = address.into();
+    let resolved = address.resolve().await?;
+    let pinned = pinned(&address, resolved);
     let cfg = DiscoveryConfig::default();
-    let config = build_client(&cfg)?;
-    try_connect_with_client(ip, config, cfg.timeout).await
+    let client = build_client(&cfg, pinned)?;
+    let resolved = pinned.map(|(_, addr)| addr);
+    try_connect_with_client(address, client, cfg.timeout, resolved).await
 }
 
 /// Se connecte directement à un amplificateur de manière synchrone
 ///
 /// # Arguments
-/// * `ip` - Adresse IP de l'amplificateur
+/// * `address` - Adresse IPv4, IPv6 ou nom d'hôte de l'amplificateur, port éventuel inclus
 ///
 /// # Returns
 /// * `Result<Option<YamahaAmpBlocking>, YamahaError>` - Amplificateur trouvé ou None
-pub fn connect_direct_blocking(ip: Ipv4Addr) -> Result<Option<YamahaAmpBlocking>, YamahaError> {
+pub fn connect_direct_blocking(
+    address: impl Into<AmpAddress>,
+) -> Result<Option<YamahaAmpBlocking>, YamahaError> {
+    let address = address.into();
+    let resolved = address.resolve_blocking()?;
+    let pinned = pinned(&address, resolved);
     let cfg = DiscoveryConfig::default();
-    let client = build_blocking_client(&cfg)?;
-    try_connect_blocking_with_client(ip, &client).map_err(YamahaError::from)
+    let client = build_blocking_client(&cfg, pinned)?;
+    let resolved = pinned.map(|(_, addr)| addr);
+    try_connect_blocking_with_client(address, &client, resolved)
+}
+
+/// Nom d'hôte de `address` associé à son adresse résolue, `None` pour une adresse IP
+fn pinned(address: &AmpAddress, resolved: SocketAddr) -> Option<(&str, SocketAddr)> {
+    match &address.host {
+        Host::Name(name) => Some((name.as_str(), resolved)),
+        Host::Ip(_) => None,
+    }
+}
+
+/// Constructeur de l'amplificateur trouvé, avec l'adresse déjà résolue s'il y en a une
+fn pinned_builder(address: AmpAddress, resolved: Option<SocketAddr>) -> YamahaAmpBuilder {
+    let builder = YamahaAmpBuilder::from_address(address);
+    match resolved {
+        Some(addr) => builder.resolved(addr),
+        None => builder,
+    }
+}
+
+/// URL de `getDeviceInfo` pour l'adresse donnée
+fn device_info_url(address: &AmpAddress) -> String {
+    format!(
+        "http://{}/{}/{}",
+        address.authority(),
+        API_BASE_PATH,
+        DEVICE_INFO_PATH
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_ranges_follow_the_mask() {
+        let subnet = Ipv4Addr::new(192, 168, 1, 17);
+        let ips = generate_ip_range(&subnet, 24);
+        assert_eq!(ips.len(), 254);
+        assert_eq!(ips[0], Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(
+            generate_ip_range(&subnet, 32),
+            [Ipv4Addr::new(192, 168, 1, 17)]
+        );
+        assert!(generate_ip_range(&subnet, 0).is_empty());
+        assert!(generate_ip_range(&subnet, 33).is_empty());
+    }
 }
@@ -1,39 +1,239 @@
+use crate::address::AmpAddress;
+use crate::model::Zone;
 use reqwest::Error as ReqwestError;
 use serde_json::Error as SerdeError;
+use std::time::Duration;
 use std::{fmt, io};
 
+/// Contexte d'une requête vers un amplificateur, attaché aux erreurs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Adresse de l'amplificateur (`hôte[:port]`)
+    pub host: String,
+    /// Chemin de l'endpoint, sans les paramètres (ex: `main/setVolume`)
+    pub endpoint: String,
+    /// Zone ciblée, déduite du chemin de l'endpoint
+    pub zone: Option<Zone>,
+    /// Paramètres de la requête, tels qu'envoyés
+    pub query: Option<String>,
+}
+
+impl RequestContext {
+    /// Construit le contexte à partir d'un chemin relatif à l'API (`main/setVolume?volume=-40`)
+    pub fn new(address: &AmpAddress, path: &str) -> Self {
+        let (endpoint, query) = match path.split_once('?') {
+            Some((endpoint, query)) => (endpoint, Some(query.to_string())),
+            None => (path, None),
+        };
+        let zone = endpoint
+            .split('/')
+            .next()
+            .and_then(|prefix| prefix.parse::<Zone>().ok());
+
+        Self {
+            host: address.authority(),
+            endpoint: endpoint.to_string(),
+            zone,
+            query,
+        }
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.host, self.endpoint)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(zone) = &self.zone {
+            write!(f, " (zone {})", zone)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum YamahaError {
-    Http(ReqwestError),
+    /// Impossible d'établir la connexion avec l'amplificateur
+    Connect {
+        context: RequestContext,
+        source: ReqwestError,
+    },
+    /// L'amplificateur n'a pas répondu à temps
+    Timeout {
+        context: RequestContext,
+        source: ReqwestError,
+    },
+    /// Erreur HTTP (statut non valide, connexion interrompue, ...)
+    Http {
+        context: RequestContext,
+        source: ReqwestError,
+    },
+    /// La réponse de l'amplificateur n'a pas pu être décodée
+    Decode {
+        context: RequestContext,
+        source: SerdeError,
+    },
+    /// L'amplificateur a renvoyé un `response_code` différent de 0
+    Device {
+        context: RequestContext,
+        code: YamahaErrorCode,
+    },
+    /// Le nom d'hôte de l'amplificateur n'a pas pu être résolu
+    Resolve {
+        host: String,
+        source: Option<io::Error>,
+    },
+    /// La réponse contient des champs inconnus des structures de `json_data` (mode strict)
+    SchemaDrift {
+        type_name: &'static str,
+        fields: Vec<String>,
+    },
+    /// L'opération n'a pas abouti dans le délai imparti (ex: [`crate::Fleet::with_timeout`])
+    Elapsed(Duration),
+    /// La requête dépasse une limite de [`crate::VolumePolicy`] ; elle n'a pas été envoyée
+    VolumeLimit {
+        zone: Zone,
+        kind: VolumeLimitKind,
+        /// Volume demandé, ou hausse demandée pour [`VolumeLimitKind::MaxStep`], en pas
+        requested: i64,
+        limit: i64,
+    },
+    /// Le client HTTP n'a pas pu être construit, avant toute requête
+    Client(ReqwestError),
+    /// JSON invalide hors réponse de l'amplificateur : fichiers de scène ou de sauvegarde,
+    /// valeur passée à [`crate::decode_strict`] (les réponses donnent [`Self::Decode`])
     Json(SerdeError),
+    /// Erreur d'entrée/sortie locale : fichiers de scène, socket UDP des événements
     Io(io::Error),
-    YamahaErrorCode(YamahaErrorCode),
-    InvalidZone,
+    /// Erreur détectée localement, sans requête en échec (nom de scène invalide, version
+    /// de sauvegarde trop récente, zone sans volume en dB, ...)
     Other(String),
-    Deserialization(String),
+}
+
+impl YamahaError {
+    /// Classe une erreur `reqwest` survenue pendant une requête
+    pub(crate) fn from_reqwest(context: RequestContext, source: ReqwestError) -> Self {
+        if source.is_timeout() {
+            YamahaError::Timeout { context, source }
+        } else if source.is_connect() {
+            YamahaError::Connect { context, source }
+        } else {
+            YamahaError::Http { context, source }
+        }
+    }
+
+    /// Contexte de la requête ayant échoué, si l'erreur en provient
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            YamahaError::Connect { context, .. }
+            | YamahaError::Timeout { context, .. }
+            | YamahaError::Http { context, .. }
+            | YamahaError::Decode { context, .. }
+            | YamahaError::Device { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Code d'erreur renvoyé par l'amplificateur, le cas échéant
+    pub fn device_code(&self) -> Option<YamahaErrorCode> {
+        match self {
+            YamahaError::Device { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Indique si la même requête a des chances d'aboutir en la relançant
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            YamahaError::Connect { .. } | YamahaError::Timeout { .. } => true,
+            YamahaError::Http { source, .. } => source
+                .status()
+                .is_none_or(|status| status.is_server_error()),
+            YamahaError::Device { code, .. } => code.is_retryable(),
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for YamahaError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            YamahaError::Http(e) => write!(f, "HTTP error: {}", e),
+            YamahaError::Connect { context, source } => {
+                write!(f, "Connection failed to {}: {}", context, source)
+            }
+            YamahaError::Timeout { context, .. } => write!(f, "Timeout on {}", context),
+            YamahaError::Http { context, source } => {
+                write!(f, "HTTP error on {}: {}", context, source)
+            }
+            YamahaError::Decode { context, source } => {
+                write!(f, "Invalid response from {}: {}", context, source)
+            }
+            YamahaError::Device { context, code } => {
+                write!(
+                    f,
+                    "Yamaha returned error code {} on {}",
+                    code.message(),
+                    context
+                )
+            }
+            YamahaError::Resolve { host, source } => match source {
+                Some(e) => write!(f, "Cannot resolve {}: {}", host, e),
+                None => write!(f, "Cannot resolve {}: no address found", host),
+            },
+            YamahaError::SchemaDrift { type_name, fields } => {
+                write!(f, "Unknown fields in {}: {}", type_name, fields.join(", "))
+            }
+            YamahaError::Elapsed(timeout) => {
+                write!(f, "No answer within {} ms", timeout.as_millis())
+            }
+            YamahaError::VolumeLimit {
+                zone,
+                kind: VolumeLimitKind::MaxVolume,
+                requested,
+                limit,
+            } => write!(
+                f,
+                "Volume {} on zone {} is above the limit of {}",
+                requested, zone, limit
+            ),
+            YamahaError::VolumeLimit {
+                zone,
+                kind: VolumeLimitKind::MaxStep,
+                requested,
+                limit,
+            } => write!(
+                f,
+                "Volume increase of {} on zone {} is above the limit of {} per call",
+                requested, zone, limit
+            ),
+            YamahaError::Client(e) => write!(f, "HTTP client error: {}", e),
             YamahaError::Json(e) => write!(f, "JSON error: {}", e),
             YamahaError::Io(e) => write!(f, "IO error: {}", e),
-            YamahaError::YamahaErrorCode(code) => {
-                write!(f, "Yamaha returned error code: {}", code.message())
-            }
-            YamahaError::InvalidZone => write!(f, "Invalid zone"),
             YamahaError::Other(msg) => write!(f, "{}", msg),
-            YamahaError::Deserialization(msg) => write!(f, "{}", msg),
         }
     }
 }
 
-impl std::error::Error for YamahaError {}
-
-impl From<ReqwestError> for YamahaError {
-    fn from(e: ReqwestError) -> Self {
-        YamahaError::Http(e)
+impl std::error::Error for YamahaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            YamahaError::Connect { source, .. }
+            | YamahaError::Timeout { source, .. }
+            | YamahaError::Http { source, .. } => Some(source),
+            YamahaError::Decode { source, .. } => Some(source),
+            YamahaError::Resolve { source, .. } => source
+                .as_ref()
+                .map(|e| e as &(dyn std::error::Error + 'static)),
+            YamahaError::Client(e) => Some(e),
+            YamahaError::Json(e) => Some(e),
+            YamahaError::Io(e) => Some(e),
+            YamahaError::Device { .. }
+            | YamahaError::SchemaDrift { .. }
+            | YamahaError::Elapsed(_)
+            | YamahaError::VolumeLimit { .. }
+            | YamahaError::Other(_) => None,
+        }
     }
 }
 
@@ -49,6 +249,42 @@ impl From<io::Error> for YamahaError {
     }
 }
 
+/// Limite de volume dépassée par une requête
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeLimitKind {
+    /// Volume maximal de la zone
+    MaxVolume,
+    /// Hausse maximale en un seul appel
+    MaxStep,
+}
+
+/// Erreur renvoyée lorsqu'une chaîne ne correspond à aucune valeur connue d'une énumération
+/// ou à aucune adresse valide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    /// Nom de l'énumération ciblée
+    pub kind: &'static str,
+    /// Valeur qui n'a pas pu être convertie
+    pub value: String,
+}
+
+impl ParseEnumError {
+    pub(crate) fn new(kind: &'static str, value: &str) -> Self {
+        Self {
+            kind,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {} value: {}", self.kind, self.value)
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum YamahaErrorCode {
     Ok,
@@ -114,6 +350,17 @@ impl YamahaErrorCode {
         }
     }
 
+    /// Indique si l'appareil est dans un état transitoire (démarrage, liaison en cours, ...)
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Initializing
+                | Self::Timeout
+                | Self::DistributionLinking
+                | Self::DistributionUnlinking
+        )
+    }
+
     pub fn message(&self) -> String {
         match self {
             YamahaErrorCode::Ok => "0 Successful request".into(),
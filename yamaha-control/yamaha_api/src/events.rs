@@ -0,0 +1,117 @@
+use crate::error::YamahaError;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+
+/// Taille maximale d'un événement UDP envoyé par un amplificateur
+const MAX_EVENT_SIZE: usize = 8192;
+
+/// Événement UDP reçu d'un amplificateur
+///
+/// Le contenu est un objet JSON partiel, par exemple
+/// `{"main":{"volume":62,"status_updated":true},"device_id":"00A0DE123456"}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmpEvent {
+    /// Adresse de l'amplificateur ayant émis l'événement
+    pub source: SocketAddr,
+    /// Identifiant de l'appareil, s'il est présent dans l'événement
+    pub device_id: Option<String>,
+    /// Contenu brut de l'événement
+    pub payload: serde_json::Value,
+}
+
+/// Écoute les événements UDP poussés par les amplificateurs
+///
+/// Les amplificateurs n'envoient d'événements qu'aux clients s'étant abonnés via
+/// [`crate::EventSettings`] ; le port d'écoute doit correspondre à `EventSettings::port`.
+#[derive(Debug)]
+pub struct EventListener {
+    socket: UdpSocket,
+}
+
+impl EventListener {
+    /// Ouvre le port UDP spécifié sur toutes les interfaces, en IPv6 et en IPv4
+    ///
+    /// Le socket écoute en double pile sur `[::]` afin de recevoir les événements des
+    /// amplificateurs des deux familles ; sans IPv6 sur la machine, il se limite à `0.0.0.0`.
+    ///
+    /// # Arguments
+    /// * `port` - Port UDP d'écoute (0 pour un port choisi par le système)
+    pub async fn bind(port: u16) -> Result<Self, YamahaError> {
+        let socket = match bind_dual_stack(port) {
+            Ok(socket) => UdpSocket::from_std(socket)?,
+            Err(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port)).await?,
+        };
+        Ok(Self { socket })
+    }
+
+    /// Port UDP effectivement utilisé
+    pub fn port(&self) -> Result<u16, YamahaError> {
+        Ok(self.socket.local_addr()?.port())
+    }
+
+    /// Attend le prochain événement valide
+    ///
+    /// Les datagrammes qui ne sont pas des objets JSON sont ignorés.
+    pub async fn recv(&self) -> Result<AmpEvent, YamahaError> {
+        let mut buf = vec![0u8; MAX_EVENT_SIZE];
+        loop {
+            let (len, mut source) = self.socket.recv_from(&mut buf).await?;
+            // Les sources IPv4 arrivent sous la forme `::ffff:a.b.c.d` sur un socket double pile
+            source.set_ip(source.ip().to_canonical());
+            let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&buf[..len]) else {
+                continue;
+            };
+            if !payload.is_object() {
+                continue;
+            }
+            let device_id = payload
+                .get("device_id")
+                .and_then(|id| id.as_str())
+                .map(str::to_string);
+            return Ok(AmpEvent {
+                source,
+                device_id,
+                payload,
+            });
+        }
+    }
+}
+
+/// Ouvre un socket UDP IPv6 acceptant aussi l'IPv4, quel que soit le réglage du système
+fn bind_dual_stack(port: u16) -> std::io::Result<std::net::UdpSocket> {
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_only_v6(false)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::from((Ipv6Addr::UNSPECIFIED, port)).into())?;
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::IpAddr;
+
+    async fn send_from(from: IpAddr) -> AmpEvent {
+        let listener = EventListener::bind(0).await.unwrap();
+        let sender = UdpSocket::bind((from, 0)).await.unwrap();
+        let payload = br#"{"main":{"volume":62},"device_id":"00A0DE123456"}"#;
+        sender
+            .send_to(payload, (from, listener.port().unwrap()))
+            .await
+            .unwrap();
+        let event = listener.recv().await.unwrap();
+        assert_eq!(event.source.port(), sender.local_addr().unwrap().port());
+        event
+    }
+
+    #[tokio::test]
+    async fn events_are_received_over_ipv4_and_ipv6() {
+        let event = send_from(Ipv6Addr::LOCALHOST.into()).await;
+        assert_eq!(event.source.ip(), IpAddr::from(Ipv6Addr::LOCALHOST));
+        assert_eq!(event.device_id.as_deref(), Some("00A0DE123456"));
+
+        let event = send_from(Ipv4Addr::LOCALHOST.into()).await;
+        assert_eq!(event.source.ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+    }
+}
@@ -0,0 +1,139 @@
+use crate::async_api::YamahaAmpAsync;
+use crate::error::YamahaError;
+use crate::json_data::GetStatus;
+use crate::model::{PowerState, Zone};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Intervalle minimal entre deux changements de volume d'un fondu
+pub const MIN_FADE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Écart toléré entre le volume relu et le dernier volume envoyé, en dB, certains
+/// appareils arrondissant au dB près
+const EXTERNAL_CHANGE_DB: f64 = 0.5;
+
+/// Progression du volume au cours d'un fondu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeCurve {
+    /// Le volume en dB varie uniformément
+    #[default]
+    Linear,
+    /// L'amplitude varie uniformément : un fondu sortant reste fort longtemps puis chute,
+    /// un fondu entrant monte vite puis s'attarde près de la cible
+    Logarithmic,
+}
+
+impl FadeCurve {
+    /// Volume en dB à la fraction `progress` (de 0 à 1) du fondu
+    pub fn at(self, start_db: f64, target_db: f64, progress: f64) -> f64 {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => start_db + (target_db - start_db) * progress,
+            FadeCurve::Logarithmic => {
+                let (start, target) = (amplitude(start_db), amplitude(target_db));
+                20.0 * (start + (target - start) * progress).log10()
+            }
+        }
+    }
+}
+
+fn amplitude(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+/// Fin d'un fondu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FadeOutcome {
+    /// Le volume cible est atteint
+    Completed,
+    /// Le volume a été changé par ailleurs ou la zone mise en veille ; le fondu s'est arrêté
+    Interrupted {
+        /// Volume relu sur l'appareil, en dB
+        volume_db: f64,
+    },
+}
+
+impl YamahaAmpAsync {
+    /// Amène progressivement le volume d'une zone à `target_db`, par pas de 0.5 dB
+    ///
+    /// Le statut de la zone est relu avant chaque pas : si le volume ne correspond plus au
+    /// dernier volume envoyé ou si la zone n'est plus allumée, le fondu s'arrête sans
+    /// toucher au volume. Abandonner le futur (ex: `tokio::select!`, `JoinHandle::abort`)
+    /// annule le fondu, le volume restant là où il en était.
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée, qui doit prendre en charge le volume en dB
+    /// * `target_db` - Volume final en dB (ex: `-35.0`)
+    /// * `duration` - Durée totale du fondu
+    /// * `curve` - Progression du volume
+    ///
+    /// # Returns
+    /// * `Result<FadeOutcome, YamahaError>` - Fondu terminé ou interrompu
+    pub async fn fade_volume(
+        &self,
+        zone: Zone,
+        target_db: f64,
+        duration: Duration,
+        curve: FadeCurve,
+    ) -> Result<FadeOutcome, YamahaError> {
+        let (start, _) = self.fade_status(&zone).await?;
+        let target = round_db(target_db);
+        let steps = ((target - start).abs() / 0.5).round() as u32;
+        if steps == 0 {
+            return Ok(FadeOutcome::Completed);
+        }
+        let ticks = ((duration.as_millis() / MIN_FADE_INTERVAL.as_millis()) as u32).clamp(1, steps);
+        let interval = duration / ticks;
+
+        let started = Instant::now();
+        let mut last = start;
+        for tick in 1..=ticks {
+            tokio::time::sleep_until(started + interval * tick).await;
+            let (volume, power) = self.fade_status(&zone).await?;
+            if power != PowerState::On || (volume - last).abs() > EXTERNAL_CHANGE_DB {
+                return Ok(FadeOutcome::Interrupted { volume_db: volume });
+            }
+            let db = round_db(curve.at(start, target, f64::from(tick) / f64::from(ticks)));
+            if db != last {
+                self.set_zone_volume_db(zone.clone(), db).await?;
+                last = db;
+            }
+        }
+        Ok(FadeOutcome::Completed)
+    }
+
+    /// Volume en dB et alimentation de la zone
+    async fn fade_status(&self, zone: &Zone) -> Result<(f64, PowerState), YamahaError> {
+        let status: GetStatus = self.fetch(&format!("{zone}/getStatus")).await?;
+        let volume = status
+            .actual_volume
+            .map(|actual| actual.value)
+            .ok_or_else(|| {
+                YamahaError::Other(format!("Zone {zone} does not report its volume in dB"))
+            })?;
+        Ok((volume, status.power))
+    }
+}
+
+/// Arrondit au pas de 0.5 dB des appareils
+fn round_db(db: f64) -> f64 {
+    (db * 2.0).round() / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_start_and_end_on_the_given_volumes() {
+        for curve in [FadeCurve::Linear, FadeCurve::Logarithmic] {
+            assert!((curve.at(-60.0, -20.0, 0.0) + 60.0).abs() < 1e-9);
+            assert!((curve.at(-60.0, -20.0, 1.0) + 20.0).abs() < 1e-9);
+        }
+        assert_eq!(FadeCurve::Linear.at(-60.0, -20.0, 0.5), -40.0);
+        let log_in = FadeCurve::Logarithmic.at(-60.0, -20.0, 0.5);
+        let log_out = FadeCurve::Logarithmic.at(-20.0, -60.0, 0.5);
+        assert_eq!(round_db(log_in), -26.0);
+        assert_eq!(round_db(log_out), -26.0);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::async_api::YamahaAmpAsync;
+use crate::error::YamahaError;
+use crate::json_data::GetStatus;
+use crate::model::{PowerState, Zone};
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+
+/// Nombre d'amplificateurs contactés simultanément par défaut
+pub const DEFAULT_FLEET_CONCURRENCY: usize = 8;
+
+/// Ensemble d'amplificateurs nommés, commandés ensemble
+///
+/// Les commandes sont envoyées en parallèle, au plus [`Fleet::with_concurrency`] à la
+/// fois, et chaque amplificateur obtient son propre résultat : l'échec de l'un
+/// n'interrompt pas les autres.
+#[derive(Debug)]
+pub struct Fleet {
+    members: Vec<(String, YamahaAmpAsync)>,
+    concurrency: usize,
+    timeout: Option<Duration>,
+}
+
+/// Résultat d'une commande pour un membre d'un [`Fleet`]
+#[derive(Debug)]
+pub struct FleetResult<T, E = YamahaError> {
+    /// Nom de l'amplificateur dans le groupe
+    pub name: String,
+    pub result: Result<T, E>,
+}
+
+impl Default for Fleet {
+    fn default() -> Self {
+        Self {
+            members: Vec::new(),
+            concurrency: DEFAULT_FLEET_CONCURRENCY,
+            timeout: None,
+        }
+    }
+}
+
+impl Fleet {
+    /// Crée un groupe vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un amplificateur au groupe sous le nom donné
+    pub fn with_member(mut self, name: impl Into<String>, amp: YamahaAmpAsync) -> Self {
+        self.push(name, amp);
+        self
+    }
+
+    pub fn push(&mut self, name: impl Into<String>, amp: YamahaAmpAsync) {
+        self.members.push((name.into(), amp));
+    }
+
+    /// Limite le nombre d'amplificateurs contactés simultanément (au moins 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Délai accordé à chaque amplificateur pour une commande complète, relances comprises
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Noms des amplificateurs, dans l'ordre du groupe
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&YamahaAmpAsync> {
+        self.members
+            .iter()
+            .find(|(member, _)| member == name)
+            .map(|(_, amp)| amp)
+    }
+
+    /// Exécute une commande sur chaque amplificateur du groupe
+    ///
+    /// # Arguments
+    /// * `command` - Appelée avec le nom et l'amplificateur de chaque membre
+    ///
+    /// # Returns
+    /// * `Vec<FleetResult<T, E>>` - Un résultat par amplificateur, dans l'ordre du groupe ;
+    ///   une commande hors délai se termine par [`YamahaError::Elapsed`]
+    pub async fn run<'a, F, Fut, T, E>(&'a self, command: F) -> Vec<FleetResult<T, E>>
+    where
+        F: Fn(&'a str, &'a YamahaAmpAsync) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<YamahaError>,
+    {
+        let timeout = self.timeout;
+        stream::iter(&self.members)
+            .map(|(name, amp)| {
+                let future = command(name, amp);
+                async move {
+                    let result = match timeout {
+                        Some(timeout) => tokio::time::timeout(timeout, future)
+                            .await
+                            .unwrap_or_else(|_| Err(YamahaError::Elapsed(timeout).into())),
+                        None => future.await,
+                    };
+                    FleetResult {
+                        name: name.clone(),
+                        result,
+                    }
+                }
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+
+    /// Change l'alimentation d'une zone sur chaque amplificateur
+    pub async fn set_power(
+        &self,
+        zone: &Zone,
+        power: PowerState,
+    ) -> Vec<FleetResult<serde_json::Value>> {
+        self.run(|_, amp| {
+            let (zone, power) = (zone.clone(), power.clone());
+            async move { amp.set_zone_power(zone, power).await }
+        })
+        .await
+    }
+
+    /// Règle le volume d'une zone en dB sur chaque amplificateur
+    ///
+    /// # Arguments
+    /// * `zone` - Zone ciblée
+    /// * `db` - Volume en dB (ex: `-40.0`), arrondi au pas de l'appareil
+    pub async fn set_volume_db(&self, zone: &Zone, db: f64) -> Vec<FleetResult<serde_json::Value>> {
+        self.run(|_, amp| {
+            let zone = zone.clone();
+            async move { amp.set_zone_volume_db(zone, db).await }
+        })
+        .await
+    }
+
+    /// Récupère le statut d'une zone sur chaque amplificateur
+    pub async fn status(&self, zone: &Zone) -> Vec<FleetResult<GetStatus>> {
+        self.run(|_, amp| async move { amp.fetch(&format!("{zone}/getStatus")).await })
+            .await
+    }
+}
@@ -1,6 +1,15 @@
+use crate::model::{
+    Input, LinkControl, PowerState, SoundProgram, SurrDecoderType, ToneControlMode,
+};
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 
+/// Champs renvoyés par l'appareil mais inconnus des structures de ce module
+///
+/// Chaque structure conserve ces champs dans `extra` au lieu d'échouer, ce qui permet
+/// de décoder les réponses de tous les modèles. [`SchemaDrift`](crate::SchemaDrift) permet de les lister.
+pub type Extra = serde_json::Map<String, serde_json::Value>;
+
 // GetDeviceInfo
 
 /// category_code
@@ -12,25 +21,25 @@ use serde_derive::Serialize;
 /// 5 : Mini System
 /// 6 : Desktop Audio 1
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetDeviceInfo {
     #[serde(rename = "analytics_info")]
-    pub analytics_info: AnalyticsInfo,
+    pub analytics_info: Option<AnalyticsInfo>,
     #[serde(rename = "api_version")]
     pub api_version: f64,
     #[serde(rename = "category_code")]
-    pub category_code: i64,
+    pub category_code: Option<i64>,
     pub destination: String,
     #[serde(rename = "device_id")]
     pub device_id: String,
     #[serde(rename = "model_name")]
     pub model_name: String,
     #[serde(rename = "net_module_num")]
-    pub net_module_num: i64,
+    pub net_module_num: Option<i64>,
     #[serde(rename = "netmodule_checksum")]
     pub netmodule_checksum: String,
     #[serde(rename = "netmodule_generation")]
-    pub netmodule_generation: i64,
+    pub netmodule_generation: Option<i64>,
     #[serde(rename = "netmodule_version")]
     pub netmodule_version: String,
     #[serde(rename = "operation_mode")]
@@ -38,59 +47,69 @@ pub struct GetDeviceInfo {
     #[serde(rename = "response_code")]
     pub response_code: i64,
     #[serde(rename = "serial_number")]
-    pub serial_number: String,
+    pub serial_number: Option<String>,
     #[serde(rename = "system_id")]
     pub system_id: String,
     #[serde(rename = "system_version")]
     pub system_version: f64,
     #[serde(rename = "update_data_type")]
-    pub update_data_type: i64,
+    pub update_data_type: Option<i64>,
     #[serde(rename = "update_error_code")]
     pub update_error_code: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct AnalyticsInfo {
     pub uuid: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetFeatures
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetFeatures {
-    pub ccs: Ccs,
+    pub ccs: Option<Ccs>,
     pub distribution: Distribution,
     pub netusb: Netusb,
     #[serde(rename = "response_code")]
     pub response_code: i64,
     pub system: System,
-    pub tuner: Tuner,
+    pub tuner: Option<Tuner>,
     pub zone: Vec<GetFeaturesZone>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Ccs {
     pub supported: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Distribution {
     #[serde(rename = "client_max")]
     pub client_max: i64,
     #[serde(rename = "compatible_client")]
     pub compatible_client: Vec<i64>,
     #[serde(rename = "mc_surround")]
-    pub mc_surround: McSurround,
+    pub mc_surround: Option<McSurround>,
     #[serde(rename = "server_zone_list")]
     pub server_zone_list: Vec<String>,
     pub version: f64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct McSurround {
     #[serde(rename = "func_list")]
     pub func_list: Vec<String>,
@@ -99,10 +118,12 @@ pub struct McSurround {
     #[serde(rename = "slave_role")]
     pub slave_role: SlaveRole,
     pub version: f64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct MasterRole {
     #[serde(rename = "stereo_pair")]
     pub stereo_pair: bool,
@@ -110,10 +131,12 @@ pub struct MasterRole {
     pub subwoofer_pair: bool,
     #[serde(rename = "surround_pair")]
     pub surround_pair: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct SlaveRole {
     #[serde(rename = "subwoofer_pair")]
     pub subwoofer_pair: bool,
@@ -121,89 +144,121 @@ pub struct SlaveRole {
     pub surround_pair_l_or_r: bool,
     #[serde(rename = "surround_pair_lr")]
     pub surround_pair_lr: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Netusb {
     #[serde(rename = "func_list")]
     pub func_list: Vec<String>,
     #[serde(rename = "mc_playlist")]
-    pub mc_playlist: McPlaylist,
+    pub mc_playlist: Option<McPlaylist>,
     #[serde(rename = "net_radio_type")]
     pub net_radio_type: String,
     #[serde(rename = "play_queue")]
     pub play_queue: PlayQueue,
     pub preset: Preset,
-    pub qobuz: Qobuz,
+    pub qobuz: Option<Qobuz>,
     #[serde(rename = "recent_info")]
     pub recent_info: RecentInfo,
-    pub tidal: Tidal,
+    pub tidal: Option<Tidal>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct McPlaylist {
     pub num: i64,
     pub size: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct PlayQueue {
     pub size: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Preset {
     pub num: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Qobuz {
     #[serde(rename = "login_type")]
     pub login_type: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct RecentInfo {
     pub num: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Tidal {
     pub mode: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct System {
-    pub bluetooth: Bluetooth,
+    pub bluetooth: Option<Bluetooth>,
     #[serde(rename = "func_list")]
     pub func_list: Vec<String>,
     #[serde(rename = "input_list")]
     pub input_list: Vec<InputList>,
+    #[serde(rename = "speaker_settings")]
+    pub speaker_settings: Option<SpeakerSettings>,
     #[serde(rename = "web_control_url")]
-    pub web_control_url: String,
+    pub web_control_url: Option<String>,
     #[serde(rename = "zone_num")]
     pub zone_num: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeakerSettings {
+    pub supported: bool,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Bluetooth {
     #[serde(rename = "tx_connectivity_type_max")]
     pub tx_connectivity_type_max: i64,
     #[serde(rename = "update_cancelable")]
     pub update_cancelable: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct InputList {
     #[serde(rename = "account_enable")]
     pub account_enable: bool,
@@ -214,45 +269,56 @@ pub struct InputList {
     pub play_info_type: String,
     #[serde(rename = "rename_enable")]
     pub rename_enable: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Tuner {
     #[serde(rename = "func_list")]
     pub func_list: Vec<String>,
     pub preset: Preset2,
     #[serde(rename = "range_step")]
     pub range_step: Vec<RangeStep>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Preset2 {
     pub num: i64,
     #[serde(rename = "type")]
     pub type_field: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct RangeStep {
     pub id: String,
     pub max: i64,
     pub min: i64,
     pub step: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetFeaturesZone {
     #[serde(rename = "actual_volume_mode_list")]
-    pub actual_volume_mode_list: Vec<String>,
+    pub actual_volume_mode_list: Option<Vec<String>>,
+    #[serde(rename = "audio_select_list")]
+    pub audio_select_list: Option<Vec<String>>,
     #[serde(rename = "ccs_supported")]
-    #[serde(default)]
     pub ccs_supported: Vec<String>,
     #[serde(rename = "cursor_list")]
     pub cursor_list: Option<Vec<String>>,
+    #[serde(rename = "equalizer_mode_list")]
+    pub equalizer_mode_list: Option<Vec<String>>,
     #[serde(rename = "func_list")]
     pub func_list: Vec<String>,
     pub id: String,
@@ -269,29 +335,31 @@ pub struct GetFeaturesZone {
     #[serde(rename = "scene_num")]
     pub scene_num: Option<i64>,
     #[serde(rename = "sound_program_list")]
-    #[serde(default)]
     pub sound_program_list: Vec<String>,
     #[serde(rename = "surr_decoder_type_list")]
     pub surr_decoder_type_list: Option<Vec<String>>,
     #[serde(rename = "tone_control_mode_list")]
-    #[serde(default)]
     pub tone_control_mode_list: Vec<String>,
     #[serde(rename = "zone_b")]
     pub zone_b: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct RangeStep2 {
     pub id: String,
     pub max: f64,
     pub min: f64,
     pub step: f64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetNetworkStatus
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetNetworkStatus {
     #[serde(rename = "airplay_pin")]
     pub airplay_pin: String,
@@ -320,17 +388,21 @@ pub struct GetNetworkStatus {
     pub subnet_mask: String,
     #[serde(rename = "wireless_lan")]
     pub wireless_lan: WirelessLan,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Ipv6 {
     pub address: String,
     pub enable: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct MacAddress {
     #[serde(rename = "wired_lan")]
     pub wired_lan: String,
@@ -338,10 +410,12 @@ pub struct MacAddress {
     pub wireless_direct: String,
     #[serde(rename = "wireless_lan")]
     pub wireless_lan: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct MusiccastNetwork {
     pub ch: i64,
     #[serde(rename = "child_num")]
@@ -354,18 +428,22 @@ pub struct MusiccastNetwork {
     pub ready: bool,
     #[serde(rename = "wlan1_ch")]
     pub wlan1_ch: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Dfs {
     pub option: bool,
     #[serde(rename = "radar_ch")]
     pub radar_ch: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct WirelessLan {
     pub ch: i64,
     pub enable: bool,
@@ -374,11 +452,13 @@ pub struct WirelessLan {
     pub strength: i64,
     #[serde(rename = "type")]
     pub type_field: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetBluetoothInfo
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetBluetoothInfo {
     #[serde(rename = "bluetooth_device")]
     pub bluetooth_device: BluetoothDevice,
@@ -390,41 +470,49 @@ pub struct GetBluetoothInfo {
     pub bluetooth_tx_setting: bool,
     #[serde(rename = "response_code")]
     pub response_code: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct BluetoothDevice {
     pub address: String,
     pub connected: bool,
     pub name: String,
     #[serde(rename = "type")]
     pub type_field: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetBluetoothDeviceList
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetBluetoothDeviceList {
     #[serde(rename = "response_code")]
     pub response_code: i64,
     pub updating: bool,
     #[serde(rename = "device_list")]
     pub device_list: Vec<DeviceList>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct DeviceList {
     pub name: String,
     #[serde(rename = "type")]
     pub type_field: String,
     pub address: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetFuncStatus
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetFuncStatus {
     #[serde(rename = "hdmi_out_1")]
     pub hdmi_out_1: bool,
@@ -435,11 +523,13 @@ pub struct GetFuncStatus {
     pub response_code: i64,
     #[serde(rename = "zone_b_volume_sync")]
     pub zone_b_volume_sync: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // getNameText without zone_id
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetNameTextWithoutID {
     #[serde(rename = "input_list")]
     pub input_list: Vec<InputList>,
@@ -449,124 +539,247 @@ pub struct GetNameTextWithoutID {
     pub sound_program_list: Vec<SoundProgramList>,
     #[serde(rename = "zone_list")]
     pub zone_list: Vec<ZoneList>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct NameTextInputList {
     pub id: String,
     pub text: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct SoundProgramList {
     pub id: String,
     pub text: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ZoneList {
     pub id: String,
     pub text: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetNameText?id=main with id
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetNameTextWithID {
     pub id: String,
     #[serde(rename = "response_code")]
     pub response_code: i64,
     pub text: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetStatus
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetStatus {
     #[serde(rename = "actual_volume")]
-    pub actual_volume: ActualVolume,
+    pub actual_volume: Option<ActualVolume>,
     #[serde(rename = "adaptive_drc")]
-    pub adaptive_drc: bool,
+    pub adaptive_drc: Option<bool>,
+    pub balance: Option<i64>,
+    #[serde(rename = "bass_extension")]
+    pub bass_extension: Option<bool>,
+    #[serde(rename = "clear_voice")]
+    pub clear_voice: Option<bool>,
     #[serde(rename = "contents_display")]
-    pub contents_display: bool,
+    pub contents_display: Option<bool>,
     #[serde(rename = "dialogue_level")]
-    pub dialogue_level: i64,
+    pub dialogue_level: Option<i64>,
+    pub direct: Option<bool>,
     #[serde(rename = "disable_flags")]
     pub disable_flags: i64,
     #[serde(rename = "distribution_enable")]
     pub distribution_enable: bool,
-    pub enhancer: bool,
+    #[serde(rename = "dts_dialogue_control")]
+    pub dts_dialogue_control: Option<i64>,
+    pub enhancer: Option<bool>,
+    pub equalizer: Option<Equalizer>,
     #[serde(rename = "extra_bass")]
-    pub extra_bass: bool,
-    pub input: String,
+    pub extra_bass: Option<bool>,
+    pub input: Input,
     #[serde(rename = "input_text")]
-    pub input_text: String,
+    pub input_text: Option<String>,
     #[serde(rename = "link_audio_delay")]
-    pub link_audio_delay: String,
+    pub link_audio_delay: Option<String>,
     #[serde(rename = "link_control")]
-    pub link_control: String,
+    pub link_control: Option<LinkControl>,
     #[serde(rename = "max_volume")]
     pub max_volume: i64,
     pub mute: bool,
-    pub power: String,
+    #[serde(rename = "party_enable")]
+    pub party_enable: Option<bool>,
+    pub power: PowerState,
     #[serde(rename = "pure_direct")]
-    pub pure_direct: bool,
+    pub pure_direct: Option<bool>,
     pub sleep: i64,
     #[serde(rename = "sound_program")]
-    pub sound_program: String,
+    pub sound_program: Option<SoundProgram>,
     #[serde(rename = "subwoofer_volume")]
-    pub subwoofer_volume: i64,
+    pub subwoofer_volume: Option<i64>,
     #[serde(rename = "surr_decoder_type")]
-    pub surr_decoder_type: String,
+    pub surr_decoder_type: Option<SurrDecoderType>,
+    #[serde(rename = "surround_3d")]
+    pub surround_3d: Option<bool>,
     #[serde(rename = "tone_control")]
-    pub tone_control: ToneControl,
+    pub tone_control: Option<ToneControl>,
     pub volume: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ActualVolume {
     pub mode: String,
     pub unit: String,
     pub value: f64,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ToneControl {
     pub bass: i64,
-    pub mode: String,
+    pub mode: ToneControlMode,
     pub treble: i64,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Equalizer {
+    pub high: i64,
+    pub low: i64,
+    pub mid: i64,
+    pub mode: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetSoundProgramList
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetSoundProgramList {
     #[serde(rename = "response_code")]
     pub response_code: i64,
     #[serde(rename = "sound_program_list")]
     pub sound_program_list: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 // GetSignalInfo
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct GetSignalInfo {
     pub audio: Audio,
     #[serde(rename = "response_code")]
     pub response_code: i64,
+    pub video: Option<Video>,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Audio {
-    pub bit: String,
-    pub bitrate: i64,
+    pub bit: Option<String>,
+    pub bitrate: Option<i64>,
     pub error: i64,
     pub format: String,
     pub fs: String,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Video {
+    #[serde(rename = "frame_rate")]
+    pub frame_rate: String,
+    pub hdmi: String,
+    pub hdr: String,
+    pub resolution: String,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// netusb/getPlayInfo
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetNetusbPlayInfo {
+    pub input: Input,
+    pub playback: String,
+    pub repeat: String,
+    pub shuffle: String,
+    #[serde(rename = "play_time")]
+    pub play_time: i64,
+    #[serde(rename = "total_time")]
+    pub total_time: i64,
+    pub artist: String,
+    pub album: String,
+    pub track: String,
+    #[serde(rename = "albumart_url")]
+    pub albumart_url: String,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+// tuner/getPlayInfo
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetTunerPlayInfo {
+    pub band: String,
+    #[serde(rename = "auto_scan")]
+    pub auto_scan: bool,
+    #[serde(rename = "auto_preset")]
+    pub auto_preset: bool,
+    pub am: TunerBand,
+    pub fm: TunerBand,
+    pub rds: Rds,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TunerBand {
+    pub preset: i64,
+    pub freq: i64,
+    pub tuned: bool,
+    #[serde(rename = "audio_mode")]
+    pub audio_mode: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rds {
+    #[serde(rename = "program_type")]
+    pub program_type: String,
+    #[serde(rename = "program_service")]
+    pub program_service: String,
+    #[serde(rename = "radio_text_a")]
+    pub radio_text_a: String,
+    #[serde(rename = "radio_text_b")]
+    pub radio_text_b: String,
+    #[serde(flatten)]
+    pub extra: Extra,
 }
@@ -1,18 +1,45 @@
+mod address;
+mod amp_state;
 mod async_api;
+mod backup;
 mod blocking_api;
+mod builder;
 mod common_api;
 mod discovery;
 mod error;
+mod events;
+mod fade;
+mod fleet;
 mod json_data;
+mod limits;
 mod model;
+mod retry;
+mod scene;
+mod schema;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use {
-    async_api::YamahaAmpAsync, blocking_api::YamahaAmpBlocking, discovery::*, error::YamahaError,
-    json_data::*, model::*,
+    address::{AmpAddress, DEFAULT_PORT, Host},
+    amp_state::{AmpSnapshot, AmpState, EVENT_KEEPALIVE},
+    async_api::YamahaAmpAsync,
+    backup::{BACKUP_VERSION, Backup, RestoreCall, RestorePlan},
+    blocking_api::YamahaAmpBlocking,
+    builder::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT, EventSettings, YamahaAmpBuilder},
+    common_api::DeviceInfo,
+    discovery::*,
+    error::{ParseEnumError, RequestContext, VolumeLimitKind, YamahaError, YamahaErrorCode},
+    events::{AmpEvent, EventListener},
+    fade::{FadeCurve, FadeOutcome, MIN_FADE_INTERVAL},
+    fleet::{DEFAULT_FLEET_CONCURRENCY, Fleet, FleetResult},
+    json_data::*,
+    limits::{VolumeLimits, VolumePolicy},
+    model::*,
+    retry::RetryPolicy,
+    scene::{Scene, SceneCall, SceneStore},
+    schema::{SchemaDrift, decode_strict},
 };
 
-use std::net::Ipv4Addr;
-
 impl YamahaAmpAsync {
     /// Découvre tous les amplificateurs Yamaha sur le réseau
     ///
@@ -25,15 +52,15 @@ impl YamahaAmpAsync {
         discover_amplifiers(config).await
     }
 
-    /// Se connecte directement à un amplificateur à l'adresse IP spécifiée
+    /// Se connecte directement à un amplificateur à l'adresse spécifiée
     ///
     /// # Arguments
-    /// * `ip` - Adresse IPv4 de l'amplificateur
+    /// * `address` - Adresse IPv4, IPv6 ou nom d'hôte de l'amplificateur
     ///
     /// # Returns
     /// * `Result<Option<YamahaAmpAsync>, YamahaError>` - L'amplificateur s'il est trouvé
-    pub async fn connect(ip: Ipv4Addr) -> Result<Option<Self>, YamahaError> {
-        connect_direct(ip).await
+    pub async fn connect(address: impl Into<AmpAddress>) -> Result<Option<Self>, YamahaError> {
+        connect_direct(address).await
     }
 }
 
@@ -49,14 +76,14 @@ impl YamahaAmpBlocking {
         discover_amplifiers_blocking(config)
     }
 
-    /// Se connecte directement à un amplificateur à l'adresse IP spécifiée
+    /// Se connecte directement à un amplificateur à l'adresse spécifiée
     ///
     /// # Arguments
-    /// * `ip` - Adresse IPv4 de l'amplificateur
+    /// * `address` - Adresse IPv4, IPv6 ou nom d'hôte de l'amplificateur
     ///
     /// # Returns
     /// * `Result<Option<YamahaAmpBlocking>, YamahaError>` - L'amplificateur s'il est trouvé
-    pub fn connect(ip: Ipv4Addr) -> Result<Option<Self>, YamahaError> {
-        connect_direct_blocking(ip)
+    pub fn connect(address: impl Into<AmpAddress>) -> Result<Option<Self>, YamahaError> {
+        connect_direct_blocking(address)
     }
 }
@@ -0,0 +1,354 @@
+use crate::error::{VolumeLimitKind, YamahaError};
+use crate::json_data::GetStatus;
+use crate::model::{PowerState, Zone};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Limites de volume d'une zone, en pas de l'appareil (l'unité de `volume` et
+/// `max_volume` dans `getStatus`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeLimits {
+    /// Volume maximal accepté
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_volume: Option<i64>,
+    /// Hausse maximale en un seul appel ; les baisses ne sont pas limitées
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_step: Option<i64>,
+    /// Volume auquel la zone est ramenée avant sa mise sous tension s'il est supérieur
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_on_volume: Option<i64>,
+}
+
+impl VolumeLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Limites de volume d'un amplificateur, vérifiées par le client avant chaque requête
+///
+/// Une requête dépassant une limite n'est pas envoyée et échoue avec
+/// [`YamahaError::VolumeLimit`]. Les setters, `raw_get`, les fondus et les groupes
+/// d'amplificateurs sont concernés ; seul [`crate::YamahaAmpAsync::with_volume_override`]
+/// permet de passer outre.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumePolicy {
+    /// Limites des zones qui n'en ont pas de propres
+    #[serde(flatten)]
+    pub default: VolumeLimits,
+    /// Limites propres à certaines zones, qui remplacent celles par défaut
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub zones: HashMap<Zone, VolumeLimits>,
+}
+
+impl VolumePolicy {
+    /// Crée une politique appliquant les mêmes limites à toutes les zones
+    pub fn new(default: VolumeLimits) -> Self {
+        Self {
+            default,
+            zones: HashMap::new(),
+        }
+    }
+
+    /// Remplace les limites d'une zone
+    pub fn with_zone(mut self, zone: Zone, limits: VolumeLimits) -> Self {
+        self.zones.insert(zone, limits);
+        self
+    }
+
+    /// Limites applicables à une zone
+    pub fn limits(&self, zone: &Zone) -> VolumeLimits {
+        self.zones.get(zone).copied().unwrap_or(self.default)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_empty() && self.zones.values().all(VolumeLimits::is_empty)
+    }
+}
+
+/// Effet d'une requête sur le volume d'une zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VolumeRequest {
+    /// `setVolume?volume=<n>`
+    To(i64),
+    /// `setActualVolume?mode=db&value=<dB>`
+    ToDb(f64),
+    /// Hausse relative, en pas
+    Up(i64),
+    /// Mise sous tension (`toggle` si la zone peut aussi passer en veille), précédée du
+    /// plafonnement du volume
+    PowerOn { toggle: bool },
+}
+
+/// Vérification d'une requête touchant au volume, préparée avant son envoi
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VolumeGuard {
+    zone: Zone,
+    limits: VolumeLimits,
+    request: VolumeRequest,
+}
+
+impl VolumeGuard {
+    /// Analyse une requête, `None` si elle ne touche pas au volume d'une zone limitée
+    ///
+    /// # Arguments
+    /// * `path` - Chemin de l'endpoint (ex: `zone2/setVolume`), requête encodée éventuelle
+    ///   incluse (ex: `main/setVolume?volume=160` passé à `raw_get`)
+    /// * `params` - Paramètres de la requête, non encodés
+    pub(crate) fn new(policy: &VolumePolicy, path: &str, params: &[(&str, &str)]) -> Option<Self> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let query: Vec<(String, String)> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| {
+                let decode = |s: &str| percent_decode_str(s).decode_utf8_lossy().into_owned();
+                (decode(key), decode(value))
+            })
+            .collect();
+        let (zone, function) = path.split_once('/')?;
+        let zone: Zone = zone.parse().ok()?;
+        let limits = policy.limits(&zone);
+        if limits.is_empty() {
+            return None;
+        }
+        let param = |name: &str| {
+            params
+                .iter()
+                .copied()
+                .chain(
+                    query
+                        .iter()
+                        .map(|(key, value)| (key.as_str(), value.as_str())),
+                )
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        };
+        let request = match (function, param("volume"), param("mode")) {
+            ("setVolume", Some("up"), _) => VolumeRequest::Up(
+                param("step")
+                    .and_then(|step| step.parse().ok())
+                    .unwrap_or(1),
+            ),
+            ("setVolume", Some("down"), _) => return None,
+            ("setVolume", Some(volume), _) => VolumeRequest::To(volume.parse().ok()?),
+            ("setActualVolume", _, Some("db")) => {
+                VolumeRequest::ToDb(param("value")?.parse().ok()?)
+            }
+            ("setActualVolume", _, Some("up")) => {
+                let db: f64 = param("value").and_then(|db| db.parse().ok()).unwrap_or(0.5);
+                VolumeRequest::Up((db / 0.5).round() as i64)
+            }
+            ("setPower", _, _)
+                if matches!(param("power"), Some("on" | "toggle"))
+                    && limits.power_on_volume.is_some() =>
+            {
+                VolumeRequest::PowerOn {
+                    toggle: param("power") == Some("toggle"),
+                }
+            }
+            _ => return None,
+        };
+        Some(Self {
+            zone,
+            limits,
+            request,
+        })
+    }
+
+    /// Zone concernée par la requête
+    pub(crate) fn zone(&self) -> &Zone {
+        &self.zone
+    }
+
+    /// Indique si le statut de la zone doit être lu avant [`Self::check`]
+    pub(crate) fn needs_status(&self) -> bool {
+        match self.request {
+            VolumeRequest::To(_) => self.limits.max_step.is_some(),
+            VolumeRequest::ToDb(_) | VolumeRequest::Up(_) | VolumeRequest::PowerOn { .. } => true,
+        }
+    }
+
+    /// Refuse la requête si elle dépasse une limite
+    ///
+    /// # Arguments
+    /// * `status` - Statut actuel de la zone, requis si [`Self::needs_status`]
+    pub(crate) fn check(&self, status: Option<&GetStatus>) -> Result<(), YamahaError> {
+        let current = status.map(|status| status.volume);
+        let target = match (self.request, status) {
+            (VolumeRequest::To(volume), _) => volume,
+            (VolumeRequest::Up(step), Some(status)) => status.volume + step,
+            (VolumeRequest::ToDb(db), Some(status)) => {
+                let actual = status.actual_volume.as_ref().ok_or_else(|| {
+                    YamahaError::Other(format!(
+                        "Zone {} does not report its volume in dB, volume limits cannot be checked",
+                        self.zone
+                    ))
+                })?;
+                status.volume + ((db - actual.value) / 0.5).round() as i64
+            }
+            _ => return Ok(()),
+        };
+
+        let error = |kind, requested, limit| YamahaError::VolumeLimit {
+            zone: self.zone.clone(),
+            kind,
+            requested,
+            limit,
+        };
+        if let Some(max) = self.limits.max_volume
+            && target > max
+        {
+            return Err(error(VolumeLimitKind::MaxVolume, target, max));
+        }
+        if let (Some(current), Some(max_step)) = (current, self.limits.max_step)
+            && target - current > max_step
+        {
+            return Err(error(VolumeLimitKind::MaxStep, target - current, max_step));
+        }
+        Ok(())
+    }
+
+    /// Volume auquel ramener la zone avant d'envoyer la requête
+    ///
+    /// La zone accepte `setVolume` en veille : le plafond est appliqué avant la mise sous
+    /// tension, pour qu'elle ne démarre jamais au-dessus.
+    ///
+    /// # Arguments
+    /// * `status` - Statut actuel de la zone
+    ///
+    /// # Returns
+    /// * `Option<i64>` - `None` si le volume ne dépasse pas le plafond ou si la requête
+    ///   met la zone en veille
+    pub(crate) fn power_on_cap(&self, status: &GetStatus) -> Option<i64> {
+        match self.request {
+            VolumeRequest::PowerOn { toggle } if !(toggle && status.power == PowerState::On) => {
+                self.limits
+                    .power_on_volume
+                    .filter(|cap| status.volume > *cap)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_data::ActualVolume;
+
+    fn policy() -> VolumePolicy {
+        VolumePolicy::new(VolumeLimits {
+            max_volume: Some(100),
+            max_step: Some(10),
+            power_on_volume: Some(60),
+        })
+        .with_zone(Zone::Zone2, VolumeLimits::default())
+    }
+
+    fn status(volume: i64) -> GetStatus {
+        GetStatus {
+            volume,
+            actual_volume: Some(ActualVolume {
+                mode: "db".into(),
+                unit: "dB".into(),
+                value: -80.5 + volume as f64 * 0.5,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn check(path: &str, params: &[(&str, &str)], current: i64) -> Result<(), YamahaError> {
+        let guard = VolumeGuard::new(&policy(), path, params).unwrap();
+        let status = status(current);
+        guard.check(guard.needs_status().then_some(&status))
+    }
+
+    #[test]
+    fn requests_above_the_limits_are_refused() {
+        assert!(check("main/setVolume", &[("volume", "95")], 90).is_ok());
+        let err = check("main/setVolume", &[("volume", "101")], 95).unwrap_err();
+        assert!(matches!(
+            err,
+            YamahaError::VolumeLimit {
+                kind: VolumeLimitKind::MaxVolume,
+                requested: 101,
+                limit: 100,
+                ..
+            }
+        ));
+        let err = check("main/setVolume", &[("volume", "16")], 2).unwrap_err();
+        assert!(matches!(
+            err,
+            YamahaError::VolumeLimit {
+                kind: VolumeLimitKind::MaxStep,
+                requested: 14,
+                ..
+            }
+        ));
+        assert!(check("main/setVolume", &[("volume", "up"), ("step", "5")], 95).is_ok());
+        assert!(check("main/setVolume", &[("volume", "up"), ("step", "6")], 95).is_err());
+        assert!(
+            check(
+                "main/setActualVolume",
+                &[("mode", "db"), ("value", "-31")],
+                99
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                "main/setActualVolume",
+                &[("mode", "db"), ("value", "-30")],
+                99
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn unlimited_requests_are_not_guarded() {
+        let policy = policy();
+        let guard = |path, params| VolumeGuard::new(&policy, path, params);
+        assert!(guard("main/setVolume", &[("volume", "down")]).is_none());
+        assert!(guard("zone2/setVolume", &[("volume", "160")]).is_none());
+        assert!(guard("main/setMute", &[("enable", "true")]).is_none());
+        assert!(guard("system/setNameText", &[("id", "main")]).is_none());
+        let power = guard("main/setPower", &[("power", "on")]).unwrap();
+        assert!(power.needs_status());
+        assert_eq!(power.power_on_cap(&status(80)), Some(60));
+        assert_eq!(power.power_on_cap(&status(50)), None);
+        let toggle = guard("main/setPower", &[("power", "toggle")]).unwrap();
+        let on = GetStatus {
+            power: PowerState::On,
+            ..status(80)
+        };
+        assert_eq!(toggle.power_on_cap(&status(80)), Some(60));
+        assert_eq!(toggle.power_on_cap(&on), None);
+        assert!(guard("main/setPower", &[("power", "standby")]).is_none());
+        assert!(guard("main/setPower?power=standby", &[]).is_none());
+    }
+
+    #[test]
+    fn queries_in_the_path_are_guarded() {
+        let policy = policy();
+        let guard = |path| VolumeGuard::new(&policy, path, &[]);
+        let current = status(50);
+        let volume = guard("main/setVolume?volume=160").unwrap();
+        assert!(matches!(
+            volume.check(Some(&current)),
+            Err(YamahaError::VolumeLimit {
+                kind: VolumeLimitKind::MaxVolume,
+                requested: 160,
+                ..
+            })
+        ));
+        let up = guard("main/setVolume?volume=up&step=20").unwrap();
+        assert!(up.check(Some(&current)).is_err());
+        let db = guard("main/setActualVolume?mode=db&value=-20.0").unwrap();
+        assert!(db.needs_status());
+        let power = guard("main/setPower?power=on").unwrap();
+        assert_eq!(power.power_on_cap(&status(80)), Some(60));
+    }
+}
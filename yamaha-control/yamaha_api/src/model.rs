@@ -1,10 +1,12 @@
+use crate::error::ParseEnumError;
+
 macro_rules! impl_string_enum {
     (
         $(#[$meta:meta])*
         pub enum $name:ident {
             $(
                 $(#[$variant_meta:meta])*
-                $variant:ident $(= $str_value:expr)?
+                $variant:ident => $wire:literal
             ),* $(,)?
         }
     ) => {
@@ -14,208 +16,495 @@ macro_rules! impl_string_enum {
                 $(#[$variant_meta])*
                 $variant,
             )*
+            /// Valeur renvoyée par l'appareil mais inconnue de cette bibliothèque
+            Unknown(String),
         }
 
         impl $name {
-            fn to_snake_case(s: &str) -> String {
-                let mut result = String::with_capacity(s.len() + 4);
-                for (i, ch) in s.chars().enumerate() {
-                    if ch.is_uppercase() {
-                        if i != 0 {
-                            result.push('_');
-                        }
-                        for lower_ch in ch.to_lowercase() {
-                            result.push(lower_ch);
-                        }
-                    } else {
-                        result.push(ch);
-                    }
+            /// Liste de toutes les valeurs connues (hors `Unknown`)
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            /// Identifiant utilisé par l'API YXC
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $wire,)*
+                    Self::Unknown(raw) => raw,
                 }
-                result
             }
 
-            pub fn as_str(&self) -> String {
-                match self {
-                    $(
-                        Self::$variant => {
-                            let raw = impl_string_enum!(@stringify_or_expr $variant $(, $str_value)?);
-                            Self::to_snake_case(raw)
-                        },
-                    )*
+            fn from_known(s: &str) -> Option<Self> {
+                match s {
+                    $($wire => Some(Self::$variant),)*
+                    _ => None,
                 }
             }
 
-            pub fn from_str(s: &str) -> Option<Self> {
-                $(
-                    if s == Self::to_snake_case(impl_string_enum!(@stringify_or_expr $variant $(, $str_value)?)) {
-                        return Some(Self::$variant);
-                    }
-                )*
-                None
+            /// Convertit une chaîne de l'API, en conservant les valeurs inconnues dans `Unknown`
+            pub fn from_str_lossy(s: &str) -> Self {
+                Self::from_known(s).unwrap_or_else(|| Self::Unknown(s.to_string()))
+            }
+
+            /// Indique si la valeur est inconnue de cette bibliothèque
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, Self::Unknown(_))
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = ParseEnumError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::from_known(s).ok_or_else(|| ParseEnumError::new(stringify!($name), s))
             }
         }
 
         impl std::fmt::Display for $name {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                let s = self.as_str();
-                f.write_str(&s)
+                f.write_str(self.as_str())
             }
         }
-    };
 
-    (@stringify_or_expr $variant:ident, $str_value:expr) => {
-        $str_value
-    };
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
 
-    (@stringify_or_expr $variant:ident) => {
-        stringify!($variant)
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let raw = <String as serde::Deserialize>::deserialize(deserializer)?;
+                Ok(Self::from_known(&raw).unwrap_or(Self::Unknown(raw)))
+            }
+        }
     };
 }
 
 impl_string_enum! {
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Zone {
-    Main,
+    #[default]
+    Main => "main",
     /// Zone B is handles as "Zone2"
-    Zone2,
-    Zone3,
-    Zone4,
+    Zone2 => "zone2",
+    Zone3 => "zone3",
+    Zone4 => "zone4",
 }
     }
 
 impl_string_enum! {
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum PowerState {
-    On,
-    Toggle,
-    Standby,
+    On => "on",
+    Toggle => "toggle",
+    #[default]
+    Standby => "standby",
 }
     }
 
 impl_string_enum! {
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Input {
-    Cd,
-    Tuner,
-    MultiCh,
-    Phono,
-    Hdmi1,
-    Hdmi2,
-    Hdmi3,
-    Hdmi4,
-    Hdmi5,
-    Hdmi6,
-    Hdmi7,
-    Hdmi8,
-    Hdmi,
-    Av1,
-    Av2,
-    Av3,
-    Av4,
-    Av5,
-    Av6,
-    Av7,
-    VAux,
-    Aux1,
-    Aux2,
-    Aux,
-    Audio1,
-    Audio2,
-    Audio3,
-    Audio4,
-    AudioCd,
-    Audio,
-    Optical1,
-    Optical2,
-    Optical,
-    Coaxial1,
-    Coaxial2,
-    Coaxial,
-    Digital1,
-    Digital2,
-    Digital,
-    Line1,
-    Line2,
-    Line3,
-    LineCd,
-    Analog,
-    Tv,
-    BdDvd,
-    UsbDac,
-    Usb,
-    Bluetooth,
-    Server,
-    NetRadio,
-    Rhapsody,
-    Napster,
-    Pandora,
-    Siriusxm,
-    Spotify,
-    Juke,
-    Airplay,
-    Radiko,
-    Qobuz,
-    McLink,
-    MainSync,
-    None,
+    Cd => "cd",
+    Tuner => "tuner",
+    MultiCh => "multi_ch",
+    Phono => "phono",
+    Hdmi1 => "hdmi1",
+    Hdmi2 => "hdmi2",
+    Hdmi3 => "hdmi3",
+    Hdmi4 => "hdmi4",
+    Hdmi5 => "hdmi5",
+    Hdmi6 => "hdmi6",
+    Hdmi7 => "hdmi7",
+    Hdmi8 => "hdmi8",
+    Hdmi => "hdmi",
+    Av1 => "av1",
+    Av2 => "av2",
+    Av3 => "av3",
+    Av4 => "av4",
+    Av5 => "av5",
+    Av6 => "av6",
+    Av7 => "av7",
+    VAux => "v_aux",
+    Aux1 => "aux1",
+    Aux2 => "aux2",
+    Aux => "aux",
+    Audio1 => "audio1",
+    Audio2 => "audio2",
+    Audio3 => "audio3",
+    Audio4 => "audio4",
+    Audio5 => "audio5",
+    AudioCd => "audio_cd",
+    Audio => "audio",
+    Optical1 => "optical1",
+    Optical2 => "optical2",
+    Optical => "optical",
+    Coaxial1 => "coaxial1",
+    Coaxial2 => "coaxial2",
+    Coaxial => "coaxial",
+    Digital1 => "digital1",
+    Digital2 => "digital2",
+    Digital => "digital",
+    Line1 => "line1",
+    Line2 => "line2",
+    Line3 => "line3",
+    LineCd => "line_cd",
+    Analog => "analog",
+    Tv => "tv",
+    BdDvd => "bd_dvd",
+    UsbDac => "usb_dac",
+    Usb => "usb",
+    Bluetooth => "bluetooth",
+    Server => "server",
+    NetRadio => "net_radio",
+    Rhapsody => "rhapsody",
+    Napster => "napster",
+    Pandora => "pandora",
+    Siriusxm => "siriusxm",
+    Spotify => "spotify",
+    Juke => "juke",
+    Airplay => "airplay",
+    Radiko => "radiko",
+    Qobuz => "qobuz",
+    Tidal => "tidal",
+    Deezer => "deezer",
+    McLink => "mc_link",
+    MainSync => "main_sync",
+    #[default]
+    None => "none",
 }
     }
 
 impl_string_enum! {
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum SoundProgram {
-    MunichA,
-    MunichB,
-    Munich,
-    Frankfurt,
-    Stuttgart,
-    Vienna,
-    Amsterdam,
-    UsaA,
-    UsaB,
-    Tokyo,
-    Freiburg,
-    Royaumont,
-    Chamber,
-    Concert,
-    VillageGate,
-    VillageVanguard,
-    WarehouseLoft,
-    CellarClub,
-    JazzClub,
-    RoxyTheatre,
-    BottomLine,
-    Arena,
-    Sports,
-    ActionGame,
-    RoleplayingGame,
-    Game,
-    MusicVideo,
-    Music,
-    RecitalOpera,
-    Pavilion,
-    Disco,
-    Standard,
-    Spectacle,
-    SciFi,
-    Adventure,
-    Drama,
-    TalkShow,
-    TvProgram,
-    MonoMovie,
-    Movie,
-    Enhanced,
-    Ch2Stereo,
-    Ch5Stereo,
-    Ch7Stereo,
-    Ch9Stereo,
-    Ch11Stereo,
-    Stereo,
-    SurrDecoder,
-    MySurround,
-    Target,
-    Straight,
-    Off,
+    MunichA => "munich_a",
+    MunichB => "munich_b",
+    Munich => "munich",
+    Frankfurt => "frankfurt",
+    Stuttgart => "stuttgart",
+    Vienna => "vienna",
+    Amsterdam => "amsterdam",
+    UsaA => "usa_a",
+    UsaB => "usa_b",
+    Tokyo => "tokyo",
+    Freiburg => "freiburg",
+    Royaumont => "royaumont",
+    Chamber => "chamber",
+    Concert => "concert",
+    VillageGate => "village_gate",
+    VillageVanguard => "village_vanguard",
+    WarehouseLoft => "warehouse_loft",
+    CellarClub => "cellar_club",
+    JazzClub => "jazz_club",
+    RoxyTheatre => "roxy_theatre",
+    BottomLine => "bottom_line",
+    Arena => "arena",
+    Sports => "sports",
+    ActionGame => "action_game",
+    RoleplayingGame => "roleplaying_game",
+    Game => "game",
+    MusicVideo => "music_video",
+    Music => "music",
+    RecitalOpera => "recital_opera",
+    Pavilion => "pavilion",
+    Disco => "disco",
+    Standard => "standard",
+    Spectacle => "spectacle",
+    SciFi => "sci-fi",
+    Adventure => "adventure",
+    Drama => "drama",
+    TalkShow => "talk_show",
+    TvProgram => "tv_program",
+    MonoMovie => "mono_movie",
+    Movie => "movie",
+    Enhanced => "enhanced",
+    Ch2Stereo => "2ch_stereo",
+    Ch5Stereo => "5ch_stereo",
+    Ch7Stereo => "7ch_stereo",
+    Ch9Stereo => "9ch_stereo",
+    Ch11Stereo => "11ch_stereo",
+    Stereo => "stereo",
+    SurrDecoder => "surr_decoder",
+    MySurround => "my_surround",
+    Target => "target",
+    Straight => "straight",
+    BassBooster => "bass_booster",
+    #[default]
+    Off => "off",
+}
+    }
+
+impl_string_enum! {
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum SurrDecoderType {
+    Toggle => "toggle",
+    #[default]
+    Auto => "auto",
+    DolbyPl => "dolby_pl",
+    DolbyPl2xMovie => "dolby_pl2x_movie",
+    DolbyPl2xMusic => "dolby_pl2x_music",
+    DolbyPl2xGame => "dolby_pl2x_game",
+    DolbySurround => "dolby_surround",
+    DtsNeuralX => "dts_neural_x",
+    DtsNeo6Cinema => "dts_neo6_cinema",
+    DtsNeo6Music => "dts_neo6_music",
+    AuroLegacy => "auro_legacy",
+}
+    }
+
+impl_string_enum! {
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum LinkControl {
+    #[default]
+    Standard => "standard",
+    Speed => "speed",
+    Stability => "stability",
 }
     }
+
+impl_string_enum! {
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum ToneControlMode {
+    #[default]
+    Manual => "manual",
+    Auto => "auto",
+    Bypass => "bypass",
+}
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vérifie l'aller-retour chaîne <-> variante et la couverture de `ALL`
+    fn assert_round_trip<T>(table: &[(T, &str)], all: &[T])
+    where
+        T: std::fmt::Debug + std::fmt::Display + PartialEq + std::str::FromStr + serde::Serialize,
+        T: for<'de> serde::Deserialize<'de>,
+        <T as std::str::FromStr>::Err: std::fmt::Debug,
+    {
+        assert_eq!(table.len(), all.len(), "table does not cover every variant");
+        for ((variant, wire), listed) in table.iter().zip(all) {
+            assert_eq!(variant, listed);
+            assert_eq!(&variant.to_string(), wire);
+            assert_eq!(&wire.parse::<T>().unwrap(), variant);
+            assert_eq!(
+                serde_json::to_value(variant).unwrap(),
+                serde_json::Value::from(*wire)
+            );
+            assert_eq!(
+                &serde_json::from_value::<T>(serde_json::Value::from(*wire)).unwrap(),
+                variant
+            );
+        }
+    }
+
+    #[test]
+    fn zone_wire_names() {
+        let table = [
+            (Zone::Main, "main"),
+            (Zone::Zone2, "zone2"),
+            (Zone::Zone3, "zone3"),
+            (Zone::Zone4, "zone4"),
+        ];
+        assert_round_trip(&table, Zone::ALL);
+    }
+
+    #[test]
+    fn power_state_wire_names() {
+        let table = [
+            (PowerState::On, "on"),
+            (PowerState::Toggle, "toggle"),
+            (PowerState::Standby, "standby"),
+        ];
+        assert_round_trip(&table, PowerState::ALL);
+    }
+
+    #[test]
+    fn input_wire_names() {
+        let table = [
+            (Input::Cd, "cd"),
+            (Input::Tuner, "tuner"),
+            (Input::MultiCh, "multi_ch"),
+            (Input::Phono, "phono"),
+            (Input::Hdmi1, "hdmi1"),
+            (Input::Hdmi2, "hdmi2"),
+            (Input::Hdmi3, "hdmi3"),
+            (Input::Hdmi4, "hdmi4"),
+            (Input::Hdmi5, "hdmi5"),
+            (Input::Hdmi6, "hdmi6"),
+            (Input::Hdmi7, "hdmi7"),
+            (Input::Hdmi8, "hdmi8"),
+            (Input::Hdmi, "hdmi"),
+            (Input::Av1, "av1"),
+            (Input::Av2, "av2"),
+            (Input::Av3, "av3"),
+            (Input::Av4, "av4"),
+            (Input::Av5, "av5"),
+            (Input::Av6, "av6"),
+            (Input::Av7, "av7"),
+            (Input::VAux, "v_aux"),
+            (Input::Aux1, "aux1"),
+            (Input::Aux2, "aux2"),
+            (Input::Aux, "aux"),
+            (Input::Audio1, "audio1"),
+            (Input::Audio2, "audio2"),
+            (Input::Audio3, "audio3"),
+            (Input::Audio4, "audio4"),
+            (Input::Audio5, "audio5"),
+            (Input::AudioCd, "audio_cd"),
+            (Input::Audio, "audio"),
+            (Input::Optical1, "optical1"),
+            (Input::Optical2, "optical2"),
+            (Input::Optical, "optical"),
+            (Input::Coaxial1, "coaxial1"),
+            (Input::Coaxial2, "coaxial2"),
+            (Input::Coaxial, "coaxial"),
+            (Input::Digital1, "digital1"),
+            (Input::Digital2, "digital2"),
+            (Input::Digital, "digital"),
+            (Input::Line1, "line1"),
+            (Input::Line2, "line2"),
+            (Input::Line3, "line3"),
+            (Input::LineCd, "line_cd"),
+            (Input::Analog, "analog"),
+            (Input::Tv, "tv"),
+            (Input::BdDvd, "bd_dvd"),
+            (Input::UsbDac, "usb_dac"),
+            (Input::Usb, "usb"),
+            (Input::Bluetooth, "bluetooth"),
+            (Input::Server, "server"),
+            (Input::NetRadio, "net_radio"),
+            (Input::Rhapsody, "rhapsody"),
+            (Input::Napster, "napster"),
+            (Input::Pandora, "pandora"),
+            (Input::Siriusxm, "siriusxm"),
+            (Input::Spotify, "spotify"),
+            (Input::Juke, "juke"),
+            (Input::Airplay, "airplay"),
+            (Input::Radiko, "radiko"),
+            (Input::Qobuz, "qobuz"),
+            (Input::Tidal, "tidal"),
+            (Input::Deezer, "deezer"),
+            (Input::McLink, "mc_link"),
+            (Input::MainSync, "main_sync"),
+            (Input::None, "none"),
+        ];
+        assert_round_trip(&table, Input::ALL);
+    }
+
+    #[test]
+    fn sound_program_wire_names() {
+        let table = [
+            (SoundProgram::MunichA, "munich_a"),
+            (SoundProgram::MunichB, "munich_b"),
+            (SoundProgram::Munich, "munich"),
+            (SoundProgram::Frankfurt, "frankfurt"),
+            (SoundProgram::Stuttgart, "stuttgart"),
+            (SoundProgram::Vienna, "vienna"),
+            (SoundProgram::Amsterdam, "amsterdam"),
+            (SoundProgram::UsaA, "usa_a"),
+            (SoundProgram::UsaB, "usa_b"),
+            (SoundProgram::Tokyo, "tokyo"),
+            (SoundProgram::Freiburg, "freiburg"),
+            (SoundProgram::Royaumont, "royaumont"),
+            (SoundProgram::Chamber, "chamber"),
+            (SoundProgram::Concert, "concert"),
+            (SoundProgram::VillageGate, "village_gate"),
+            (SoundProgram::VillageVanguard, "village_vanguard"),
+            (SoundProgram::WarehouseLoft, "warehouse_loft"),
+            (SoundProgram::CellarClub, "cellar_club"),
+            (SoundProgram::JazzClub, "jazz_club"),
+            (SoundProgram::RoxyTheatre, "roxy_theatre"),
+            (SoundProgram::BottomLine, "bottom_line"),
+            (SoundProgram::Arena, "arena"),
+            (SoundProgram::Sports, "sports"),
+            (SoundProgram::ActionGame, "action_game"),
+            (SoundProgram::RoleplayingGame, "roleplaying_game"),
+            (SoundProgram::Game, "game"),
+            (SoundProgram::MusicVideo, "music_video"),
+            (SoundProgram::Music, "music"),
+            (SoundProgram::RecitalOpera, "recital_opera"),
+            (SoundProgram::Pavilion, "pavilion"),
+            (SoundProgram::Disco, "disco"),
+            (SoundProgram::Standard, "standard"),
+            (SoundProgram::Spectacle, "spectacle"),
+            (SoundProgram::SciFi, "sci-fi"),
+            (SoundProgram::Adventure, "adventure"),
+            (SoundProgram::Drama, "drama"),
+            (SoundProgram::TalkShow, "talk_show"),
+            (SoundProgram::TvProgram, "tv_program"),
+            (SoundProgram::MonoMovie, "mono_movie"),
+            (SoundProgram::Movie, "movie"),
+            (SoundProgram::Enhanced, "enhanced"),
+            (SoundProgram::Ch2Stereo, "2ch_stereo"),
+            (SoundProgram::Ch5Stereo, "5ch_stereo"),
+            (SoundProgram::Ch7Stereo, "7ch_stereo"),
+            (SoundProgram::Ch9Stereo, "9ch_stereo"),
+            (SoundProgram::Ch11Stereo, "11ch_stereo"),
+            (SoundProgram::Stereo, "stereo"),
+            (SoundProgram::SurrDecoder, "surr_decoder"),
+            (SoundProgram::MySurround, "my_surround"),
+            (SoundProgram::Target, "target"),
+            (SoundProgram::Straight, "straight"),
+            (SoundProgram::BassBooster, "bass_booster"),
+            (SoundProgram::Off, "off"),
+        ];
+        assert_round_trip(&table, SoundProgram::ALL);
+    }
+
+    #[test]
+    fn surr_decoder_type_wire_names() {
+        let table = [
+            (SurrDecoderType::Toggle, "toggle"),
+            (SurrDecoderType::Auto, "auto"),
+            (SurrDecoderType::DolbyPl, "dolby_pl"),
+            (SurrDecoderType::DolbyPl2xMovie, "dolby_pl2x_movie"),
+            (SurrDecoderType::DolbyPl2xMusic, "dolby_pl2x_music"),
+            (SurrDecoderType::DolbyPl2xGame, "dolby_pl2x_game"),
+            (SurrDecoderType::DolbySurround, "dolby_surround"),
+            (SurrDecoderType::DtsNeuralX, "dts_neural_x"),
+            (SurrDecoderType::DtsNeo6Cinema, "dts_neo6_cinema"),
+            (SurrDecoderType::DtsNeo6Music, "dts_neo6_music"),
+            (SurrDecoderType::AuroLegacy, "auro_legacy"),
+        ];
+        assert_round_trip(&table, SurrDecoderType::ALL);
+    }
+
+    #[test]
+    fn link_control_wire_names() {
+        let table = [
+            (LinkControl::Standard, "standard"),
+            (LinkControl::Speed, "speed"),
+            (LinkControl::Stability, "stability"),
+        ];
+        assert_round_trip(&table, LinkControl::ALL);
+    }
+
+    #[test]
+    fn tone_control_mode_wire_names() {
+        let table = [
+            (ToneControlMode::Manual, "manual"),
+            (ToneControlMode::Auto, "auto"),
+            (ToneControlMode::Bypass, "bypass"),
+        ];
+        assert_round_trip(&table, ToneControlMode::ALL);
+    }
+
+    #[test]
+    fn unknown_values_are_preserved() {
+        let program: SoundProgram = serde_json::from_str("\"all_night_jazz\"").unwrap();
+        assert_eq!(program, SoundProgram::Unknown("all_night_jazz".into()));
+        assert!(program.is_unknown());
+        assert_eq!(
+            serde_json::to_string(&program).unwrap(),
+            "\"all_night_jazz\""
+        );
+        assert_eq!(
+            Input::from_str_lossy("hdmi9"),
+            Input::Unknown("hdmi9".into())
+        );
+        assert!("hdmi9".parse::<Input>().is_err());
+    }
+}
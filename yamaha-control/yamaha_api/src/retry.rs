@@ -0,0 +1,187 @@
+use crate::error::YamahaError;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Politique de relance des requêtes vers un amplificateur
+///
+/// Seules les erreurs pour lesquelles [`YamahaError::is_retryable`] renvoie `true`
+/// sont relancées : erreurs de connexion, délais dépassés, erreurs serveur et codes
+/// transitoires de l'appareil (`Initializing`, `Timeout`, ...). Une requête n'est
+/// relancée que si elle peut être répétée sans risque : lecture (`get*`) ou réglage
+/// à une valeur absolue (`set*`), jamais une bascule, un pas relatif ou un `store*`.
+///
+/// La politique par défaut ne relance rien ; les relances s'activent avec
+/// [`RetryPolicy::attempts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Nombre maximum de tentatives, première requête incluse
+    pub max_attempts: u32,
+    /// Délai d'attente avant la première relance
+    pub initial_backoff: Duration,
+    /// Délai d'attente maximum entre deux tentatives
+    pub max_backoff: Duration,
+    /// Facteur multiplicatif appliqué au délai après chaque tentative
+    pub multiplier: f64,
+    /// Part aléatoire du délai, entre 0.0 (aucune) et 1.0 (± 100 %)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(4),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Politique sans relance : chaque requête n'est tentée qu'une fois
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Politique tentant chaque requête répétable jusqu'à `max_attempts` fois
+    pub fn attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Indique si une requête ayant échoué avec `error` doit être relancée
+    ///
+    /// # Arguments
+    /// * `attempt` - Numéro de la tentative qui vient d'échouer (à partir de 1)
+    /// * `path` - Chemin de la requête, paramètres inclus (ex: `main/setVolume?volume=40`)
+    /// * `error` - Erreur renvoyée par cette tentative
+    pub fn should_retry(&self, attempt: u32, path: &str, error: &YamahaError) -> bool {
+        attempt < self.max_attempts && error.is_retryable() && is_repeatable(path)
+    }
+
+    /// Délai à respecter après l'échec de la tentative `attempt` (à partir de 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let base = base.min(self.max_backoff.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (2.0 * random_unit() - 1.0);
+        Duration::from_secs_f64((base * factor).max(0.0))
+    }
+}
+
+/// Valeurs de paramètres rendant un `set*` relatif à l'état courant de l'appareil
+const RELATIVE_VALUES: &[&str] = &[
+    "toggle",
+    "up",
+    "down",
+    "auto_up",
+    "auto_down",
+    "tp_up",
+    "tp_down",
+    "next",
+    "previous",
+    "play_pause",
+];
+
+/// Setters dont l'effet dépend de l'état courant quelle que soit la valeur envoyée
+const RELATIVE_SETTERS: &[&str] = &["setListControl", "setCursor", "setMenu"];
+
+/// Indique si une requête peut être renvoyée sans risque de répéter son effet
+///
+/// Les lectures (`get*`) et les réglages à une valeur absolue (`set*`) le sont. Les
+/// bascules (`toggle`), les pas relatifs (`volume=up`, `mode=down`, ...), les
+/// enregistrements (`store*`, ex: `tuner/storePreset`) et tout autre appel ne le sont pas.
+///
+/// # Arguments
+/// * `path` - Chemin de la requête, paramètres inclus (ex: `main/setPower?power=on`)
+pub(crate) fn is_repeatable(path: &str) -> bool {
+    let (endpoint, query) = path.split_once('?').unwrap_or((path, ""));
+    let function = endpoint.rsplit('/').next().unwrap_or(endpoint);
+    if function.starts_with("get") {
+        return true;
+    }
+    if !function.starts_with("set") || RELATIVE_SETTERS.contains(&function) {
+        return false;
+    }
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .all(|(_, value)| !RELATIVE_VALUES.contains(&value))
+}
+
+/// Renvoie une valeur pseudo-aléatoire dans `[0, 1)`, suffisante pour étaler les relances
+fn random_unit() -> f64 {
+    let bits = RandomState::new().hash_one(std::time::SystemTime::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            multiplier: 2.0,
+            jitter: 0.0,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(60), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_bounds() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..RetryPolicy::default()
+        };
+        for _ in 0..100 {
+            let delay = policy.backoff(1);
+            assert!(delay >= Duration::from_millis(125));
+            assert!(delay <= Duration::from_millis(375));
+        }
+    }
+
+    #[test]
+    fn none_never_retries() {
+        let err = YamahaError::Other("boom".into());
+        assert!(!RetryPolicy::none().should_retry(1, "main/getStatus", &err));
+        assert_eq!(RetryPolicy::default(), RetryPolicy::none());
+    }
+
+    #[test]
+    fn only_repeatable_requests_are_retried() {
+        assert!(is_repeatable("main/getStatus"));
+        assert!(is_repeatable("system/getFeatures"));
+        assert!(is_repeatable("main/setVolume?volume=40"));
+        assert!(is_repeatable("main/setPower?power=on"));
+        assert!(is_repeatable("netusb/setPlayback?playback=stop"));
+        assert!(!is_repeatable("main/setPower?power=toggle"));
+        assert!(!is_repeatable("main/setVolume?volume=up&step=2"));
+        assert!(!is_repeatable("main/setActualVolume?mode=down&value=1.0"));
+        assert!(!is_repeatable("tuner/setFreq?band=fm&tuning=auto_up"));
+        assert!(!is_repeatable("netusb/setPlayback?playback=next"));
+        assert!(!is_repeatable(
+            "netusb/setListControl?list_id=main&type=select&index=0"
+        ));
+        assert!(!is_repeatable("tuner/storePreset?num=1"));
+        assert!(!is_repeatable("netusb/storePreset?num=1"));
+        assert!(!is_repeatable("netusb/recallPreset?zone=main&num=1"));
+
+        let policy = RetryPolicy::attempts(3);
+        let err = YamahaError::Other("boom".into());
+        assert!(!policy.should_retry(1, "main/getStatus", &err));
+    }
+}
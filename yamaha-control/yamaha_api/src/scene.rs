@@ -0,0 +1,588 @@
+use crate::async_api::YamahaAmpAsync;
+use crate::error::YamahaError;
+use crate::json_data::{Equalizer, GetStatus, ToneControl};
+use crate::model::{
+    Input, LinkControl, PowerState, SoundProgram, SurrDecoderType, ToneControlMode, Zone,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// État complet d'une zone, enregistré sous un nom pour être réappliqué plus tard
+///
+/// Les réglages absents (`None`) ne sont pas pris en charge par l'appareil d'origine et
+/// ne sont pas modifiés lors de l'application.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    /// Zone dont l'état a été capturé
+    pub zone: Zone,
+    pub power: PowerState,
+    pub input: Input,
+    /// Volume en pas de l'appareil (0 à `max_volume`)
+    pub volume: i64,
+    pub mute: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound_program: Option<SoundProgram>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surr_decoder_type: Option<SurrDecoderType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pure_direct: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhancer: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone_control: Option<ToneControl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equalizer: Option<Equalizer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialogue_level: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subwoofer_volume: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bass_extension: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_bass: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_drc: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_voice: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surround_3d: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_control: Option<LinkControl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_audio_delay: Option<String>,
+}
+
+/// Appel d'un setter YXC nécessaire pour appliquer une scène
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneCall {
+    Power(PowerState),
+    Mute(bool),
+    Input(Input),
+    PureDirect(bool),
+    SoundProgram(SoundProgram),
+    SurroundDecoderType(SurrDecoderType),
+    Direct(bool),
+    Enhancer(bool),
+    ToneControl {
+        mode: ToneControlMode,
+        bass: i64,
+        treble: i64,
+    },
+    Equalizer {
+        mode: String,
+        low: i64,
+        mid: i64,
+        high: i64,
+    },
+    DialogueLevel(i64),
+    SubwooferVolume(i64),
+    Balance(i64),
+    BassExtension(bool),
+    ExtraBass(bool),
+    AdaptiveDrc(bool),
+    ClearVoice(bool),
+    Surround3d(bool),
+    LinkControl(LinkControl),
+    LinkAudioDelay(String),
+    Volume(i64),
+}
+
+impl SceneCall {
+    /// Fonction appelée sur la zone (ex: `setInput`)
+    pub fn function(&self) -> &'static str {
+        match self {
+            Self::Power(_) => "setPower",
+            Self::Mute(_) => "setMute",
+            Self::Input(_) => "setInput",
+            Self::PureDirect(_) => "setPureDirect",
+            Self::SoundProgram(_) => "setSoundProgram",
+            Self::SurroundDecoderType(_) => "setSurroundDecoderType",
+            Self::Direct(_) => "setDirect",
+            Self::Enhancer(_) => "setEnhancer",
+            Self::ToneControl { .. } => "setToneControl",
+            Self::Equalizer { .. } => "setEqualizer",
+            Self::DialogueLevel(_) => "setDialogueLevel",
+            Self::SubwooferVolume(_) => "setSubwooferVolume",
+            Self::Balance(_) => "setBalance",
+            Self::BassExtension(_) => "setBassExtension",
+            Self::ExtraBass(_) => "setExtraBass",
+            Self::AdaptiveDrc(_) => "setAdaptiveDrc",
+            Self::ClearVoice(_) => "setClearVoice",
+            Self::Surround3d(_) => "setSurround3d",
+            Self::LinkControl(_) => "setLinkControl",
+            Self::LinkAudioDelay(_) => "setLinkAudioDelay",
+            Self::Volume(_) => "setVolume",
+        }
+    }
+
+    /// Paramètres de la requête, non encodés
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Power(power) => vec![("power", power.to_string())],
+            Self::Input(input) => vec![("input", input.to_string())],
+            Self::SoundProgram(program) => vec![("program", program.to_string())],
+            Self::SurroundDecoderType(decoder) => vec![("type", decoder.to_string())],
+            Self::ToneControl { mode, bass, treble } => vec![
+                ("mode", mode.to_string()),
+                ("bass", bass.to_string()),
+                ("treble", treble.to_string()),
+            ],
+            Self::Equalizer {
+                mode,
+                low,
+                mid,
+                high,
+            } => vec![
+                ("mode", mode.clone()),
+                ("low", low.to_string()),
+                ("mid", mid.to_string()),
+                ("high", high.to_string()),
+            ],
+            Self::DialogueLevel(value) | Self::Balance(value) => {
+                vec![("value", value.to_string())]
+            }
+            Self::SubwooferVolume(volume) | Self::Volume(volume) => {
+                vec![("volume", volume.to_string())]
+            }
+            Self::LinkControl(control) => vec![("control", control.to_string())],
+            Self::LinkAudioDelay(delay) => vec![("delay", delay.clone())],
+            Self::Mute(enable)
+            | Self::PureDirect(enable)
+            | Self::Direct(enable)
+            | Self::Enhancer(enable)
+            | Self::BassExtension(enable)
+            | Self::ExtraBass(enable)
+            | Self::AdaptiveDrc(enable)
+            | Self::ClearVoice(enable)
+            | Self::Surround3d(enable) => vec![("enable", enable.to_string())],
+        }
+    }
+
+    /// Chemin de l'endpoint pour la zone donnée (ex: `zone2/setInput`)
+    pub fn path(&self, zone: &Zone) -> String {
+        format!("{zone}/{}", self.function())
+    }
+
+    /// Effectue l'appel sur une zone via le setter correspondant
+    pub async fn send(&self, amp: &YamahaAmpAsync, zone: &Zone) -> Result<(), YamahaError> {
+        let zone = zone.clone();
+        match self {
+            Self::Power(power) => amp.set_zone_power(zone, power.clone()).await,
+            Self::Mute(mute) => amp.set_zone_mute(zone, *mute).await,
+            Self::Input(input) => amp.set_zone_input(zone, input.clone()).await,
+            Self::PureDirect(enable) => amp.set_zone_pure_direct(zone, *enable).await,
+            Self::SoundProgram(program) => amp.set_zone_sound_program(zone, program.clone()).await,
+            Self::SurroundDecoderType(decoder) => {
+                amp.set_zone_surround_decoder_type(zone, decoder.clone())
+                    .await
+            }
+            Self::Direct(enable) => amp.set_zone_direct(zone, *enable).await,
+            Self::Enhancer(enable) => amp.set_zone_enhancer(zone, *enable).await,
+            Self::ToneControl { mode, bass, treble } => {
+                amp.set_zone_tone_control(zone, mode.clone(), *bass, *treble)
+                    .await
+            }
+            Self::Equalizer {
+                mode,
+                low,
+                mid,
+                high,
+            } => amp.set_zone_equalizer(zone, mode, *low, *mid, *high).await,
+            Self::DialogueLevel(level) => amp.set_zone_dialogue_level(zone, *level).await,
+            Self::SubwooferVolume(volume) => amp.set_zone_subwoofer_volume(zone, *volume).await,
+            Self::Balance(balance) => amp.set_zone_balance(zone, *balance).await,
+            Self::BassExtension(enable) => amp.set_zone_bass_extension(zone, *enable).await,
+            Self::ExtraBass(enable) => amp.set_zone_extra_bass(zone, *enable).await,
+            Self::AdaptiveDrc(enable) => amp.set_zone_adaptive_drc(zone, *enable).await,
+            Self::ClearVoice(enable) => amp.set_zone_clear_voice(zone, *enable).await,
+            Self::Surround3d(enable) => amp.set_zone_surround_3d(zone, *enable).await,
+            Self::LinkControl(control) => amp.set_zone_link_control(zone, control.clone()).await,
+            Self::LinkAudioDelay(delay) => amp.set_zone_link_audio_delay(zone, delay).await,
+            Self::Volume(volume) => amp.set_zone_volume(zone, *volume).await,
+        }?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SceneCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.function())?;
+        for (i, (key, value)) in self.params().iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            write!(f, "{separator}{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene {
+    /// Construit une scène à partir du statut d'une zone
+    ///
+    /// # Arguments
+    /// * `name` - Nom de la scène
+    /// * `zone` - Zone dont provient le statut
+    /// * `status` - Réponse de `getStatus` pour cette zone
+    pub fn from_status(name: impl Into<String>, zone: Zone, status: &GetStatus) -> Self {
+        Self {
+            name: name.into(),
+            zone,
+            power: status.power.clone(),
+            input: status.input.clone(),
+            volume: status.volume,
+            mute: status.mute,
+            sound_program: status.sound_program.clone(),
+            surr_decoder_type: status.surr_decoder_type.clone(),
+            pure_direct: status.pure_direct,
+            direct: status.direct,
+            enhancer: status.enhancer,
+            tone_control: status.tone_control.clone(),
+            equalizer: status.equalizer.clone(),
+            dialogue_level: status.dialogue_level,
+            subwoofer_volume: status.subwoofer_volume,
+            balance: status.balance,
+            bass_extension: status.bass_extension,
+            extra_bass: status.extra_bass,
+            adaptive_drc: status.adaptive_drc,
+            clear_voice: status.clear_voice,
+            surround_3d: status.surround_3d,
+            link_control: status.link_control.clone(),
+            link_audio_delay: status.link_audio_delay.clone(),
+        }
+    }
+
+    /// Capture l'état actuel d'une zone de l'amplificateur
+    ///
+    /// # Arguments
+    /// * `amp` - Amplificateur à interroger
+    /// * `name` - Nom de la scène
+    /// * `zone` - Zone à capturer
+    pub async fn capture(
+        amp: &YamahaAmpAsync,
+        name: impl Into<String>,
+        zone: Zone,
+    ) -> Result<Self, YamahaError> {
+        let status: GetStatus = amp.fetch(&format!("{zone}/getStatus")).await?;
+        Ok(Self::from_status(name, zone, &status))
+    }
+
+    /// Liste les appels nécessaires pour passer de `current` à cette scène, dans l'ordre
+    /// où ils doivent être effectués
+    ///
+    /// L'alimentation vient en premier et le volume en dernier. Une coupure du son a lieu
+    /// dès la mise sous tension, son rétablissement juste avant le volume. `pure_direct`
+    /// est désactivé avant le choix du programme sonore et activé après. Une scène en
+    /// veille se limite à la mise en veille.
+    ///
+    /// # Returns
+    /// * `Vec<SceneCall>` - Appels à effectuer, vide si la zone est déjà dans l'état voulu
+    pub fn plan(&self, current: &GetStatus) -> Vec<SceneCall> {
+        let mut calls = Vec::new();
+        if self.power != current.power {
+            calls.push(SceneCall::Power(self.power.clone()));
+        }
+        if self.power == PowerState::Standby {
+            return calls;
+        }
+
+        let changed = |wanted: &Option<bool>, current: Option<bool>| {
+            wanted.filter(|wanted| current != Some(*wanted))
+        };
+        if self.mute && !current.mute {
+            calls.push(SceneCall::Mute(true));
+        }
+        if self.input != current.input {
+            calls.push(SceneCall::Input(self.input.clone()));
+        }
+        let pure_direct = changed(&self.pure_direct, current.pure_direct);
+        if pure_direct == Some(false) {
+            calls.push(SceneCall::PureDirect(false));
+        }
+        if let Some(program) = &self.sound_program
+            && current.sound_program.as_ref() != Some(program)
+        {
+            calls.push(SceneCall::SoundProgram(program.clone()));
+        }
+        if let Some(decoder) = &self.surr_decoder_type
+            && current.surr_decoder_type.as_ref() != Some(decoder)
+        {
+            calls.push(SceneCall::SurroundDecoderType(decoder.clone()));
+        }
+        if pure_direct == Some(true) {
+            calls.push(SceneCall::PureDirect(true));
+        }
+        if let Some(direct) = changed(&self.direct, current.direct) {
+            calls.push(SceneCall::Direct(direct));
+        }
+        if let Some(enhancer) = changed(&self.enhancer, current.enhancer) {
+            calls.push(SceneCall::Enhancer(enhancer));
+        }
+        if let Some(tone) = &self.tone_control
+            && current.tone_control.as_ref().is_none_or(|current| {
+                (&current.mode, current.bass, current.treble)
+                    != (&tone.mode, tone.bass, tone.treble)
+            })
+        {
+            calls.push(SceneCall::ToneControl {
+                mode: tone.mode.clone(),
+                bass: tone.bass,
+                treble: tone.treble,
+            });
+        }
+        if let Some(equalizer) = &self.equalizer
+            && current.equalizer.as_ref().is_none_or(|current| {
+                (&current.mode, current.low, current.mid, current.high)
+                    != (
+                        &equalizer.mode,
+                        equalizer.low,
+                        equalizer.mid,
+                        equalizer.high,
+                    )
+            })
+        {
+            calls.push(SceneCall::Equalizer {
+                mode: equalizer.mode.clone(),
+                low: equalizer.low,
+                mid: equalizer.mid,
+                high: equalizer.high,
+            });
+        }
+        let levels = [
+            (
+                SceneCall::DialogueLevel as fn(i64) -> SceneCall,
+                self.dialogue_level,
+                current.dialogue_level,
+            ),
+            (
+                SceneCall::SubwooferVolume,
+                self.subwoofer_volume,
+                current.subwoofer_volume,
+            ),
+            (SceneCall::Balance, self.balance, current.balance),
+        ];
+        for (call, wanted, current) in levels {
+            if let Some(wanted) = wanted
+                && current != Some(wanted)
+            {
+                calls.push(call(wanted));
+            }
+        }
+        let switches = [
+            (
+                SceneCall::BassExtension as fn(bool) -> SceneCall,
+                self.bass_extension,
+                current.bass_extension,
+            ),
+            (SceneCall::ExtraBass, self.extra_bass, current.extra_bass),
+            (
+                SceneCall::AdaptiveDrc,
+                self.adaptive_drc,
+                current.adaptive_drc,
+            ),
+            (SceneCall::ClearVoice, self.clear_voice, current.clear_voice),
+            (SceneCall::Surround3d, self.surround_3d, current.surround_3d),
+        ];
+        for (call, wanted, current) in switches {
+            if let Some(enable) = changed(&wanted, current) {
+                calls.push(call(enable));
+            }
+        }
+        if let Some(control) = &self.link_control
+            && current.link_control.as_ref() != Some(control)
+        {
+            calls.push(SceneCall::LinkControl(control.clone()));
+        }
+        if let Some(delay) = &self.link_audio_delay
+            && current.link_audio_delay.as_ref() != Some(delay)
+        {
+            calls.push(SceneCall::LinkAudioDelay(delay.clone()));
+        }
+        if !self.mute && current.mute {
+            calls.push(SceneCall::Mute(false));
+        }
+        if self.volume != current.volume {
+            calls.push(SceneCall::Volume(self.volume));
+        }
+        calls
+    }
+
+    /// Applique la scène à une zone, en n'effectuant que les appels nécessaires
+    ///
+    /// Le statut est relu après un changement d'alimentation, l'appareil restaurant
+    /// lui-même une partie de ses réglages à la mise sous tension.
+    ///
+    /// # Arguments
+    /// * `amp` - Amplificateur cible
+    /// * `zone` - Zone cible, pas forcément celle d'où provient la scène
+    ///
+    /// # Returns
+    /// * `Result<Vec<SceneCall>, YamahaError>` - Appels effectués, dans l'ordre
+    pub async fn apply(
+        &self,
+        amp: &YamahaAmpAsync,
+        zone: &Zone,
+    ) -> Result<Vec<SceneCall>, YamahaError> {
+        let mut done = Vec::new();
+        let mut calls = self.plan(&read_status(amp, zone).await?);
+        if calls
+            .first()
+            .is_some_and(|call| matches!(call, SceneCall::Power(_)))
+        {
+            let power = calls.remove(0);
+            power.send(amp, zone).await?;
+            done.push(power);
+            calls = self.plan(&read_status(amp, zone).await?);
+        }
+        for scene_call in calls {
+            scene_call.send(amp, zone).await?;
+            done.push(scene_call);
+        }
+        Ok(done)
+    }
+}
+
+async fn read_status(amp: &YamahaAmpAsync, zone: &Zone) -> Result<GetStatus, YamahaError> {
+    amp.fetch(&format!("{zone}/getStatus")).await
+}
+
+/// Répertoire de scènes, un fichier JSON `<nom>.json` par scène
+#[derive(Debug, Clone)]
+pub struct SceneStore {
+    dir: PathBuf,
+}
+
+impl SceneStore {
+    /// Crée un répertoire de scènes, sans toucher au disque
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, YamahaError> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(YamahaError::Other(format!(
+                "Invalid scene name `{name}`: use letters, digits, `-` and `_`"
+            )));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+
+    /// Noms des scènes enregistrées, triés
+    pub fn list(&self) -> Result<Vec<String>, YamahaError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Scene, YamahaError> {
+        let text = std::fs::read_to_string(self.path(name)?)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Enregistre la scène sous son nom, en remplaçant une scène existante
+    pub fn save(&self, scene: &Scene) -> Result<(), YamahaError> {
+        let path = self.path(&scene.name)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(path, serde_json::to_string_pretty(scene)?)?;
+        Ok(())
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), YamahaError> {
+        Ok(std::fs::remove_file(self.path(name)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(value: serde_json::Value) -> GetStatus {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn plan_orders_power_first_and_volume_last() {
+        let current = status(serde_json::json!({
+            "power": "standby", "volume": 20, "mute": false, "input": "hdmi1",
+            "sound_program": "straight", "pure_direct": true, "enhancer": false,
+        }));
+        let mut scene = Scene::from_status("movie", Zone::Main, &current);
+        assert!(scene.plan(&current).is_empty());
+
+        scene.power = PowerState::On;
+        scene.volume = 90;
+        scene.mute = true;
+        scene.input = Input::Hdmi2;
+        scene.sound_program = Some(SoundProgram::SciFi);
+        scene.pure_direct = Some(false);
+        scene.enhancer = Some(true);
+        let functions: Vec<&str> = scene
+            .plan(&current)
+            .iter()
+            .map(SceneCall::function)
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                "setPower",
+                "setMute",
+                "setInput",
+                "setPureDirect",
+                "setSoundProgram",
+                "setEnhancer",
+                "setVolume"
+            ]
+        );
+
+        scene.power = PowerState::Standby;
+        let calls = scene.plan(&status(serde_json::json!({ "power": "on" })));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].to_string(), "setPower?power=standby");
+    }
+
+    #[test]
+    fn store_round_trip_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SceneStore::new(dir.path());
+        assert!(store.list().unwrap().is_empty());
+
+        let scene = Scene::from_status(
+            "late-night",
+            Zone::Zone2,
+            &status(serde_json::json!({ "power": "on", "volume": 30, "dialogue_level": 2 })),
+        );
+        store.save(&scene).unwrap();
+        assert_eq!(store.list().unwrap(), ["late-night"]);
+        assert_eq!(store.load("late-night").unwrap(), scene);
+        assert!(store.load("../config").is_err());
+
+        store.remove("late-night").unwrap();
+        assert!(store.list().unwrap().is_empty());
+    }
+}
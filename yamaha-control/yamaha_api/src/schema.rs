@@ -0,0 +1,194 @@
+use crate::error::YamahaError;
+use crate::json_data::*;
+use serde::de::DeserializeOwned;
+
+/// Détection des champs renvoyés par l'appareil mais inconnus des structures de `json_data`
+///
+/// Les structures sont décodées de manière tolérante : les champs absents prennent leur
+/// valeur par défaut et les champs inconnus sont conservés dans `extra`. Ce trait liste
+/// ces derniers pour signaler une évolution du firmware.
+pub trait SchemaDrift {
+    /// Ajoute à `out` les chemins des champs inconnus, préfixés par `path`
+    fn collect_unknown(&self, path: &str, out: &mut Vec<String>);
+
+    /// Chemins des champs inconnus (ex: `zone[0].audio_select_list`)
+    fn unknown_fields(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.collect_unknown("", &mut out);
+        out
+    }
+}
+
+impl<T: SchemaDrift> SchemaDrift for Option<T> {
+    fn collect_unknown(&self, path: &str, out: &mut Vec<String>) {
+        if let Some(value) = self {
+            value.collect_unknown(path, out);
+        }
+    }
+}
+
+impl<T: SchemaDrift> SchemaDrift for Vec<T> {
+    fn collect_unknown(&self, path: &str, out: &mut Vec<String>) {
+        for (i, value) in self.iter().enumerate() {
+            value.collect_unknown(&format!("{path}[{i}]"), out);
+        }
+    }
+}
+
+/// Décode une réponse en mode strict : échoue si elle contient des champs inconnus
+///
+/// # Arguments
+/// * `value` - Réponse renvoyée par l'un des clients (ex: `get_main_status`)
+///
+/// # Returns
+/// * `Result<T, YamahaError>` - La structure décodée, ou [`YamahaError::SchemaDrift`]
+///   listant les champs inconnus
+pub fn decode_strict<T: DeserializeOwned + SchemaDrift>(
+    value: serde_json::Value,
+) -> Result<T, YamahaError> {
+    let decoded = serde_json::from_value::<T>(value)?;
+    let fields = decoded.unknown_fields();
+    if fields.is_empty() {
+        Ok(decoded)
+    } else {
+        Err(YamahaError::SchemaDrift {
+            type_name: short_type_name::<T>(),
+            fields,
+        })
+    }
+}
+
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// Implémente [`SchemaDrift`] pour une structure possédant un champ `extra`, en
+/// parcourant les champs imbriqués listés entre accolades
+macro_rules! impl_schema_drift {
+    ($($ty:ident { $($field:ident),* })*) => {
+        $(
+            impl SchemaDrift for $ty {
+                fn collect_unknown(&self, path: &str, out: &mut Vec<String>) {
+                    out.extend(self.extra.keys().map(|key| join(path, key)));
+                    $(self.$field.collect_unknown(&join(path, stringify!($field)), out);)*
+                }
+            }
+        )*
+    };
+}
+
+impl_schema_drift! {
+    GetDeviceInfo { analytics_info }
+    AnalyticsInfo {}
+    GetFeatures { ccs, distribution, netusb, system, tuner, zone }
+    Ccs {}
+    Distribution { mc_surround }
+    McSurround { master_role, slave_role }
+    MasterRole {}
+    SlaveRole {}
+    Netusb { mc_playlist, play_queue, preset, qobuz, recent_info, tidal }
+    McPlaylist {}
+    PlayQueue {}
+    Preset {}
+    Qobuz {}
+    RecentInfo {}
+    Tidal {}
+    System { bluetooth, input_list, speaker_settings }
+    SpeakerSettings {}
+    Bluetooth {}
+    InputList {}
+    Tuner { preset, range_step }
+    Preset2 {}
+    RangeStep {}
+    GetFeaturesZone { range_step }
+    RangeStep2 {}
+    GetNetworkStatus { ipv6, mac_address, musiccast_network, wireless_lan }
+    Ipv6 {}
+    MacAddress {}
+    MusiccastNetwork { dfs }
+    Dfs {}
+    WirelessLan {}
+    GetBluetoothInfo { bluetooth_device }
+    BluetoothDevice {}
+    GetBluetoothDeviceList { device_list }
+    DeviceList {}
+    GetFuncStatus {}
+    GetNameTextWithoutID { input_list, sound_program_list, zone_list }
+    NameTextInputList {}
+    SoundProgramList {}
+    ZoneList {}
+    GetNameTextWithID {}
+    GetStatus { actual_volume, equalizer, tone_control }
+    ActualVolume {}
+    ToneControl {}
+    Equalizer {}
+    GetSoundProgramList {}
+    GetSignalInfo { audio, video }
+    Audio {}
+    Video {}
+    GetNetusbPlayInfo {}
+    GetTunerPlayInfo { am, fm, rds }
+    TunerBand {}
+    Rds {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn missing_fields_default_and_unknown_fields_are_kept() {
+        let status: GetStatus = serde_json::from_value(json!({
+            "power": "on",
+            "volume": 30,
+            "mute": false,
+            "input": "tv",
+            "surround_3d": true,
+            "new_feature": 1,
+            "tone_control": { "mode": "manual", "bass": 1, "treble": 0, "balance": 2 }
+        }))
+        .unwrap();
+
+        assert_eq!(status.volume, 30);
+        assert_eq!(status.pure_direct, None);
+        assert_eq!(status.surround_3d, Some(true));
+        assert_eq!(status.extra["new_feature"], 1);
+        assert_eq!(
+            status.unknown_fields(),
+            ["new_feature", "tone_control.balance"]
+        );
+    }
+
+    #[test]
+    fn strict_mode_reports_drift() {
+        let features = json!({
+            "system": { "zone_num": 1, "input_list": [] },
+            "zone": [{ "id": "main", "func_list": [], "input_list": [], "range_step": [], "mystery": [] }]
+        });
+        let err = decode_strict::<GetFeatures>(features.clone()).unwrap_err();
+        match err {
+            YamahaError::SchemaDrift { type_name, fields } => {
+                assert_eq!(type_name, "GetFeatures");
+                assert_eq!(fields, ["zone[0].mystery"]);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let mut features = features;
+        features["zone"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("mystery");
+        assert!(decode_strict::<GetFeatures>(features).is_ok());
+    }
+}
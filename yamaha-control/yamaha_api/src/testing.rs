@@ -0,0 +1,1324 @@
+use crate::address::AmpAddress;
+use crate::builder::YamahaAmpBuilder;
+use crate::common_api::API_BASE_PATH;
+use crate::error::YamahaError;
+use crate::model::{Input, PowerState, SoundProgram, SurrDecoderType, Zone};
+use crate::retry::RetryPolicy;
+use percent_encoding::percent_decode_str;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::task::JoinHandle;
+
+/// Chemin de la description UPnP annoncée en réponse aux requêtes SSDP
+pub const DESCRIPTION_PATH: &str = "MediaRenderer/desc.xml";
+
+/// Configuration d'un appareil simulé
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockConfig {
+    /// Nom du modèle renvoyé par `getDeviceInfo`
+    pub model_name: String,
+    /// Identifiant unique de l'appareil
+    pub device_id: String,
+    /// Version de l'API annoncée
+    pub api_version: f32,
+    /// Version du firmware annoncée
+    pub system_version: f32,
+    /// Zones disponibles, la première étant la zone principale
+    pub zones: Vec<Zone>,
+    /// Entrées sélectionnables
+    pub inputs: Vec<Input>,
+    /// Programmes sonores disponibles
+    pub sound_programs: Vec<SoundProgram>,
+    /// Volume maximum (pas de 0.5 dB à partir de -80.5 dB)
+    pub max_volume: i64,
+    /// Présence d'un tuner FM/AM
+    pub tuner: bool,
+}
+
+impl Default for MockConfig {
+    /// Amplificateur audio-vidéo deux zones, comparable à un RX-V6A
+    fn default() -> Self {
+        Self {
+            model_name: "RX-V6A".into(),
+            device_id: "00A0DE000001".into(),
+            api_version: 2.11,
+            system_version: 1.7,
+            zones: vec![Zone::Main, Zone::Zone2],
+            inputs: vec![
+                Input::Hdmi1,
+                Input::Hdmi2,
+                Input::Hdmi3,
+                Input::Hdmi4,
+                Input::Av1,
+                Input::Audio1,
+                Input::Optical1,
+                Input::Tuner,
+                Input::Bluetooth,
+                Input::Server,
+                Input::NetRadio,
+                Input::Spotify,
+                Input::Airplay,
+                Input::Usb,
+            ],
+            sound_programs: vec![
+                SoundProgram::Munich,
+                SoundProgram::Vienna,
+                SoundProgram::Chamber,
+                SoundProgram::CellarClub,
+                SoundProgram::RoxyTheatre,
+                SoundProgram::BottomLine,
+                SoundProgram::Sports,
+                SoundProgram::ActionGame,
+                SoundProgram::RoleplayingGame,
+                SoundProgram::MusicVideo,
+                SoundProgram::Standard,
+                SoundProgram::Spectacle,
+                SoundProgram::SciFi,
+                SoundProgram::Adventure,
+                SoundProgram::Drama,
+                SoundProgram::MonoMovie,
+                SoundProgram::Ch2Stereo,
+                SoundProgram::Ch7Stereo,
+                SoundProgram::SurrDecoder,
+                SoundProgram::Straight,
+            ],
+            max_volume: 161,
+            tuner: true,
+        }
+    }
+}
+
+impl MockConfig {
+    /// Barre de son une zone sans tuner, comparable à une YAS-209
+    pub fn sound_bar() -> Self {
+        Self {
+            model_name: "YAS-209".into(),
+            device_id: "00A0DE000002".into(),
+            api_version: 2.08,
+            system_version: 2.1,
+            zones: vec![Zone::Main],
+            inputs: vec![
+                Input::Hdmi,
+                Input::Tv,
+                Input::Analog,
+                Input::Bluetooth,
+                Input::NetRadio,
+                Input::Server,
+                Input::Spotify,
+                Input::Airplay,
+            ],
+            sound_programs: vec![
+                SoundProgram::Movie,
+                SoundProgram::Music,
+                SoundProgram::Sports,
+                SoundProgram::Game,
+                SoundProgram::TvProgram,
+                SoundProgram::Stereo,
+            ],
+            max_volume: 100,
+            tuner: false,
+        }
+    }
+}
+
+/// État simulé d'une zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockZone {
+    pub power: PowerState,
+    pub volume: i64,
+    pub mute: bool,
+    pub input: Input,
+    pub sound_program: SoundProgram,
+    pub surr_decoder_type: SurrDecoderType,
+    pub direct: bool,
+    pub pure_direct: bool,
+    pub enhancer: bool,
+    pub extra_bass: bool,
+    pub bass_extension: bool,
+    pub adaptive_drc: bool,
+    pub dialogue_level: i64,
+    pub subwoofer_volume: i64,
+    pub sleep: i64,
+}
+
+/// État simulé de la lecture réseau/USB
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockNetusb {
+    pub input: Input,
+    pub playback: String,
+    pub repeat: String,
+    pub shuffle: String,
+    pub artist: String,
+    pub album: String,
+    pub track: String,
+    pub play_time: i64,
+    pub total_time: i64,
+}
+
+impl Default for MockNetusb {
+    fn default() -> Self {
+        Self {
+            input: Input::NetRadio,
+            playback: "stop".into(),
+            repeat: "off".into(),
+            shuffle: "off".into(),
+            artist: String::new(),
+            album: String::new(),
+            track: String::new(),
+            play_time: 0,
+            total_time: 0,
+        }
+    }
+}
+
+/// État simulé du tuner
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockTuner {
+    /// Bande écoutée (`fm` ou `am`)
+    pub band: String,
+    /// Fréquence écoutée, en kHz
+    pub freq: i64,
+    /// Présélections communes (bande, fréquence), `("unknown", 0)` pour un emplacement vide
+    pub presets: Vec<(String, i64)>,
+}
+
+impl Default for MockTuner {
+    fn default() -> Self {
+        let mut presets = vec![("unknown".to_string(), 0); 8];
+        presets[0] = ("fm".into(), 101700);
+        Self {
+            band: "fm".into(),
+            freq: 101700,
+            presets,
+        }
+    }
+}
+
+/// État complet de l'appareil simulé
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockState {
+    /// État de chaque zone configurée
+    pub zones: HashMap<Zone, MockZone>,
+    /// Lecture réseau/USB
+    pub netusb: MockNetusb,
+    /// Noms personnalisés définis via `setNameText`, par identifiant
+    pub names: HashMap<String, String>,
+    /// Réglages renvoyés par `system/getFuncStatus`, modifiés par les setters `system/set*`
+    pub functions: serde_json::Map<String, Value>,
+    /// Réglages renvoyés par `clock/getSettings`
+    pub clock: serde_json::Map<String, Value>,
+    /// Présélections réseau (entrée, nom), un nom vide pour un emplacement libre
+    pub netusb_presets: Vec<(Input, String)>,
+    pub tuner: MockTuner,
+}
+
+impl MockState {
+    fn new(config: &MockConfig) -> Self {
+        let input = config.inputs.first().cloned().unwrap_or_default();
+        let sound_program = config.sound_programs.first().cloned().unwrap_or_default();
+        let zones = config
+            .zones
+            .iter()
+            .map(|zone| {
+                let state = MockZone {
+                    power: PowerState::Standby,
+                    volume: config.max_volume / 4,
+                    mute: false,
+                    input: input.clone(),
+                    sound_program: sound_program.clone(),
+                    surr_decoder_type: SurrDecoderType::Auto,
+                    direct: false,
+                    pure_direct: false,
+                    enhancer: true,
+                    extra_bass: false,
+                    bass_extension: false,
+                    adaptive_drc: false,
+                    dialogue_level: 0,
+                    subwoofer_volume: 0,
+                    sleep: 0,
+                };
+                (zone.clone(), state)
+            })
+            .collect();
+        let mut netusb_presets = vec![(Input::Unknown("unknown".into()), String::new()); 8];
+        netusb_presets[0] = (Input::NetRadio, "FIP".into());
+        Self {
+            zones,
+            netusb: MockNetusb::default(),
+            names: HashMap::new(),
+            functions: object(json!({
+                "auto_power_standby": true,
+                "hdmi_out_1": true,
+                "hdmi_standby_through": "auto",
+                "speaker_pattern": 1,
+                "dimmer": -1
+            })),
+            clock: object(json!({
+                "auto_sync": true,
+                "format": "24h",
+                "alarm_on": false,
+                "volume": 40,
+                "fade_interval": 0,
+                "mode": "oneday"
+            })),
+            netusb_presets,
+            tuner: MockTuner::default(),
+        }
+    }
+}
+
+/// Défaillance injectée sur un endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Réponse HTTP 200 avec le `response_code` spécifié
+    Code(i32),
+    /// Réponse HTTP avec le statut spécifié et un corps vide
+    Http(u16),
+    /// Réponse HTTP 200 avec un corps arbitraire
+    Body(String),
+    /// Réponse normale envoyée après le délai spécifié
+    Delay(Duration),
+}
+
+/// Appareil Yamaha Extended Control simulé, servant l'API HTTP sur `127.0.0.1`
+///
+/// L'appareil conserve son état (alimentation, volume, entrée, zones, lecture réseau),
+/// répond avec les mêmes JSON et codes d'erreur qu'un amplificateur réel, pousse des
+/// événements UDP aux clients abonnés via `X-AppName`/`X-AppPort` et répond aux requêtes
+/// SSDP `M-SEARCH` reçues sur [`MockDevice::ssdp_address`].
+///
+/// Les tâches du serveur s'exécutent sur le runtime tokio courant et sont arrêtées à la
+/// destruction de l'instance. Pour tester l'API synchrone, démarrez l'appareil dans un
+/// runtime multi-thread conservé pendant toute la durée du test.
+#[derive(Debug)]
+pub struct MockDevice {
+    http: SocketAddr,
+    ssdp: SocketAddr,
+    shared: Arc<Shared>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: MockConfig,
+    inner: Mutex<Inner>,
+    events: std::net::UdpSocket,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: MockState,
+    faults: HashMap<String, VecDeque<Fault>>,
+    recorded: HashMap<String, String>,
+    requests: Vec<String>,
+    subscribers: HashSet<SocketAddr>,
+}
+
+impl MockDevice {
+    /// Démarre un appareil simulé avec la configuration par défaut
+    pub async fn start() -> Result<Self, YamahaError> {
+        Self::start_with(MockConfig::default()).await
+    }
+
+    /// Démarre un appareil simulé avec la configuration spécifiée
+    ///
+    /// # Returns
+    /// * `Result<MockDevice, YamahaError>` - Appareil à l'écoute sur des ports choisis par le système
+    pub async fn start_with(config: MockConfig) -> Result<Self, YamahaError> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let ssdp_socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let events = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let http = listener.local_addr()?;
+        let ssdp = ssdp_socket.local_addr()?;
+
+        let shared = Arc::new(Shared {
+            inner: Mutex::new(Inner {
+                state: MockState::new(&config),
+                faults: HashMap::new(),
+                recorded: HashMap::new(),
+                requests: Vec::new(),
+                subscribers: HashSet::new(),
+            }),
+            config,
+            events,
+        });
+
+        let tasks = vec![
+            tokio::spawn(serve_http(Arc::clone(&shared), listener)),
+            tokio::spawn(serve_ssdp(Arc::clone(&shared), ssdp_socket, http)),
+        ];
+
+        Ok(Self {
+            http,
+            ssdp,
+            shared,
+            tasks,
+        })
+    }
+
+    /// Adresse HTTP de l'appareil
+    pub fn address(&self) -> AmpAddress {
+        AmpAddress::new(self.http.ip(), self.http.port())
+    }
+
+    /// Adresse UDP à laquelle envoyer les requêtes SSDP `M-SEARCH`
+    pub fn ssdp_address(&self) -> SocketAddr {
+        self.ssdp
+    }
+
+    /// Configuration de l'appareil
+    pub fn config(&self) -> &MockConfig {
+        &self.shared.config
+    }
+
+    /// Constructeur pointant vers l'appareil, sans relance pour des tests déterministes
+    pub fn builder(&self) -> YamahaAmpBuilder {
+        YamahaAmpBuilder::from_address(self.address()).retry_policy(RetryPolicy::none())
+    }
+
+    /// Copie de l'état courant de l'appareil
+    pub fn state(&self) -> MockState {
+        self.shared.lock().state.clone()
+    }
+
+    /// Modifie l'état comme le ferait la télécommande ou la façade de l'appareil
+    ///
+    /// Les abonnés reçoivent un événement `status_updated`/`signal_info_updated`/
+    /// `play_info_updated` pour chaque partie modifiée.
+    pub fn update(&self, modify: impl FnOnce(&mut MockState)) {
+        let mut inner = self.shared.lock();
+        let before = inner.state.clone();
+        modify(&mut inner.state);
+
+        let mut event = serde_json::Map::new();
+        for (zone, status) in &inner.state.zones {
+            let previous = before.zones.get(zone);
+            if previous == Some(status) {
+                continue;
+            }
+            let mut changes = json!({ "status_updated": true });
+            // Le signal reçu ne dépend que de l'alimentation et de l'entrée de la zone principale
+            if *zone == Zone::Main
+                && previous.is_none_or(|previous| {
+                    previous.power != status.power || previous.input != status.input
+                })
+            {
+                changes["signal_info_updated"] = json!(true);
+            }
+            event.insert(zone.to_string(), changes);
+        }
+        if before.netusb != inner.state.netusb {
+            event.insert("netusb".into(), json!({ "play_info_updated": true }));
+        }
+        if !event.is_empty() {
+            self.shared.emit(&inner, Value::Object(event));
+        }
+    }
+
+    /// Injecte une défaillance pour les `times` prochaines requêtes vers `path`
+    ///
+    /// # Arguments
+    /// * `path` - Chemin de l'endpoint, sans paramètres (ex: `main/setVolume`)
+    /// * `fault` - Défaillance à simuler
+    /// * `times` - Nombre de requêtes concernées
+    pub fn fail_next(&self, path: &str, fault: Fault, times: usize) {
+        let mut inner = self.shared.lock();
+        let queue = inner.faults.entry(path.to_string()).or_default();
+        queue.extend(std::iter::repeat_n(fault, times));
+    }
+
+    /// Remplace durablement la réponse de `path` par un corps enregistré sur un appareil réel
+    ///
+    /// Les défaillances injectées via [`Self::fail_next`] restent prioritaires.
+    pub fn respond_with(&self, path: &str, body: impl Into<String>) {
+        let mut inner = self.shared.lock();
+        inner.recorded.insert(path.to_string(), body.into());
+    }
+
+    /// Chemins reçus depuis le démarrage, paramètres décodés inclus (ex: `main/setVolume?volume=40`)
+    pub fn requests(&self) -> Vec<String> {
+        self.shared.lock().requests.clone()
+    }
+
+    /// Clients abonnés aux événements UDP
+    pub fn subscribers(&self) -> Vec<SocketAddr> {
+        self.shared.lock().subscribers.iter().copied().collect()
+    }
+
+    /// Envoie un événement arbitraire à tous les abonnés
+    ///
+    /// Le champ `device_id` est ajouté s'il est absent.
+    pub fn emit_event(&self, payload: Value) {
+        let inner = self.shared.lock();
+        self.shared.emit(&inner, payload);
+    }
+}
+
+impl Drop for MockDevice {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn emit(&self, inner: &Inner, mut payload: Value) {
+        if let Some(object) = payload.as_object_mut() {
+            object
+                .entry("device_id")
+                .or_insert_with(|| Value::String(self.config.device_id.clone()));
+        }
+        let bytes = payload.to_string();
+        for subscriber in &inner.subscribers {
+            let _ = self.events.send_to(bytes.as_bytes(), subscriber);
+        }
+    }
+
+    /// Traite une requête de l'API et renvoie le corps JSON de la réponse
+    fn handle(&self, inner: &mut Inner, path: &str, params: &HashMap<String, String>) -> Value {
+        let (code, body, event) = match self.dispatch(&mut inner.state, path, params) {
+            Ok((body, event)) => (0, body, event),
+            Err(code) => (code, Value::Null, None),
+        };
+
+        if let Some(event) = event {
+            self.emit(inner, event);
+        }
+
+        let mut response = serde_json::Map::new();
+        response.insert("response_code".into(), json!(code));
+        if let Value::Object(fields) = body {
+            response.extend(fields);
+        }
+        Value::Object(response)
+    }
+
+    /// Exécute l'endpoint demandé
+    ///
+    /// # Returns
+    /// * `Result<(Value, Option<Value>), i32>` - Données de la réponse et événement éventuel,
+    ///   ou `response_code` d'erreur
+    fn dispatch(
+        &self,
+        state: &mut MockState,
+        path: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let (group, endpoint) = path.split_once('/').ok_or(INVALID_REQUEST)?;
+        match group {
+            "system" => self.system(state, endpoint, params),
+            "netusb" => self.netusb(state, endpoint, params),
+            "tuner" if self.config.tuner => self.tuner(state, endpoint, params),
+            "clock" => self.clock(state, endpoint, params),
+            _ => {
+                let zone = group.parse::<Zone>().map_err(|_| INVALID_REQUEST)?;
+                if !self.config.zones.contains(&zone) {
+                    return Err(INVALID_REQUEST);
+                }
+                self.zone(state, &zone, endpoint, params)
+            }
+        }
+    }
+
+    fn system(
+        &self,
+        state: &mut MockState,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let config = &self.config;
+        match endpoint {
+            "getDeviceInfo" => Ok((
+                json!({
+                    "model_name": config.model_name,
+                    "destination": "BG",
+                    "device_id": config.device_id,
+                    "system_id": "0B587073",
+                    "system_version": config.system_version,
+                    "api_version": config.api_version,
+                    "netmodule_generation": 1,
+                    "netmodule_version": "100402",
+                    "netmodule_checksum": "6F1A3C52",
+                    "serial_number": "Y123456AB",
+                    "category_code": 1,
+                    "operation_mode": "normal",
+                    "update_error_code": "00000000",
+                    "net_module_num": 1,
+                    "update_data_type": 0,
+                    "analytics_info": { "uuid": "5c6bd5a0-8f3e-4b1e-9b61-000000000001" }
+                }),
+                None,
+            )),
+            "getFeatures" => Ok((self.features(), None)),
+            "setNameText" => {
+                let id = param(params, "id")?;
+                let text = param(params, "text")?;
+                state.names.insert(id.to_string(), text.to_string());
+                Ok((
+                    Value::Null,
+                    Some(json!({ "system": { "name_text_updated": true } })),
+                ))
+            }
+            "getNameText" => Ok((self.name_text(state), None)),
+            "getFuncStatus" => Ok((Value::Object(state.functions.clone()), None)),
+            _ => {
+                let (key, name) = state
+                    .functions
+                    .keys()
+                    .find_map(|key| match crate::backup::system_setter(key) {
+                        Some((function, name)) if function == endpoint => Some((key.clone(), name)),
+                        _ => None,
+                    })
+                    .ok_or(INVALID_REQUEST)?;
+                let value = match &state.functions[&key] {
+                    Value::Bool(_) => json!(bool_param(params, name)?),
+                    Value::Number(_) => json!(int_param(params, name, -100..=100)?),
+                    _ => return Err(INVALID_REQUEST),
+                };
+                state.functions.insert(key, value);
+                Ok((
+                    Value::Null,
+                    Some(json!({ "system": { "func_status_updated": true } })),
+                ))
+            }
+        }
+    }
+
+    /// Corps de `system/getNameText`, les noms par défaut étant ceux de `getStatus`
+    fn name_text(&self, state: &MockState) -> Value {
+        let config = &self.config;
+        let text = |id: &str, default: String| state.names.get(id).cloned().unwrap_or(default);
+        json!({
+            "zone_list": config.zones.iter().map(|zone| json!({
+                "id": zone.as_str(),
+                "text": text(zone.as_str(), zone.as_str().to_uppercase()),
+            })).collect::<Vec<_>>(),
+            "input_list": config.inputs.iter().map(|input| json!({
+                "id": input.as_str(),
+                "text": text(input.as_str(), input.as_str().to_uppercase()),
+            })).collect::<Vec<_>>(),
+            "sound_program_list": config.sound_programs.iter().map(|program| json!({
+                "id": program.as_str(),
+                "text": text(program.as_str(), program.as_str().to_string()),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    fn clock(
+        &self,
+        state: &mut MockState,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let (key, value) = match endpoint {
+            "getSettings" => return Ok((Value::Object(state.clock.clone()), None)),
+            "setAutoSync" => ("auto_sync", json!(bool_param(params, "enable")?)),
+            "setClockFormat" => match param(params, "format")? {
+                format @ ("12h" | "24h") => ("format", json!(format)),
+                _ => return Err(INVALID_PARAMETER),
+            },
+            _ => return Err(INVALID_REQUEST),
+        };
+        state.clock.insert(key.into(), value);
+        Ok((
+            Value::Null,
+            Some(json!({ "clock": { "settings_updated": true } })),
+        ))
+    }
+
+    fn features(&self) -> Value {
+        let config = &self.config;
+        let inputs: Vec<&str> = config.inputs.iter().map(Input::as_str).collect();
+        let programs: Vec<&str> = config
+            .sound_programs
+            .iter()
+            .map(SoundProgram::as_str)
+            .collect();
+        let zones: Vec<Value> = config
+            .zones
+            .iter()
+            .map(|zone| {
+                json!({
+                    "id": zone.as_str(),
+                    "func_list": ["power", "sleep", "volume", "mute", "sound_program", "direct",
+                        "enhancer", "tone_control", "dialogue_level", "signal_info",
+                        "prepare_input_change", "link_control", "link_audio_delay"],
+                    "input_list": inputs,
+                    "sound_program_list": programs,
+                    "surr_decoder_type_list": ["auto", "dolby_surround", "dts_neural_x"],
+                    "tone_control_mode_list": ["manual"],
+                    "link_control_list": ["standard", "speed", "stability"],
+                    "link_audio_delay_list": ["audio_sync", "lip_sync"],
+                    "range_step": [
+                        { "id": "volume", "min": 0, "max": config.max_volume, "step": 1 },
+                        { "id": "tone_control", "min": -12, "max": 12, "step": 1 },
+                        { "id": "dialogue_level", "min": 0, "max": 3, "step": 1 }
+                    ],
+                    "scene_num": 8,
+                    "cursor_list": ["up", "down", "left", "right", "select", "return"],
+                    "menu_list": ["on_screen", "top_menu", "menu", "option", "display"],
+                    "actual_volume_mode_list": ["db", "numeric"]
+                })
+            })
+            .collect();
+
+        json!({
+            "system": {
+                "func_list": ["wired_lan", "wireless_lan", "wireless_direct", "network_standby",
+                    "bluetooth_standby", "bluetooth_tx_setting", "auto_power_standby",
+                    "hdmi_out_1", "airplay", "speaker_pattern"],
+                "zone_num": config.zones.len(),
+                "input_list": config.inputs.iter().map(|input| json!({
+                    "id": input.as_str(),
+                    "distribution_enable": true,
+                    "rename_enable": true,
+                    "account_enable": matches!(input, Input::Spotify),
+                    "play_info_type": match input {
+                        Input::Tuner => "tuner",
+                        Input::Server | Input::NetRadio | Input::Spotify
+                            | Input::Airplay | Input::Usb | Input::Bluetooth => "netusb",
+                        _ => "none",
+                    }
+                })).collect::<Vec<_>>(),
+                "bluetooth": { "update_cancelable": false, "tx_connectivity_type_max": 0 },
+                "web_control_url": "/index.html"
+            },
+            "zone": zones,
+            "tuner": {
+                "func_list": if config.tuner { json!(["fm", "am", "rds"]) } else { json!([]) },
+                "range_step": [
+                    { "id": "fm", "min": 87500, "max": 108000, "step": 50 },
+                    { "id": "am", "min": 531, "max": 1611, "step": 9 }
+                ],
+                "preset": { "type": "common", "num": 40 }
+            },
+            "netusb": {
+                "func_list": ["recent_info", "play_queue", "mc_playlist", "streaming_service_use"],
+                "preset": { "num": 40 },
+                "recent_info": { "num": 40 },
+                "play_queue": { "size": 200 },
+                "mc_playlist": { "size": 200, "num": 5 },
+                "net_radio_type": "airable",
+                "tidal": { "mode": "ok" },
+                "qobuz": { "login_type": "email" }
+            },
+            "distribution": {
+                "version": 2.0,
+                "compatible_client": [2],
+                "client_max": 9,
+                "server_zone_list": ["main"],
+                "mc_surround": {
+                    "version": 1.0,
+                    "func_list": ["speaker_a", "speaker_b"],
+                    "master_role": {
+                        "surround_pair": false,
+                        "stereo_pair": false,
+                        "subwoofer_pair": true
+                    },
+                    "slave_role": {
+                        "surround_pair_l_or_r": false,
+                        "surround_pair_lr": false,
+                        "subwoofer_pair": false
+                    }
+                }
+            },
+            "ccs": { "supported": false }
+        })
+    }
+
+    fn zone(
+        &self,
+        state: &mut MockState,
+        zone: &Zone,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let config = &self.config;
+        let names = state.names.clone();
+        let status = state.zones.get_mut(zone).ok_or(INVALID_REQUEST)?;
+        let is_on = status.power == PowerState::On;
+        let changed = |key: &str, value: Value| Some(json!({ zone.as_str(): { key: value } }));
+
+        match endpoint {
+            "getStatus" => Ok((zone_status(config, status, &names), None)),
+            "getSoundProgramList" => Ok((
+                json!({
+                    "sound_program_list": config
+                        .sound_programs
+                        .iter()
+                        .map(SoundProgram::as_str)
+                        .collect::<Vec<_>>()
+                }),
+                None,
+            )),
+            "getSignalInfo" => Ok((
+                json!({
+                    "audio": if is_on {
+                        json!({ "error": 0, "format": "Dolby Digital", "fs": "48 kHz", "bit": "24 bit", "bitrate": 640 })
+                    } else {
+                        json!({ "error": 0, "format": "", "fs": "", "bit": "", "bitrate": 0 })
+                    }
+                }),
+                None,
+            )),
+            "setPower" => {
+                let power = param(params, "power")?
+                    .parse::<PowerState>()
+                    .ok()
+                    .filter(|power| !power.is_unknown())
+                    .ok_or(INVALID_PARAMETER)?;
+                status.power = match power {
+                    PowerState::Toggle if is_on => PowerState::Standby,
+                    PowerState::Toggle => PowerState::On,
+                    power => power,
+                };
+                Ok((Value::Null, changed("power", json!(status.power.as_str()))))
+            }
+            // Comme sur les appareils réels, seul le volume se règle en veille
+            _ if !is_on && endpoint != "setVolume" => Err(GUARDED),
+            "setVolume" | "setActualVolume" => {
+                let volume = match (endpoint, param(params, "volume")) {
+                    ("setActualVolume", _) => db_to_volume(params)?,
+                    (_, Ok("up")) => status.volume + step(params)?,
+                    (_, Ok("down")) => status.volume - step(params)?,
+                    (_, volume) => volume?.parse::<i64>().map_err(|_| INVALID_PARAMETER)?,
+                };
+                if !(0..=config.max_volume).contains(&volume) {
+                    return Err(INVALID_PARAMETER);
+                }
+                status.volume = volume;
+                Ok((Value::Null, changed("volume", json!(volume))))
+            }
+            "setMute" => {
+                status.mute = bool_param(params, "enable")?;
+                Ok((Value::Null, changed("mute", json!(status.mute))))
+            }
+            "setInput" => {
+                let input = param(params, "input")?.parse::<Input>();
+                match input {
+                    Ok(input) if config.inputs.contains(&input) => {
+                        status.input = input;
+                        Ok((Value::Null, changed("input", json!(status.input.as_str()))))
+                    }
+                    _ => Err(INVALID_PARAMETER),
+                }
+            }
+            "setSoundProgram" => {
+                let program = param(params, "program")?.parse::<SoundProgram>();
+                match program {
+                    Ok(program) if config.sound_programs.contains(&program) => {
+                        status.sound_program = program;
+                        Ok((
+                            Value::Null,
+                            changed("sound_program", json!(status.sound_program.as_str())),
+                        ))
+                    }
+                    _ => Err(INVALID_PARAMETER),
+                }
+            }
+            "setDirect" => {
+                status.direct = bool_param(params, "enable")?;
+                Ok((Value::Null, changed("direct", json!(status.direct))))
+            }
+            "setPureDirect" => {
+                status.pure_direct = bool_param(params, "enable")?;
+                Ok((
+                    Value::Null,
+                    changed("pure_direct", json!(status.pure_direct)),
+                ))
+            }
+            "setEnhancer" => {
+                status.enhancer = bool_param(params, "enable")?;
+                Ok((Value::Null, changed("enhancer", json!(status.enhancer))))
+            }
+            "setExtraBass" => {
+                status.extra_bass = bool_param(params, "enable")?;
+                Ok((Value::Null, changed("extra_bass", json!(status.extra_bass))))
+            }
+            "setBassExtension" => {
+                status.bass_extension = bool_param(params, "enable")?;
+                Ok((
+                    Value::Null,
+                    changed("bass_extension", json!(status.bass_extension)),
+                ))
+            }
+            "setAdaptiveDrc" => {
+                status.adaptive_drc = bool_param(params, "enable")?;
+                Ok((
+                    Value::Null,
+                    changed("adaptive_drc", json!(status.adaptive_drc)),
+                ))
+            }
+            "setDialogueLevel" => {
+                status.dialogue_level = int_param(params, "value", 0..=3)?;
+                Ok((
+                    Value::Null,
+                    changed("dialogue_level", json!(status.dialogue_level)),
+                ))
+            }
+            "setSubwooferVolume" => {
+                status.subwoofer_volume = int_param(params, "volume", -12..=12)?;
+                Ok((
+                    Value::Null,
+                    changed("subwoofer_volume", json!(status.subwoofer_volume)),
+                ))
+            }
+            "setSleep" => {
+                status.sleep = int_param(params, "sleep", 0..=120)?;
+                Ok((Value::Null, changed("sleep", json!(status.sleep))))
+            }
+            _ => Err(INVALID_REQUEST),
+        }
+    }
+
+    fn netusb(
+        &self,
+        state: &mut MockState,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let netusb = &mut state.netusb;
+        match endpoint {
+            "getPlayInfo" => Ok((
+                json!({
+                    "input": netusb.input.as_str(),
+                    "play_queue_type": "system",
+                    "playback": netusb.playback,
+                    "repeat": netusb.repeat,
+                    "shuffle": netusb.shuffle,
+                    "play_time": netusb.play_time,
+                    "total_time": netusb.total_time,
+                    "artist": netusb.artist,
+                    "album": netusb.album,
+                    "track": netusb.track,
+                    "albumart_url": "",
+                    "albumart_id": 0,
+                    "usb_devicetype": "unknown",
+                    "attribute": 0
+                }),
+                None,
+            )),
+            "setPlayback" => {
+                let playback = match param(params, "playback")? {
+                    "play" => "play",
+                    "stop" => "stop",
+                    "pause" => "pause",
+                    "play_pause" if netusb.playback == "play" => "pause",
+                    "play_pause" => "play",
+                    "previous" | "next" => netusb.playback.as_str(),
+                    _ => return Err(INVALID_PARAMETER),
+                }
+                .to_string();
+                netusb.playback = playback.clone();
+                if playback == "stop" {
+                    netusb.play_time = 0;
+                }
+                Ok((
+                    Value::Null,
+                    Some(json!({ "netusb": { "play_info_updated": true } })),
+                ))
+            }
+            "getPresetInfo" => Ok((
+                json!({
+                    "preset_info": state.netusb_presets.iter().map(|(input, text)| json!({
+                        "input": input.as_str(),
+                        "text": text,
+                    })).collect::<Vec<_>>(),
+                    "func_list": ["clear", "move"]
+                }),
+                None,
+            )),
+            "recallPreset" => {
+                let zone = param(params, "zone")?
+                    .parse::<Zone>()
+                    .map_err(|_| INVALID_PARAMETER)?;
+                let count = state.netusb_presets.len() as i64;
+                let num = int_param(params, "num", 1..=count)?;
+                let (input, text) = state.netusb_presets[num as usize - 1].clone();
+                let status = state.zones.get_mut(&zone).ok_or(INVALID_PARAMETER)?;
+                if matches!(input, Input::Unknown(_)) {
+                    return Err(INVALID_PARAMETER);
+                }
+                status.input = input.clone();
+                let netusb = &mut state.netusb;
+                netusb.input = input;
+                netusb.track = text;
+                netusb.playback = "play".into();
+                Ok((
+                    Value::Null,
+                    Some(json!({ "netusb": { "play_info_updated": true } })),
+                ))
+            }
+            _ => Err(INVALID_REQUEST),
+        }
+    }
+
+    fn tuner(
+        &self,
+        state: &mut MockState,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<(Value, Option<Value>), i32> {
+        let tuner = &mut state.tuner;
+        let preset = tuner
+            .presets
+            .iter()
+            .position(|preset| *preset == (tuner.band.clone(), tuner.freq))
+            .map_or(0, |index| index + 1);
+        let (fm, am) = match tuner.band.as_str() {
+            "am" => (101700, tuner.freq),
+            _ => (tuner.freq, 531),
+        };
+        match endpoint {
+            "getPresetInfo" => {
+                if param(params, "band")? != "common" {
+                    return Err(INVALID_PARAMETER);
+                }
+                Ok((
+                    json!({
+                        "preset_info": tuner.presets.iter().map(|(band, number)| json!({
+                            "band": band,
+                            "number": number,
+                        })).collect::<Vec<_>>(),
+                        "func_list": ["clear", "move"]
+                    }),
+                    None,
+                ))
+            }
+            "setFreq" => {
+                let band = param(params, "band")?;
+                let range = match band {
+                    "fm" => 87500..=108000,
+                    "am" => 531..=1611,
+                    _ => return Err(INVALID_PARAMETER),
+                };
+                if param(params, "tuning")? != "direct" {
+                    return Err(INVALID_PARAMETER);
+                }
+                tuner.band = band.to_string();
+                tuner.freq = int_param(params, "num", range)?;
+                Ok((
+                    Value::Null,
+                    Some(json!({ "tuner": { "play_info_updated": true } })),
+                ))
+            }
+            "storePreset" => {
+                let count = tuner.presets.len() as i64;
+                let num = int_param(params, "num", 1..=count)?;
+                tuner.presets[num as usize - 1] = (tuner.band.clone(), tuner.freq);
+                Ok((
+                    Value::Null,
+                    Some(json!({ "tuner": { "preset_info_updated": true } })),
+                ))
+            }
+            "getPlayInfo" => Ok((
+                json!({
+                    "band": tuner.band,
+                    "auto_scan": false,
+                    "auto_preset": false,
+                    "am": { "preset": if tuner.band == "am" { preset } else { 0 }, "freq": am, "tuned": tuner.band == "am" },
+                    "fm": { "preset": if tuner.band == "fm" { preset } else { 0 }, "freq": fm, "tuned": tuner.band == "fm", "audio_mode": "stereo" },
+                    "rds": {
+                        "program_type": "POP M",
+                        "program_service": "MOCK FM",
+                        "radio_text_a": "Mock broadcast",
+                        "radio_text_b": ""
+                    }
+                }),
+                None,
+            )),
+            _ => Err(INVALID_REQUEST),
+        }
+    }
+}
+
+const INVALID_REQUEST: i32 = 3;
+const INVALID_PARAMETER: i32 = 4;
+const GUARDED: i32 = 5;
+
+/// Corps de `getStatus` pour une zone, avec les champs renvoyés par un firmware réel
+fn zone_status(config: &MockConfig, status: &MockZone, names: &HashMap<String, String>) -> Value {
+    let input_text = names
+        .get(status.input.as_str())
+        .cloned()
+        .unwrap_or_else(|| status.input.as_str().to_uppercase());
+    json!({
+        "power": status.power.as_str(),
+        "sleep": status.sleep,
+        "volume": status.volume,
+        "mute": status.mute,
+        "max_volume": config.max_volume,
+        "input": status.input.as_str(),
+        "input_text": input_text,
+        "distribution_enable": true,
+        "sound_program": status.sound_program.as_str(),
+        "surr_decoder_type": status.surr_decoder_type.as_str(),
+        "direct": status.direct,
+        "pure_direct": status.pure_direct,
+        "enhancer": status.enhancer,
+        "tone_control": { "mode": "manual", "bass": 0, "treble": 0 },
+        "dialogue_level": status.dialogue_level,
+        "subwoofer_volume": status.subwoofer_volume,
+        "bass_extension": status.bass_extension,
+        "extra_bass": status.extra_bass,
+        "adaptive_drc": status.adaptive_drc,
+        "link_control": "standard",
+        "link_audio_delay": "audio_sync",
+        "disable_flags": 0,
+        "contents_display": true,
+        "actual_volume": {
+            "mode": "db",
+            "value": -80.5 + status.volume as f64 * 0.5,
+            "unit": "dB"
+        }
+    })
+}
+
+fn object(value: Value) -> serde_json::Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => serde_json::Map::new(),
+    }
+}
+
+fn param<'a>(params: &'a HashMap<String, String>, name: &str) -> Result<&'a str, i32> {
+    params
+        .get(name)
+        .map(String::as_str)
+        .ok_or(INVALID_PARAMETER)
+}
+
+fn bool_param(params: &HashMap<String, String>, name: &str) -> Result<bool, i32> {
+    match param(params, name)? {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(INVALID_PARAMETER),
+    }
+}
+
+fn int_param(
+    params: &HashMap<String, String>,
+    name: &str,
+    range: std::ops::RangeInclusive<i64>,
+) -> Result<i64, i32> {
+    param(params, name)?
+        .parse::<i64>()
+        .ok()
+        .filter(|value| range.contains(value))
+        .ok_or(INVALID_PARAMETER)
+}
+
+/// Convertit `setActualVolume?mode=db&value=-40.5` en pas de volume (0.5 dB par pas)
+fn db_to_volume(params: &HashMap<String, String>) -> Result<i64, i32> {
+    if param(params, "mode")? != "db" {
+        return Err(INVALID_PARAMETER);
+    }
+    let db = param(params, "value")?
+        .parse::<f64>()
+        .map_err(|_| INVALID_PARAMETER)?;
+    Ok(((db + 80.5) * 2.0).round() as i64)
+}
+
+fn step(params: &HashMap<String, String>) -> Result<i64, i32> {
+    match params.get("step") {
+        Some(step) => step.parse::<i64>().map_err(|_| INVALID_PARAMETER),
+        None => Ok(1),
+    }
+}
+
+/// Accepte les connexions HTTP ; chaque connexion traite une unique requête
+async fn serve_http(shared: Arc<Shared>, listener: TcpListener) {
+    while let Ok((stream, peer)) = listener.accept().await {
+        tokio::spawn(serve_connection(Arc::clone(&shared), stream, peer));
+    }
+}
+
+async fn serve_connection(shared: Arc<Shared>, stream: TcpStream, peer: SocketAddr) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+        return;
+    }
+
+    let mut app_port = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.unwrap_or(0) == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("X-AppPort")
+        {
+            app_port = value.trim().parse::<u16>().ok();
+        }
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let response = respond(&shared, target, peer, app_port).await;
+    let mut stream = reader.into_inner();
+    let _ = stream.write_all(&response).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Construit la réponse HTTP complète pour la cible demandée
+async fn respond(
+    shared: &Shared,
+    target: &str,
+    peer: SocketAddr,
+    app_port: Option<u16>,
+) -> Vec<u8> {
+    let target = target.trim_start_matches('/');
+    if target == DESCRIPTION_PATH {
+        return http_response(200, "text/xml", &description(&shared.config));
+    }
+    let Some(api_path) = target
+        .strip_prefix(API_BASE_PATH)
+        .and_then(|rest| rest.strip_prefix('/'))
+    else {
+        return http_response(404, "text/plain", "Not Found");
+    };
+
+    let (path, query) = api_path.split_once('?').unwrap_or((api_path, ""));
+    let params: HashMap<String, String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect();
+
+    let fault = {
+        let mut inner = shared.lock();
+        let logged = match query {
+            "" => path.to_string(),
+            query => format!("{path}?{}", decode(query)),
+        };
+        inner.requests.push(logged);
+        if let Some(port) = app_port {
+            inner.subscribers.insert(SocketAddr::new(peer.ip(), port));
+        }
+        inner
+            .faults
+            .get_mut(path)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| inner.recorded.get(path).cloned().map(Fault::Body))
+    };
+
+    match fault {
+        Some(Fault::Code(code)) => http_response(
+            200,
+            "application/json",
+            &json!({ "response_code": code }).to_string(),
+        ),
+        Some(Fault::Http(status)) => http_response(status, "text/plain", ""),
+        Some(Fault::Body(body)) => http_response(200, "application/json", &body),
+        Some(Fault::Delay(delay)) => {
+            tokio::time::sleep(delay).await;
+            api_response(shared, path, &params)
+        }
+        None => api_response(shared, path, &params),
+    }
+}
+
+fn api_response(shared: &Shared, path: &str, params: &HashMap<String, String>) -> Vec<u8> {
+    let mut inner = shared.lock();
+    let body = shared.handle(&mut inner, path, params);
+    http_response(200, "application/json", &body.to_string())
+}
+
+fn decode(s: &str) -> String {
+    percent_decode_str(s).decode_utf8_lossy().into_owned()
+}
+
+fn http_response(status: u16, content_type: &str, body: &str) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Status",
+    };
+    format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .into_bytes()
+}
+
+/// Description UPnP minimale, avec les extensions `yamaha:` utilisées par MusicCast
+fn description(config: &MockConfig) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0" xmlns:yamaha="urn:schemas-yamaha-com:device-1-0">
+  <specVersion><major>1</major><minor>0</minor></specVersion>
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <friendlyName>{model}</friendlyName>
+    <manufacturer>Yamaha Corporation</manufacturer>
+    <modelName>{model}</modelName>
+    <UDN>uuid:9ab0c000-f668-11de-9976-{id}</UDN>
+  </device>
+  <yamaha:X_device>
+    <yamaha:X_URLBase>/</yamaha:X_URLBase>
+    <yamaha:X_serviceList>
+      <yamaha:X_service>
+        <yamaha:X_specType>urn:schemas-yamaha-com:service:X_YamahaExtendedControl:1</yamaha:X_specType>
+        <yamaha:X_yxcControlURL>/{base}/</yamaha:X_yxcControlURL>
+        <yamaha:X_yxcVersion>{version}</yamaha:X_yxcVersion>
+      </yamaha:X_service>
+    </yamaha:X_serviceList>
+  </yamaha:X_device>
+</root>
+"#,
+        model = config.model_name,
+        id = config.device_id.to_lowercase(),
+        base = API_BASE_PATH,
+        version = (config.api_version * 1000.0).round() as u32,
+    )
+}
+
+/// Répond aux requêtes SSDP `M-SEARCH` compatibles avec un MediaRenderer
+async fn serve_ssdp(shared: Arc<Shared>, socket: UdpSocket, http: SocketAddr) {
+    let mut buf = vec![0u8; 2048];
+    while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+        let request = String::from_utf8_lossy(&buf[..len]);
+        if !request.starts_with("M-SEARCH") {
+            continue;
+        }
+        let target = request
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim()
+                    .eq_ignore_ascii_case("ST")
+                    .then(|| value.trim().to_string())
+            })
+            .unwrap_or_default();
+        if !matches!(
+            target.as_str(),
+            "ssdp:all" | "upnp:rootdevice" | "urn:schemas-upnp-org:device:MediaRenderer:1"
+        ) {
+            continue;
+        }
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nEXT:\r\nLOCATION: http://{http}/{DESCRIPTION_PATH}\r\nSERVER: Linux/3.0 UPnP/1.0 {model}/1.0\r\nST: {target}\r\nUSN: uuid:9ab0c000-f668-11de-9976-{id}::{target}\r\n\r\n",
+            model = shared.config.model_name,
+            id = shared.config.device_id.to_lowercase(),
+        );
+        let _ = socket.send_to(response.as_bytes(), peer).await;
+    }
+}
@@ -0,0 +1,145 @@
+//! Rejoue les réponses enregistrées dans `tests/fixtures/<modèle>/<endpoint>.json`
+//! et vérifie qu'elles se décodent dans les structures de `json_data.rs`.
+//!
+//! Pour ajouter un modèle : `cargo run -p yamaha_api --example capture_fixtures -- <hôte>`
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use yamaha_api::testing::MockDevice;
+use yamaha_api::*;
+
+/// Fichiers reconnus et endpoint correspondant
+const ENDPOINTS: &[(&str, &str)] = &[
+    ("getDeviceInfo", "system/getDeviceInfo"),
+    ("getFeatures", "system/getFeatures"),
+    ("getStatus", "main/getStatus"),
+    ("getSignalInfo", "main/getSignalInfo"),
+];
+
+fn fixture_dirs() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut dirs: Vec<PathBuf> = fs::read_dir(root)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn read_fixture(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(format!("{name}.json"))).ok()
+}
+
+/// Démarre un appareil simulé servant les réponses enregistrées pour un modèle
+async fn replay(dir: &Path) -> MockDevice {
+    let mock = MockDevice::start().await.unwrap();
+    for (name, path) in ENDPOINTS {
+        if let Some(body) = read_fixture(dir, name) {
+            mock.respond_with(path, body);
+        }
+    }
+    mock
+}
+
+/// Décode une réponse obtenue par le client, ou vérifie l'erreur attendue si le modèle
+/// ne supporte pas l'endpoint (`response_code` non nul dans la fixture)
+fn check<T: DeserializeOwned>(
+    dir: &Path,
+    name: &str,
+    result: Result<Value, YamahaError>,
+) -> Option<(T, Value)> {
+    let raw: Value = serde_json::from_str(&read_fixture(dir, name)?).unwrap();
+    let code = raw["response_code"].as_i64().unwrap() as i32;
+    let model = dir.file_name().unwrap().to_string_lossy();
+
+    if code != 0 {
+        let err = result.expect_err(&format!("{model}/{name} should fail"));
+        assert_eq!(err.device_code(), Some(YamahaErrorCode::from_code(code)));
+        return None;
+    }
+
+    let json = result.unwrap_or_else(|e| panic!("{model}/{name}: {e}"));
+    let decoded = serde_json::from_value::<T>(json)
+        .unwrap_or_else(|e| panic!("{model}/{name} does not match json_data.rs: {e}"));
+    Some((decoded, raw))
+}
+
+#[test]
+fn every_fixture_is_replayed() {
+    let dirs = fixture_dirs();
+    assert!(dirs.len() >= 4, "expected fixtures for several models");
+    for dir in dirs {
+        assert!(dir.join("getDeviceInfo.json").exists(), "{}", dir.display());
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            assert!(
+                ENDPOINTS.iter().any(|(name, _)| *name == stem),
+                "unknown fixture {}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Les fixtures enregistrées ne doivent contenir aucun champ inconnu de `json_data.rs`
+fn assert_no_drift<T: serde::de::DeserializeOwned + SchemaDrift>(dir: &Path, name: &str) {
+    let Some(body) = read_fixture(dir, name) else {
+        return;
+    };
+    let mut raw: Value = serde_json::from_str(&body).unwrap();
+    // Retiré par le client avant le décodage, comme dans `parse_response`
+    if raw.as_object_mut().unwrap().remove("response_code") != Some(0.into()) {
+        return;
+    }
+    let model = dir.file_name().unwrap().to_string_lossy();
+    if let Err(e) = decode_strict::<T>(raw) {
+        panic!("{model}/{name}: {e}");
+    }
+}
+
+#[test]
+fn fixtures_have_no_schema_drift() {
+    for dir in fixture_dirs() {
+        assert_no_drift::<GetDeviceInfo>(&dir, "getDeviceInfo");
+        assert_no_drift::<GetFeatures>(&dir, "getFeatures");
+        assert_no_drift::<GetStatus>(&dir, "getStatus");
+        assert_no_drift::<GetSignalInfo>(&dir, "getSignalInfo");
+    }
+}
+
+#[tokio::test]
+async fn fixtures_decode_into_typed_structs() {
+    for dir in fixture_dirs() {
+        let mock = replay(&dir).await;
+        let amp = mock.builder().connect().await.unwrap();
+
+        let (info, raw) =
+            check::<GetDeviceInfo>(&dir, "getDeviceInfo", amp.get_device_info().await).unwrap();
+        assert_eq!(info.model_name, raw["model_name"]);
+        assert_eq!(amp.info.model, info.model_name);
+        assert_eq!(amp.info.device_id, info.device_id);
+
+        if let Some((features, raw)) =
+            check::<GetFeatures>(&dir, "getFeatures", amp.get_features().await)
+        {
+            assert_eq!(features.zone.len(), raw["zone"].as_array().unwrap().len());
+            assert_eq!(features.system.zone_num, raw["system"]["zone_num"]);
+            assert_eq!(features.zone[0].id, "main");
+        }
+
+        if let Some((status, raw)) =
+            check::<GetStatus>(&dir, "getStatus", amp.get_main_status().await)
+        {
+            assert_eq!(status.power.as_str(), raw["power"]);
+            assert_eq!(status.volume, raw["volume"]);
+            assert_eq!(status.max_volume, raw["max_volume"]);
+            assert!(!status.power.is_unknown());
+        }
+
+        check::<GetSignalInfo>(&dir, "getSignalInfo", amp.get_signal_info().await);
+    }
+}
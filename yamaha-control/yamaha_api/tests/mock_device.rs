@@ -0,0 +1,660 @@
+use std::sync::Arc;
+use std::time::Duration;
+use yamaha_api::testing::{Fault, MockConfig, MockDevice};
+use yamaha_api::*;
+
+async fn powered_on(mock: &MockDevice) -> YamahaAmpAsync {
+    let amp = mock.builder().connect().await.unwrap();
+    amp.set_power(PowerState::On).await.unwrap();
+    amp
+}
+
+#[tokio::test]
+async fn connect_reads_device_info() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock.builder().connect().await.unwrap();
+
+    assert_eq!(amp.info.model, "RX-V6A");
+    assert_eq!(amp.info.device_id, "00A0DE000001");
+    assert_eq!(amp.address, mock.address());
+    assert_eq!(mock.requests(), ["system/getDeviceInfo"]);
+}
+
+#[tokio::test]
+async fn setters_update_status() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = powered_on(&mock).await;
+
+    amp.set_volume(80).await.unwrap();
+    amp.set_mute(true).await.unwrap();
+    amp.set_input(Input::Hdmi2).await.unwrap();
+    amp.set_sound_program(SoundProgram::SciFi).await.unwrap();
+    amp.set_adaptive_drc(true).await.unwrap();
+
+    let status: GetStatus = serde_json::from_value(amp.get_main_status().await.unwrap()).unwrap();
+    assert_eq!(status.power, PowerState::On);
+    assert_eq!(status.volume, 80);
+    assert!(status.mute);
+    assert_eq!(status.input, Input::Hdmi2);
+    assert_eq!(status.sound_program, Some(SoundProgram::SciFi));
+    assert_eq!(status.actual_volume.unwrap().value, -40.5);
+    assert_eq!(status.adaptive_drc, Some(true));
+
+    let zone2 = &mock.state().zones[&Zone::Zone2];
+    assert_eq!(zone2.power, PowerState::Standby);
+    assert!(
+        mock.requests()
+            .contains(&"main/setSoundProgram?program=sci-fi".to_string())
+    );
+}
+
+#[tokio::test]
+async fn device_error_codes_are_reported() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock.builder().connect().await.unwrap();
+
+    let err = amp.set_mute(true).await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::Guarded));
+
+    amp.set_power(PowerState::On).await.unwrap();
+    let err = amp.set_volume(500).await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidParameter));
+    let err = amp.set_input(Input::Phono).await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidParameter));
+
+    let err = amp.get_zone_status(Zone::Zone3).await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidRequest));
+    assert_eq!(err.context().unwrap().zone, Some(Zone::Zone3));
+}
+
+#[tokio::test]
+async fn typed_reads_report_the_request_that_failed() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = powered_on(&mock).await;
+
+    let body = r#"{"response_code":0,"volume":"loud"}"#;
+    mock.fail_next("zone2/getStatus", Fault::Body(body.into()), 1);
+    let err = Scene::capture(&amp, "evening", Zone::Zone2)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, YamahaError::Decode { .. }), "{err:?}");
+    let context = err.context().unwrap();
+    assert_eq!(context.endpoint, "zone2/getStatus");
+    assert_eq!(context.zone, Some(Zone::Zone2));
+}
+
+#[tokio::test]
+async fn raw_get_reaches_unwrapped_endpoints() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock.builder().connect().await.unwrap();
+
+    let status = amp.raw_get("zone2/getStatus", &[]).await.unwrap();
+    assert_eq!(status["power"], "standby");
+    assert!(status.get("response_code").is_none());
+
+    amp.raw_get(
+        "system/setNameText",
+        &[("id", "main"), ("text", "Salon & TV")],
+    )
+    .await
+    .unwrap();
+    assert_eq!(mock.state().names["main"], "Salon & TV");
+
+    let err = amp
+        .raw_get("main/setMute", &[("enable", "true")])
+        .await
+        .unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::Guarded));
+    assert_eq!(err.context().unwrap().zone, Some(Zone::Main));
+}
+
+#[tokio::test]
+async fn scenes_restore_a_zone_with_minimal_calls() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = powered_on(&mock).await;
+    amp.set_input(Input::Hdmi2).await.unwrap();
+    amp.set_sound_program(SoundProgram::SciFi).await.unwrap();
+    amp.set_dialogue_level(2).await.unwrap();
+    amp.set_volume(90).await.unwrap();
+    let scene = Scene::capture(&amp, "movie", Zone::Main).await.unwrap();
+    assert!(scene.apply(&amp, &Zone::Main).await.unwrap().is_empty());
+
+    mock.update(|device| {
+        let main = device.zones.get_mut(&Zone::Main).unwrap();
+        main.power = PowerState::Standby;
+        main.input = Input::Tuner;
+        main.volume = 20;
+        main.dialogue_level = 0;
+    });
+    let calls = scene.apply(&amp, &Zone::Main).await.unwrap();
+    let functions: Vec<&str> = calls.iter().map(SceneCall::function).collect();
+    assert_eq!(
+        functions,
+        ["setPower", "setInput", "setDialogueLevel", "setVolume"]
+    );
+    let main = &mock.state().zones[&Zone::Main];
+    assert_eq!(main.power, PowerState::On);
+    assert_eq!(main.input, Input::Hdmi2);
+    assert_eq!(main.dialogue_level, 2);
+    assert_eq!(main.volume, 90);
+
+    scene.apply(&amp, &Zone::Zone2).await.unwrap();
+    let zone2 = &mock.state().zones[&Zone::Zone2];
+    assert_eq!(zone2.power, PowerState::On);
+    assert_eq!(zone2.volume, 90);
+}
+
+#[tokio::test]
+async fn backups_restore_a_replaced_device() {
+    let old = MockDevice::start().await.unwrap();
+    let amp = powered_on(&old).await;
+    amp.set_input(Input::Hdmi2).await.unwrap();
+    amp.set_volume(70).await.unwrap();
+    amp.set_name_text("hdmi2", "Apple TV").await.unwrap();
+    amp.raw_get("system/setSpeakerPattern", &[("num", "2")])
+        .await
+        .unwrap();
+    amp.raw_get("clock/setClockFormat", &[("format", "12h")])
+        .await
+        .unwrap();
+    amp.raw_get(
+        "tuner/setFreq",
+        &[("band", "fm"), ("tuning", "direct"), ("num", "94500")],
+    )
+    .await
+    .unwrap();
+    amp.raw_get("tuner/storePreset", &[("num", "3")])
+        .await
+        .unwrap();
+    old.update(|device| device.netusb_presets[1] = (Input::NetRadio, "FIP Jazz".into()));
+    let backup = Backup::capture(&amp).await.unwrap();
+    assert_eq!(backup.device.model, "RX-V6A");
+    assert_eq!(backup.zones.keys().collect::<Vec<_>>(), ["main", "zone2"]);
+    let backup = Backup::from_json(&backup.to_json().unwrap()).unwrap();
+
+    let new = MockDevice::start().await.unwrap();
+    let amp = new.builder().connect().await.unwrap();
+    let untouched = new.state();
+    let plan = backup.plan(&Backup::capture(&amp).await.unwrap());
+    assert_eq!(new.state(), untouched);
+    let calls: Vec<String> = plan.calls.iter().map(ToString::to_string).collect();
+    assert!(calls.contains(&"system/setSpeakerPattern?num=2".to_string()));
+    assert!(calls.contains(&"system/setNameText?id=hdmi2&text=Apple TV".to_string()));
+    let stored = calls
+        .iter()
+        .position(|call| call == "tuner/storePreset?num=3")
+        .unwrap();
+    assert_eq!(
+        calls[stored + 1],
+        "tuner/setFreq?band=fm&tuning=direct&num=101700"
+    );
+    assert_eq!(
+        plan.manual,
+        ["netusb preset 2: store \"FIP Jazz\" (net_radio)"]
+    );
+
+    let done = backup.restore(&amp).await.unwrap();
+    assert_eq!(done.manual, plan.manual);
+    let state = new.state();
+    let main = &state.zones[&Zone::Main];
+    assert_eq!(
+        (main.power.clone(), main.input.clone(), main.volume),
+        (PowerState::On, Input::Hdmi2, 70)
+    );
+    assert_eq!(state.names["hdmi2"], "Apple TV");
+    assert_eq!(state.functions["speaker_pattern"], 2);
+    assert_eq!(state.clock["format"], "12h");
+    assert_eq!(state.tuner.presets[2], ("fm".to_string(), 94500));
+    assert_eq!(
+        (state.tuner.band.as_str(), state.tuner.freq),
+        ("fm", 101700)
+    );
+
+    let plan = backup.plan(&Backup::capture(&amp).await.unwrap());
+    assert!(plan.calls.is_empty());
+}
+
+#[tokio::test]
+async fn fleets_report_each_device_separately() {
+    let mocks = [
+        MockDevice::start().await.unwrap(),
+        MockDevice::start().await.unwrap(),
+        MockDevice::start().await.unwrap(),
+    ];
+    let mut fleet = Fleet::new()
+        .with_concurrency(2)
+        .with_timeout(Duration::from_millis(300));
+    for (i, mock) in mocks.iter().enumerate() {
+        fleet.push(format!("room{i}"), mock.builder().build().unwrap());
+    }
+    assert_eq!(
+        fleet.names().collect::<Vec<_>>(),
+        ["room0", "room1", "room2"]
+    );
+
+    let results = fleet.set_power(&Zone::Zone2, PowerState::On).await;
+    assert!(results.iter().all(|done| done.result.is_ok()));
+    let results = fleet.set_volume_db(&Zone::Zone2, -40.0).await;
+    assert!(results.iter().all(|done| done.result.is_ok()));
+    for mock in &mocks {
+        assert_eq!(mock.state().zones[&Zone::Zone2].volume, 81);
+    }
+
+    mocks[1].fail_next("zone2/getStatus", Fault::Delay(Duration::from_secs(2)), 1);
+    let results = fleet.status(&Zone::Zone2).await;
+    let names: Vec<&str> = results.iter().map(|done| done.name.as_str()).collect();
+    assert_eq!(names, ["room0", "room1", "room2"]);
+    assert_eq!(results[0].result.as_ref().unwrap().power, PowerState::On);
+    assert!(matches!(results[1].result, Err(YamahaError::Elapsed(_))));
+    assert!(results[2].result.is_ok());
+}
+
+#[tokio::test]
+async fn fades_step_the_volume_until_interrupted() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = Arc::new(powered_on(&mock).await);
+    amp.set_volume(41).await.unwrap();
+
+    let outcome = amp
+        .fade_volume(
+            Zone::Main,
+            -50.0,
+            Duration::from_millis(500),
+            FadeCurve::Linear,
+        )
+        .await
+        .unwrap();
+    assert_eq!(outcome, FadeOutcome::Completed);
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 61);
+    let sets = mock
+        .requests()
+        .iter()
+        .filter(|request| request.starts_with("main/setActualVolume"))
+        .count();
+    assert_eq!(sets, 5);
+
+    let fade = tokio::spawn({
+        let amp = amp.clone();
+        async move {
+            amp.fade_volume(
+                Zone::Main,
+                -20.0,
+                Duration::from_secs(3),
+                FadeCurve::Logarithmic,
+            )
+            .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(450)).await;
+    let fading = mock.state().zones[&Zone::Main].volume;
+    assert!(fading > 61, "{fading}");
+    mock.update(|device| device.zones.get_mut(&Zone::Main).unwrap().volume = 30);
+    let outcome = fade.await.unwrap().unwrap();
+    assert_eq!(outcome, FadeOutcome::Interrupted { volume_db: -65.5 });
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 30);
+
+    let fade = amp.fade_volume(Zone::Main, -20.0, Duration::from_secs(3), FadeCurve::Linear);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(350), fade)
+            .await
+            .is_err()
+    );
+    let cancelled = mock.state().zones[&Zone::Main].volume;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(mock.state().zones[&Zone::Main].volume, cancelled);
+    assert!(cancelled > 30 && cancelled < 121, "{cancelled}");
+}
+
+#[tokio::test]
+async fn zone_setters_leave_other_zones_alone() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock.builder().connect().await.unwrap();
+    let main = mock.state().zones[&Zone::Main].clone();
+
+    amp.set_zone_power(Zone::Zone2, PowerState::On)
+        .await
+        .unwrap();
+    amp.set_zone_input(Zone::Zone2, Input::Hdmi2).await.unwrap();
+    amp.set_zone_volume(Zone::Zone2, 70).await.unwrap();
+    amp.set_zone_volume_up(Zone::Zone2, Some(3)).await.unwrap();
+    amp.set_zone_mute(Zone::Zone2, true).await.unwrap();
+
+    let zone2 = &mock.state().zones[&Zone::Zone2];
+    assert_eq!(zone2.power, PowerState::On);
+    assert_eq!(zone2.input, Input::Hdmi2);
+    assert_eq!(zone2.volume, 73);
+    assert!(zone2.mute);
+    assert_eq!(mock.state().zones[&Zone::Main], main);
+    assert!(
+        mock.requests()
+            .contains(&"zone2/setVolume?volume=up&step=3".to_string())
+    );
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = powered_on(&mock).await.with_retry_policy(RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..RetryPolicy::attempts(3)
+    });
+
+    mock.fail_next("main/setVolume", Fault::Code(1), 2);
+    amp.set_volume(60).await.unwrap();
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 60);
+
+    mock.fail_next("main/getStatus", Fault::Http(503), 3);
+    let err = amp.get_main_status().await.unwrap_err();
+    assert!(matches!(err, YamahaError::Http { .. }));
+
+    mock.fail_next("main/getStatus", Fault::Body("<html>".into()), 1);
+    let err = amp.get_main_status().await.unwrap_err();
+    assert!(matches!(err, YamahaError::Decode { .. }));
+}
+
+#[tokio::test]
+async fn timed_out_toggles_are_not_repeated() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock
+        .builder()
+        .timeout(Duration::from_millis(100))
+        .retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::attempts(3)
+        })
+        .connect()
+        .await
+        .unwrap();
+
+    amp.set_power(PowerState::On).await.unwrap();
+    mock.fail_next(
+        "main/setVolume",
+        Fault::Delay(Duration::from_millis(500)),
+        1,
+    );
+    amp.set_volume(50).await.unwrap();
+    let sets = mock
+        .requests()
+        .iter()
+        .filter(|path| *path == "main/setVolume?volume=50")
+        .count();
+    assert_eq!(sets, 2);
+
+    mock.fail_next("main/setPower", Fault::Delay(Duration::from_millis(500)), 1);
+    let err = amp.set_power(PowerState::Toggle).await.unwrap_err();
+    assert!(matches!(err, YamahaError::Timeout { .. }));
+    let toggles = mock
+        .requests()
+        .iter()
+        .filter(|path| *path == "main/setPower?power=toggle")
+        .count();
+    assert_eq!(toggles, 1);
+}
+
+#[tokio::test]
+async fn unknown_host_names_fail_to_resolve() {
+    let err = YamahaAmpBuilder::new("amp.invalid")
+        .connect()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, YamahaError::Resolve { .. }));
+
+    let err = tokio::task::spawn_blocking(|| {
+        YamahaAmpBuilder::new("amp.invalid")
+            .connect_blocking()
+            .unwrap_err()
+    })
+    .await
+    .unwrap();
+    assert!(matches!(err, YamahaError::Resolve { .. }));
+}
+
+#[tokio::test]
+async fn slow_responses_time_out() {
+    let mock = MockDevice::start().await.unwrap();
+    let amp = mock
+        .builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    mock.fail_next("main/getStatus", Fault::Delay(Duration::from_secs(2)), 1);
+    let err = amp.get_main_status().await.unwrap_err();
+    assert!(matches!(err, YamahaError::Timeout { .. }));
+}
+
+#[tokio::test]
+async fn subscribed_clients_receive_events() {
+    let mock = MockDevice::start().await.unwrap();
+    let listener = EventListener::bind(0).await.unwrap();
+    let amp = mock
+        .builder()
+        .events(EventSettings {
+            port: listener.port().unwrap(),
+            ..Default::default()
+        })
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(mock.subscribers().len(), 1);
+
+    amp.set_power(PowerState::On).await.unwrap();
+    let event = listener.recv().await.unwrap();
+    assert_eq!(event.device_id.as_deref(), Some("00A0DE000001"));
+    assert_eq!(event.payload["main"]["power"], "on");
+}
+
+#[tokio::test]
+async fn amp_state_follows_device_events() {
+    let mock = MockDevice::start().await.unwrap();
+    let listener = EventListener::bind(0).await.unwrap();
+    let amp = mock
+        .builder()
+        .events(EventSettings {
+            port: listener.port().unwrap(),
+            ..Default::default()
+        })
+        .connect()
+        .await
+        .unwrap();
+    let state = Arc::new(AmpState::new(amp));
+    state.refresh().await.unwrap();
+    assert_eq!(state.snapshot().netusb.unwrap().playback, "stop");
+    assert!(state.snapshot().tuner.is_some());
+    assert_eq!(state.snapshot().signal.unwrap().audio.format, "");
+
+    let mut receiver = state.subscribe();
+    assert!(!state.has_received_events());
+    let task = state.spawn_events(listener);
+
+    mock.update(|device| {
+        let main = device.zones.get_mut(&Zone::Main).unwrap();
+        main.power = PowerState::On;
+        main.volume = 99;
+    });
+    let snapshot = tokio::time::timeout(
+        Duration::from_secs(5),
+        receiver.wait_for(|snapshot| snapshot.main().is_some_and(|main| main.volume == 99)),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .clone();
+    assert_eq!(snapshot.main().unwrap().power, PowerState::On);
+
+    let snapshot = tokio::time::timeout(
+        Duration::from_secs(5),
+        receiver.wait_for(|snapshot| {
+            snapshot
+                .signal
+                .as_ref()
+                .is_some_and(|s| !s.audio.format.is_empty())
+        }),
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .clone();
+    assert_eq!(snapshot.signal.unwrap().audio.format, "Dolby Digital");
+    assert!(state.has_received_events());
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn sound_bar_has_no_tuner() {
+    let mock = MockDevice::start_with(MockConfig::sound_bar())
+        .await
+        .unwrap();
+    let amp = mock.builder().connect().await.unwrap();
+
+    assert_eq!(amp.info.model, "YAS-209");
+    let err = amp.get_tuner_play_info().await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidRequest));
+    let err = amp.get_zone_status(Zone::Zone2).await.unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidRequest));
+}
+
+#[tokio::test]
+async fn discovery_finds_the_device() {
+    let mock = MockDevice::start().await.unwrap();
+
+    let amp = connect_direct(mock.address()).await.unwrap().unwrap();
+    assert_eq!(amp.info.model, "RX-V6A");
+    let authority = mock.address().authority();
+    let amp = YamahaAmpAsync::connect(authority.as_str())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(amp.address, mock.address());
+
+    let found = discover_amplifiers(Some(DiscoveryConfig {
+        subnet: std::net::Ipv4Addr::LOCALHOST,
+        mask: 30,
+        port: mock.address().port,
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].address, mock.address());
+}
+
+#[tokio::test]
+async fn ssdp_search_points_to_description() {
+    let mock = MockDevice::start().await.unwrap();
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let search = "M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\nMX: 1\r\nST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n";
+    socket
+        .send_to(search.as_bytes(), mock.ssdp_address())
+        .await
+        .unwrap();
+
+    let mut buf = [0u8; 1024];
+    let (len, _) = socket.recv_from(&mut buf).await.unwrap();
+    let response = String::from_utf8_lossy(&buf[..len]);
+    let location = response
+        .lines()
+        .find_map(|line| line.strip_prefix("LOCATION: "))
+        .unwrap();
+    assert!(location.ends_with(yamaha_api::testing::DESCRIPTION_PATH));
+
+    let description = reqwest::get(location).await.unwrap().text().await.unwrap();
+    assert!(description.contains("<modelName>RX-V6A</modelName>"));
+    assert!(description.contains("X_yxcControlURL"));
+}
+
+#[test]
+fn blocking_client_against_mock() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mock = rt.block_on(MockDevice::start()).unwrap();
+
+    let amp = mock.builder().connect_blocking().unwrap();
+    amp.set_power(PowerState::On).unwrap();
+    amp.set_volume(42).unwrap();
+    amp.set_name_text("hdmi1", "Apple TV").unwrap();
+    #[allow(deprecated)]
+    amp.set_adaptative_drc(true).unwrap();
+
+    let status: GetStatus = serde_json::from_value(amp.get_main_status().unwrap()).unwrap();
+    assert_eq!(status.volume, 42);
+    assert_eq!(status.input_text.as_deref(), Some("Apple TV"));
+    assert_eq!(status.adaptive_drc, Some(true));
+
+    let status = amp.raw_get("main/getStatus", &[]).unwrap();
+    assert_eq!(status["volume"], 42);
+    let err = amp.raw_get("zone3/getStatus", &[]).unwrap_err();
+    assert_eq!(err.device_code(), Some(YamahaErrorCode::InvalidRequest));
+}
+
+#[tokio::test]
+async fn volume_limits_are_enforced_before_sending() {
+    let mock = MockDevice::start().await.unwrap();
+    mock.update(|device| device.zones.get_mut(&Zone::Main).unwrap().volume = 110);
+    let policy = VolumePolicy::new(VolumeLimits {
+        max_volume: Some(100),
+        max_step: Some(10),
+        power_on_volume: Some(60),
+    });
+    let amp = mock
+        .builder()
+        .volume_policy(policy)
+        .connect()
+        .await
+        .unwrap();
+
+    amp.set_power(PowerState::On).await.unwrap();
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 60);
+    assert_eq!(
+        mock.requests()[mock.requests().len() - 2..],
+        ["main/setVolume?volume=60", "main/setPower?power=on"]
+    );
+    amp.set_volume(70).await.unwrap();
+
+    let sent = mock.requests().len();
+    let err = amp.set_volume(81).await.unwrap_err();
+    assert!(matches!(
+        err,
+        YamahaError::VolumeLimit {
+            kind: VolumeLimitKind::MaxStep,
+            requested: 11,
+            limit: 10,
+            ..
+        }
+    ));
+    let err = amp
+        .raw_get("main/setActualVolume", &[("mode", "db"), ("value", "-30")])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        YamahaError::VolumeLimit {
+            kind: VolumeLimitKind::MaxVolume,
+            requested: 101,
+            limit: 100,
+            ..
+        }
+    ));
+    assert!(
+        mock.requests()[sent..]
+            .iter()
+            .all(|request| request.starts_with("main/getStatus"))
+    );
+    let err = amp
+        .raw_get("main/setVolume?volume=160", &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        YamahaError::VolumeLimit {
+            kind: VolumeLimitKind::MaxVolume,
+            requested: 160,
+            ..
+        }
+    ));
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 70);
+
+    let amp = amp.with_volume_override(true);
+    amp.set_volume(120).await.unwrap();
+    assert_eq!(mock.state().zones[&Zone::Main].volume, 120);
+}
@@ -0,0 +1,499 @@
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::{Args, Parser, Subcommand};
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use yamaha_api::{
+    AmpAddress, DEFAULT_FLEET_CONCURRENCY, DEFAULT_PORT, FadeCurve, Input, ParseEnumError,
+    PowerState, SoundProgram, Zone,
+};
+
+/// Control Yamaha MusicCast / YXC amplifiers from the command line
+///
+/// Exit codes: 0 success, 1 other error, 2 invalid usage, 3 device unreachable,
+/// 4 request rejected by the device, 5 request refused by a volume limit.
+#[derive(Debug, Parser)]
+#[command(name = "yamaha_cli", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GlobalArgs {
+    /// Amplifier address: host, host:port, IPv4 or [IPv6]:port
+    #[arg(long, short = 'H', global = true, value_parser = parse_address)]
+    pub host: Option<AmpAddress>,
+
+    /// Device named in the config file, optionally with a zone (`kitchen:zone2`)
+    #[arg(
+        long,
+        short,
+        global = true,
+        value_name = "NAME",
+        conflicts_with = "host"
+    )]
+    pub device: Option<String>,
+
+    /// Run the command on every device of the config file
+    #[arg(long, global = true, conflicts_with_all = ["host", "device"])]
+    pub all: bool,
+
+    /// Run the command on the devices of the config file having this tag, repeatable
+    #[arg(
+        long = "tag",
+        global = true,
+        value_name = "TAG",
+        conflicts_with_all = ["host", "device", "all"]
+    )]
+    pub tags: Vec<String>,
+
+    /// Number of devices contacted at once with --all or --tag
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_FLEET_CONCURRENCY)]
+    pub parallel: usize,
+
+    /// Deadline for each device with --all or --tag, e.g. `30s` [default: none]
+    #[arg(long, global = true, value_name = "DURATION", value_parser = parse_duration)]
+    pub device_timeout: Option<Duration>,
+
+    /// Zone targeted by zone commands [default: the device's zone, or main]
+    #[arg(long, short, global = true, value_parser = KnownValues(Zone::ALL))]
+    pub zone: Option<Zone>,
+
+    /// Config file with named devices [default: <config dir>/yamaha_cli/config.toml]
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// HTTP request timeout in milliseconds
+    #[arg(long, global = true, value_name = "MS", value_parser = parse_millis)]
+    pub timeout: Option<Duration>,
+
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Ignore the volume limits of the config file, for deliberate admin changes
+    #[arg(long, global = true)]
+    pub override_limits: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Scan a subnet for amplifiers
+    Discover {
+        /// Subnet to scan
+        #[arg(long, default_value = "192.168.1.0")]
+        subnet: Ipv4Addr,
+        /// Subnet mask in CIDR notation
+        #[arg(long, default_value_t = 24, value_parser = clap::value_parser!(u8).range(1..=32))]
+        mask: u8,
+        /// Per-address timeout in milliseconds
+        #[arg(long, value_name = "MS", default_value = "500", value_parser = parse_millis)]
+        scan_timeout: Duration,
+        /// HTTP port queried on each address
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Add the amplifiers found to the config file
+        #[arg(long)]
+        save: bool,
+    },
+    /// Show the status of the zone
+    Status,
+    /// Show or change the power state
+    Power {
+        #[arg(value_parser = KnownValues(PowerState::ALL))]
+        state: Option<PowerState>,
+    },
+    /// Show or change the volume
+    Volume {
+        /// Volume step, `up` or `down`, or a level in dB with `--db`
+        #[arg(allow_negative_numbers = true)]
+        level: Option<VolumeArg>,
+        /// Interpret the level as dB (e.g. -40.5)
+        #[arg(long)]
+        db: bool,
+        /// Step used by `up` and `down`
+        #[arg(long)]
+        step: Option<u32>,
+    },
+    /// Fade the volume to a level in dB, stopping if the volume is changed meanwhile
+    Fade {
+        /// Target volume in dB, e.g. -35
+        #[arg(allow_negative_numbers = true)]
+        target: f64,
+        /// Duration of the fade: 500ms, 30s, 5m
+        #[arg(long, default_value = "10s", value_parser = parse_duration)]
+        over: Duration,
+        #[arg(long, value_enum, default_value_t = Curve::Linear)]
+        curve: Curve,
+    },
+    /// Show or change the mute state
+    Mute { state: Option<Switch> },
+    /// Show or change the input
+    Input {
+        /// Input id, e.g. `hdmi1` or `net_radio` (see `features` for those of the device)
+        #[arg(value_parser = OpenValues(Input::ALL, Input::from_str_lossy), hide_possible_values = true)]
+        input: Option<Input>,
+    },
+    /// Recall a net/USB preset (net radio station, server folder, ...)
+    Preset {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        num: u32,
+    },
+    /// Show or change the sound program
+    Program {
+        /// Sound program id, e.g. `straight` or `sci-fi` (see `features` for those of the device)
+        #[arg(
+            value_parser = OpenValues(SoundProgram::ALL, SoundProgram::from_str_lossy),
+            hide_possible_values = true
+        )]
+        program: Option<SoundProgram>,
+    },
+    /// List the zones of the amplifier with their state
+    Zone,
+    /// Show the features of the amplifier (zones, inputs, sound programs)
+    Features,
+    /// Follow the zone live, using UDP events when possible and polling otherwise
+    Watch {
+        /// Print one JSON object per change instead of a live view
+        #[arg(long)]
+        ndjson: bool,
+        /// Polling interval in milliseconds, until the first UDP event arrives
+        #[arg(long, value_name = "MS", default_value = "2000", value_parser = parse_millis)]
+        interval: Duration,
+    },
+    /// Interactive session with completion and history, connected to one amplifier
+    Shell,
+    /// Full-screen view with volume bar and keyboard shortcuts
+    Tui {
+        /// Polling interval in milliseconds, until the first UDP event arrives
+        #[arg(long, value_name = "MS", default_value = "2000", value_parser = parse_millis)]
+        interval: Duration,
+    },
+    /// Run the commands of a script file, on one or several devices
+    Run {
+        /// Script file, one command per line
+        ///
+        /// Besides the usual commands, a script may contain `wait 2s`,
+        /// `if power == standby then power on` and `on-error continue`.
+        /// Lines starting with `#` are comments.
+        script: PathBuf,
+        /// Device from the config file to run the script on, repeatable
+        #[arg(long = "on", value_name = "DEVICE")]
+        devices: Vec<String>,
+        /// What to do when a command fails, until the script says otherwise
+        #[arg(long, value_enum, default_value_t = ErrorPolicy::Stop)]
+        on_error: ErrorPolicy,
+    },
+    /// Save the state of the zone as a named scene, and bring it back later
+    Scene {
+        #[command(subcommand)]
+        action: SceneAction,
+    },
+    /// Run the jobs of the config file at their times, until interrupted
+    ///
+    /// Jobs are `[[jobs]]` entries of the config file with a `name`, a `when` such as
+    /// `weekdays 08:00` or `0 23 * * *`, script lines to `run` and the `devices`, `tags`
+    /// or `all` to run them on. Runs missed while the daemon was stopped or the host
+    /// asleep are skipped, or run once on the next start with `missed = "run"`, within
+    /// an optional `grace` delay.
+    Daemon {
+        /// List the missed runs and the next executions instead of running anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Number of executions listed by --dry-run
+        #[arg(long, value_name = "N", default_value_t = 10, requires = "dry_run")]
+        count: usize,
+    },
+    /// Save the whole configuration of the amplifier to a JSON file
+    ///
+    /// The backup holds the state of every zone, the custom names, the net/USB and tuner
+    /// presets, the clock and alarm settings and the system settings.
+    Backup { file: PathBuf },
+    /// Bring the amplifier back to a backup, changing only what differs
+    ///
+    /// Differences the amplifier cannot take over the network (net/USB presets, alarm)
+    /// are listed to be done by hand.
+    Restore {
+        file: PathBuf,
+        /// Print the calls that would be made without making them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a completion script, e.g. `yamaha_cli completions bash > ~/.local/share/bash-completion/completions/yamaha_cli`
+    ///
+    /// Inputs and sound programs are taken from the features of the selected device
+    /// when they have been cached, and from the values known to the library otherwise.
+    Completions {
+        shell: clap_complete::Shell,
+        /// Fetch the features of the selected device before generating the script
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Print the man page, or write one page per subcommand into a directory
+    Man {
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+    /// Call any YXC endpoint, e.g. `raw system/getFuncStatus` or `raw main/setSleep sleep=30`
+    Raw {
+        /// Endpoint path, relative to `/YamahaExtendedControl/v1/`
+        path: String,
+        /// Query parameters
+        #[arg(value_name = "KEY=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+}
+
+/// Volume requested by `volume`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeArg {
+    Up,
+    Down,
+    Level(f64),
+}
+
+impl FromStr for VolumeArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            _ => s
+                .parse()
+                .map(Self::Level)
+                .map_err(|_| format!("expected a number, `up` or `down`, got `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SceneAction {
+    /// Save the current state of the zone (power, input, volume, program, tone, ...)
+    Save { name: String },
+    /// Bring the zone to a saved scene, changing only what differs
+    ///
+    /// The zone is the one the scene was saved from, unless `--zone` is given.
+    Apply {
+        name: String,
+        /// Print the calls that would be made without making them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the saved scenes
+    List,
+    /// Show the settings of a saved scene
+    Show { name: String },
+    /// Delete a saved scene
+    Delete { name: String },
+}
+
+/// Progression of `fade`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Curve {
+    /// Even steps in dB
+    Linear,
+    /// Even steps in amplitude: fade-outs stay loud longer, fade-ins rise quickly
+    Log,
+}
+
+impl From<Curve> for FadeCurve {
+    fn from(curve: Curve) -> Self {
+        match curve {
+            Curve::Linear => FadeCurve::Linear,
+            Curve::Log => FadeCurve::Logarithmic,
+        }
+    }
+}
+
+/// Behaviour of `run` when a command fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorPolicy {
+    /// Skip the rest of the script for this device
+    Stop,
+    /// Report the failure and go on with the next command
+    Continue,
+}
+
+/// On/off argument, with `toggle` resolved against the current state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Switch {
+    On,
+    Off,
+    Toggle,
+}
+
+/// Rejects values unknown to `model.rs`, so typos are reported before contacting the device
+pub fn parse_known<T: FromStr>(s: &str) -> Result<T, T::Err> {
+    s.parse()
+}
+
+/// [`parse_known`] with the values of `model.rs` listed for help and shell completion
+#[derive(Clone)]
+pub struct KnownValues<T: 'static>(pub &'static [T]);
+
+impl<T> TypedValueParser for KnownValues<T>
+where
+    T: FromStr + Display + Clone + Send + Sync + 'static,
+    T::Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<T, clap::Error> {
+        let parse: fn(&str) -> Result<T, T::Err> = parse_known;
+        parse.parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            self.0
+                .iter()
+                .map(|value| PossibleValue::new(value.to_string())),
+        ))
+    }
+}
+
+/// Values of `model.rs` listed for help and shell completion, with any other value kept
+/// in the enum's `Unknown` variant: devices accept inputs and programs this crate does
+/// not know yet, so the device is left to reject the unsupported ones
+#[derive(Clone)]
+pub struct OpenValues<T: 'static>(pub &'static [T], pub fn(&str) -> T);
+
+impl<T> TypedValueParser for OpenValues<T>
+where
+    T: Display + Clone + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<T, clap::Error> {
+        let value = clap::builder::NonEmptyStringValueParser::new().parse_ref(cmd, arg, value)?;
+        Ok(self.1(&value))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            self.0
+                .iter()
+                .map(|value| PossibleValue::new(value.to_string())),
+        ))
+    }
+}
+
+/// `AmpAddress` also converts from `&str`, which clap would pick over `FromStr` and turn
+/// an invalid port into a bare host name instead of an error
+fn parse_address(s: &str) -> Result<AmpAddress, ParseEnumError> {
+    s.parse()
+}
+
+fn parse_millis(s: &str) -> Result<Duration, std::num::ParseIntError> {
+    s.parse().map(Duration::from_millis)
+}
+
+/// Parses `500ms`, `2s`, `1m` or `2h`, a bare number being in seconds
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(index) => s.split_at(index),
+        None => (s, "s"),
+    };
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration `{s}`"))?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(format!("unknown unit in `{s}`, use ms, s, m or h")),
+    };
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_options_follow_the_subcommand() {
+        let cli = Cli::try_parse_from([
+            "yamaha_cli",
+            "volume",
+            "-40.5",
+            "--db",
+            "--host",
+            "[fe80::1]:8080",
+            "--zone",
+            "zone2",
+            "--json",
+        ])
+        .unwrap();
+        assert_eq!(cli.global.zone, Some(Zone::Zone2));
+        assert_eq!(cli.global.host.unwrap().port, 8080);
+        assert!(cli.global.json);
+        assert!(matches!(
+            cli.command,
+            Command::Volume {
+                level: Some(VolumeArg::Level(-40.5)),
+                db: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn inputs_and_programs_unknown_to_the_crate_reach_the_device() {
+        let cli = Cli::try_parse_from(["yamaha_cli", "input", "hdmi9"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Input { input: Some(Input::Unknown(input)) } if input == "hdmi9"
+        ));
+        let cli = Cli::try_parse_from(["yamaha_cli", "program", "sci-fi"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Program {
+                program: Some(SoundProgram::SciFi)
+            }
+        ));
+    }
+
+    #[test]
+    fn unknown_values_are_rejected() {
+        assert!(Cli::try_parse_from(["yamaha_cli", "power", "sleepy"]).is_err());
+        assert!(Cli::try_parse_from(["yamaha_cli", "input", ""]).is_err());
+        assert!(Cli::try_parse_from(["yamaha_cli", "raw", "main/setSleep", "sleep"]).is_err());
+        assert!(Cli::try_parse_from(["yamaha_cli", "volume", "loud"]).is_err());
+        assert!(Cli::try_parse_from(["yamaha_cli", "discover", "--mask", "33"]).is_err());
+        assert!(Cli::try_parse_from(["yamaha_cli", "discover", "--mask", "0"]).is_err());
+        assert!(
+            Cli::try_parse_from(["yamaha_cli", "status", "-d", "living", "-H", "amp"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,879 @@
+use crate::cli::{Cli, Command, GlobalArgs, SceneAction, Switch, VolumeArg};
+use crate::config::{Config, Device, Discovered};
+use crate::{completions, fleet, schedule, script, shell, tui, watch};
+use serde_json::{Value, json};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use yamaha_api::{
+    AmpAddress, Backup, DiscoveryConfig, FadeOutcome, GetFeatures, GetStatus, RestorePlan, Scene,
+    SceneStore, VolumePolicy, YamahaAmpAsync, YamahaAmpBuilder, Zone, discover_amplifiers,
+};
+
+/// Result of a command, printed as text or as JSON depending on `--json`
+#[derive(Debug)]
+pub struct Report {
+    pub json: Value,
+    pub text: String,
+}
+
+impl Report {
+    pub fn new(json: Value, text: impl Into<String>) -> Self {
+        Self {
+            json,
+            text: text.into(),
+        }
+    }
+}
+
+/// Invalid combination of arguments and config, reported with the usage exit code
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+pub async fn run(cli: &Cli) -> anyhow::Result<Report> {
+    let global = &cli.global;
+    if fleet::selected(global) && !matches!(cli.command, Command::Run { .. }) {
+        if !is_device_command(&cli.command) {
+            return Err(UsageError(
+                "--all and --tag only apply to device commands and `run`".to_string(),
+            )
+            .into());
+        }
+        fleet::run(global, &cli.command).await?;
+        return Ok(Report::new(Value::Null, ""));
+    }
+    match &cli.command {
+        Command::Discover {
+            subnet,
+            mask,
+            scan_timeout,
+            port,
+            save,
+        } => {
+            let config = DiscoveryConfig {
+                subnet: *subnet,
+                mask: *mask,
+                timeout: *scan_timeout,
+                port: *port,
+                ..Default::default()
+            };
+            discover(global, config, *save).await
+        }
+        Command::Watch { ndjson, interval } => {
+            let mut stdout = std::io::stdout();
+            watch::watch(global, *ndjson || global.json, *interval, &mut stdout, None).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Shell => {
+            shell::shell(global).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Tui { interval } => {
+            tui::tui(global, *interval).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Run {
+            script,
+            devices,
+            on_error,
+        } => {
+            let devices = match fleet::selected(global) {
+                true => fleet::devices(global)?
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect(),
+                false => devices.clone(),
+            };
+            script::run(global, script, &devices, *on_error).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Scene { action } => scene(global, action).await,
+        Command::Daemon {
+            dry_run: true,
+            count,
+        } => schedule::upcoming(global, *count),
+        Command::Daemon { .. } => {
+            schedule::daemon(global).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Completions { shell, refresh } => {
+            completions::completions(global, *shell, *refresh, &mut std::io::stdout()).await?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        Command::Man { out_dir } => {
+            completions::man(out_dir.as_deref(), &mut std::io::stdout())?;
+            Ok(Report::new(Value::Null, ""))
+        }
+        command => {
+            let (amp, zone) = connect(global).await?;
+            execute(&amp, &zone, command).await
+        }
+    }
+}
+
+/// Runs a command against an amplifier that is already connected, as the shell does
+pub async fn execute(
+    amp: &YamahaAmpAsync,
+    zone: &Zone,
+    command: &Command,
+) -> anyhow::Result<Report> {
+    match command {
+        Command::Status => {
+            let raw = amp.get_zone_status(zone.clone()).await?;
+            let status: GetStatus = serde_json::from_value(raw.clone())?;
+            Ok(Report::new(raw, describe_status(zone, &status)))
+        }
+        Command::Power { state } => {
+            if let Some(state) = state {
+                amp.set_zone_power(zone.clone(), state.clone()).await?;
+            }
+            let status = zone_status(amp, zone).await?;
+            Ok(Report::new(
+                json!({ "power": status.power }),
+                format!("Power: {}", status.power),
+            ))
+        }
+        Command::Volume { level, db, step } => {
+            if let Some(level) = level {
+                set_volume(amp, zone, *level, *db, *step).await?;
+            }
+            let status = zone_status(amp, zone).await?;
+            Ok(Report::new(
+                json!({
+                    "volume": status.volume,
+                    "max_volume": status.max_volume,
+                    "actual_volume": status.actual_volume,
+                }),
+                format!("Volume: {}", describe_volume(&status)),
+            ))
+        }
+        Command::Fade {
+            target,
+            over,
+            curve,
+        } => {
+            let outcome = amp
+                .fade_volume(zone.clone(), *target, *over, (*curve).into())
+                .await?;
+            Ok(match outcome {
+                FadeOutcome::Completed => Report::new(
+                    json!({ "completed": true, "volume_db": target }),
+                    format!("Volume: {target} dB"),
+                ),
+                FadeOutcome::Interrupted { volume_db } => Report::new(
+                    json!({ "completed": false, "volume_db": volume_db }),
+                    format!("Fade stopped at {volume_db} dB, the volume was changed meanwhile"),
+                ),
+            })
+        }
+        Command::Mute { state } => {
+            if let Some(state) = state {
+                let enable = match state {
+                    Switch::On => true,
+                    Switch::Off => false,
+                    Switch::Toggle => !zone_status(amp, zone).await?.mute,
+                };
+                amp.set_zone_mute(zone.clone(), enable).await?;
+            }
+            let status = zone_status(amp, zone).await?;
+            Ok(Report::new(
+                json!({ "mute": status.mute }),
+                format!("Mute: {}", on_off(status.mute)),
+            ))
+        }
+        Command::Input { input } => {
+            if let Some(input) = input {
+                amp.set_zone_input(zone.clone(), input.clone()).await?;
+            }
+            let status = zone_status(amp, zone).await?;
+            Ok(Report::new(
+                json!({ "input": status.input, "input_text": status.input_text }),
+                format!("Input: {}", describe_input(&status)),
+            ))
+        }
+        Command::Preset { num } => {
+            amp.recall_netusb_preset(zone.clone(), *num).await?;
+            let status = zone_status(amp, zone).await?;
+            Ok(Report::new(
+                json!({ "preset": num, "input": status.input, "input_text": status.input_text }),
+                format!("Preset {num}, input: {}", describe_input(&status)),
+            ))
+        }
+        Command::Program { program } => {
+            if let Some(program) = program {
+                amp.set_zone_sound_program(zone.clone(), program.clone())
+                    .await?;
+            }
+            let status = zone_status(amp, zone).await?;
+            let text = match &status.sound_program {
+                Some(program) => format!("Sound program: {program}"),
+                None => format!("Zone {zone} has no sound program"),
+            };
+            Ok(Report::new(
+                json!({ "sound_program": status.sound_program }),
+                text,
+            ))
+        }
+        Command::Zone => zones(amp).await,
+        Command::Features => {
+            let raw = amp.get_features().await?;
+            let features: GetFeatures = serde_json::from_value(raw.clone())?;
+            completions::remember_features(amp, &raw);
+            Ok(Report::new(raw, describe_features(&features)))
+        }
+        Command::Raw { path, params } => {
+            let params: Vec<(&str, &str)> = params
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect();
+            let raw = amp.raw_get(path, &params).await?;
+            let text = serde_json::to_string_pretty(&raw)?;
+            Ok(Report::new(raw, text))
+        }
+        Command::Backup { file } => {
+            let backup = Backup::capture(amp).await?;
+            std::fs::write(file, backup.to_json()?)?;
+            Ok(Report::new(
+                json!({ "file": file, "zones": backup.zones.keys().collect::<Vec<_>>() }),
+                format!(
+                    "Saved {} ({} zones) to {}",
+                    backup.device.model,
+                    backup.zones.len(),
+                    file.display()
+                ),
+            ))
+        }
+        Command::Restore { file, dry_run } => {
+            let backup = Backup::from_json(&std::fs::read_to_string(file)?)?;
+            let plan = if *dry_run {
+                backup.plan(&Backup::capture(amp).await?)
+            } else {
+                backup.restore(amp).await?
+            };
+            let calls: Vec<String> = plan.calls.iter().map(ToString::to_string).collect();
+            Ok(Report::new(
+                json!({ "calls": calls, "manual": plan.manual }),
+                describe_restore(&plan, *dry_run),
+            ))
+        }
+        Command::Discover { .. }
+        | Command::Watch { .. }
+        | Command::Shell
+        | Command::Tui { .. }
+        | Command::Run { .. }
+        | Command::Scene { .. }
+        | Command::Daemon { .. }
+        | Command::Completions { .. }
+        | Command::Man { .. } => {
+            Err(UsageError("this command is not available here".to_string()).into())
+        }
+    }
+}
+
+/// Whether `command` is handled by [`execute`], needing nothing but a connected amplifier
+pub fn is_device_command(command: &Command) -> bool {
+    !matches!(
+        command,
+        Command::Discover { .. }
+            | Command::Watch { .. }
+            | Command::Shell
+            | Command::Tui { .. }
+            | Command::Run { .. }
+            | Command::Scene { .. }
+            | Command::Daemon { .. }
+            | Command::Completions { .. }
+            | Command::Man { .. }
+    )
+}
+
+/// Connects to the amplifier given by `--host`, `--device` or the default device, and
+/// returns it with the zone to control
+pub async fn connect(global: &GlobalArgs) -> anyhow::Result<(YamahaAmpAsync, Zone)> {
+    let (builder, zone) = builder(global)?;
+    Ok((builder.connect().await?, zone))
+}
+
+/// Prepares the connection to the selected amplifier, for commands needing extra settings
+pub fn builder(global: &GlobalArgs) -> anyhow::Result<(YamahaAmpBuilder, Zone)> {
+    let (address, zone, limits) = selected(global)?;
+    let builder = amp_builder(global, address, limits);
+    Ok((builder, zone))
+}
+
+/// Connection settings shared by single devices and fleets: timeout and volume limits
+pub fn amp_builder(
+    global: &GlobalArgs,
+    address: AmpAddress,
+    limits: VolumePolicy,
+) -> YamahaAmpBuilder {
+    let mut builder = YamahaAmpBuilder::from_address(address);
+    if let Some(timeout) = global.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if !limits.is_empty() {
+        builder = builder.volume_policy(limits);
+    }
+    builder.volume_override(global.override_limits)
+}
+
+/// Address and zone of the amplifier selected by `--host`, `--device` or the default device
+pub fn target(global: &GlobalArgs) -> anyhow::Result<(AmpAddress, Zone)> {
+    let (address, zone, _) = selected(global)?;
+    Ok((address, zone))
+}
+
+/// Address, zone and volume limits of the selected amplifier, a bare `--host` having no limits
+fn selected(global: &GlobalArgs) -> anyhow::Result<(AmpAddress, Zone, VolumePolicy)> {
+    if let Some(host) = &global.host {
+        return Ok((
+            host.clone(),
+            global.zone.clone().unwrap_or_default(),
+            VolumePolicy::default(),
+        ));
+    }
+
+    let path = config_path(global)?;
+    let config = Config::load(&path)?;
+    let spec = match (&global.device, &config.default) {
+        (Some(spec), _) | (None, Some(spec)) => spec,
+        (None, None) => {
+            return Err(UsageError(
+                "no amplifier selected, pass --host <ADDRESS> or --device <NAME>, or set a \
+                 default device in the config file"
+                    .to_string(),
+            )
+            .into());
+        }
+    };
+    let (device, spec_zone) = config.device(spec).ok_or_else(|| {
+        UsageError(format!(
+            "unknown device `{spec}` in {}, known devices: {}",
+            path.display(),
+            config
+                .devices
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+    let zone = global
+        .zone
+        .clone()
+        .or(spec_zone)
+        .or_else(|| device.zone.clone())
+        .unwrap_or_default();
+    Ok((device.host.clone(), zone, device.limits.clone()))
+}
+
+pub fn config_path(global: &GlobalArgs) -> anyhow::Result<PathBuf> {
+    match &global.config {
+        Some(path) => Ok(path.clone()),
+        None => Config::default_path()
+            .ok_or_else(|| UsageError("no config directory, pass --config <PATH>".into()).into()),
+    }
+}
+
+/// Scenes are kept next to the config file, in `scenes/<name>.json`
+fn scene_store(global: &GlobalArgs) -> anyhow::Result<SceneStore> {
+    let path = config_path(global)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    Ok(SceneStore::new(dir.join("scenes")))
+}
+
+async fn scene(global: &GlobalArgs, action: &SceneAction) -> anyhow::Result<Report> {
+    let store = scene_store(global)?;
+    match action {
+        SceneAction::Save { name } => {
+            let (amp, zone) = connect(global).await?;
+            let scene = Scene::capture(&amp, name.as_str(), zone.clone()).await?;
+            store.save(&scene)?;
+            Ok(Report::new(
+                serde_json::to_value(&scene)?,
+                format!("Saved zone {zone} as scene `{name}`"),
+            ))
+        }
+        SceneAction::Apply { name, dry_run } => {
+            let scene = store.load(name)?;
+            let (amp, _) = connect(global).await?;
+            let zone = global.zone.clone().unwrap_or_else(|| scene.zone.clone());
+            let calls = if *dry_run {
+                scene.plan(&zone_status(&amp, &zone).await?)
+            } else {
+                scene.apply(&amp, &zone).await?
+            };
+            let calls: Vec<String> = calls.iter().map(|call| call.path(&zone)).collect();
+            let text = match (calls.is_empty(), dry_run) {
+                (true, _) => format!("Zone {zone} already matches scene `{name}`"),
+                (false, true) => format!("Would call:\n{}", calls.join("\n")),
+                (false, false) => format!("Applied scene `{name}` to zone {zone}"),
+            };
+            Ok(Report::new(json!({ "zone": zone, "calls": calls }), text))
+        }
+        SceneAction::List => {
+            let names = store.list()?;
+            Ok(Report::new(json!(names), names.join("\n")))
+        }
+        SceneAction::Show { name } => {
+            let scene = store.load(name)?;
+            let json = serde_json::to_value(&scene)?;
+            let text = serde_json::to_string_pretty(&json)?;
+            Ok(Report::new(json, text))
+        }
+        SceneAction::Delete { name } => {
+            store.remove(name)?;
+            Ok(Report::new(Value::Null, format!("Deleted scene `{name}`")))
+        }
+    }
+}
+
+pub async fn zone_status(amp: &YamahaAmpAsync, zone: &Zone) -> anyhow::Result<GetStatus> {
+    let raw = amp.get_zone_status(zone.clone()).await?;
+    Ok(serde_json::from_value(raw)?)
+}
+
+async fn set_volume(
+    amp: &YamahaAmpAsync,
+    zone: &Zone,
+    level: VolumeArg,
+    db: bool,
+    step: Option<u32>,
+) -> anyhow::Result<()> {
+    let zone = zone.clone();
+    match level {
+        VolumeArg::Up => amp.set_zone_volume_up(zone, step).await?,
+        VolumeArg::Down => amp.set_zone_volume_down(zone, step).await?,
+        VolumeArg::Level(value) if db => amp.set_zone_volume_db(zone, value).await?,
+        VolumeArg::Level(value) => {
+            if value.fract() != 0.0 || value < 0.0 {
+                return Err(UsageError(
+                    "volume steps are positive integers, use --db for a level in dB".into(),
+                )
+                .into());
+            }
+            amp.set_zone_volume(zone, value as i64).await?
+        }
+    };
+    Ok(())
+}
+
+async fn discover(
+    global: &GlobalArgs,
+    config: DiscoveryConfig,
+    save: bool,
+) -> anyhow::Result<Report> {
+    let amps = discover_amplifiers(Some(config)).await?;
+    let saved = if save {
+        save_discovered(global, &amps)?
+    } else {
+        Vec::new()
+    };
+    let json = amps
+        .iter()
+        .map(|amp| {
+            json!({
+                "address": amp.address.authority(),
+                "model": amp.info.model,
+                "device_id": amp.info.device_id,
+            })
+        })
+        .collect();
+    let mut text = format!(
+        "{} amplifier(s) found on {}/{}",
+        amps.len(),
+        config.subnet,
+        config.mask
+    );
+    for amp in &amps {
+        write!(
+            text,
+            "\n{:<24} {:<12} {}",
+            amp.address.authority(),
+            amp.info.model,
+            amp.info.device_id
+        )?;
+    }
+    for change in &saved {
+        write!(text, "\n{change}")?;
+    }
+    Ok(Report::new(json, text))
+}
+
+/// Adds the amplifiers found to the config file, returning the changes made to it
+fn save_discovered(
+    global: &GlobalArgs,
+    amps: &[YamahaAmpAsync],
+) -> anyhow::Result<Vec<Discovered>> {
+    let path = config_path(global)?;
+    let mut config = Config::load(&path)?;
+    let saved: Vec<Discovered> = amps
+        .iter()
+        .filter_map(|amp| {
+            config.add_discovered(Device {
+                host: amp.address.clone(),
+                zone: None,
+                model: Some(amp.info.model.clone()),
+                device_id: Some(amp.info.device_id.clone()),
+                tags: Vec::new(),
+                limits: VolumePolicy::default(),
+            })
+        })
+        .collect();
+    if !saved.is_empty() {
+        config.save(&path)?;
+    }
+    Ok(saved)
+}
+
+async fn zones(amp: &YamahaAmpAsync) -> anyhow::Result<Report> {
+    let features: GetFeatures = serde_json::from_value(amp.get_features().await?)?;
+    let mut json = Vec::new();
+    let mut text = String::new();
+    for zone in &features.zone {
+        let id = Zone::from_str_lossy(&zone.id);
+        let status = zone_status(amp, &id).await?;
+        json.push(json!({
+            "zone": id,
+            "power": status.power,
+            "volume": status.volume,
+            "max_volume": status.max_volume,
+            "mute": status.mute,
+            "input": status.input,
+        }));
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        write!(
+            text,
+            "{:<6} {:<8} {:<16} {}{}",
+            id,
+            status.power,
+            describe_volume(&status),
+            describe_input(&status),
+            if status.mute { " (muted)" } else { "" }
+        )?;
+    }
+    Ok(Report::new(Value::Array(json), text))
+}
+
+fn describe_status(zone: &Zone, status: &GetStatus) -> String {
+    let mut lines = vec![
+        format!("Zone:          {zone}"),
+        format!("Power:         {}", status.power),
+        format!("Volume:        {}", describe_volume(status)),
+        format!("Mute:          {}", on_off(status.mute)),
+        format!("Input:         {}", describe_input(status)),
+    ];
+    if let Some(program) = &status.sound_program {
+        lines.push(format!("Sound program: {program}"));
+    }
+    if let Some(pure_direct) = status.pure_direct {
+        lines.push(format!("Pure direct:   {}", on_off(pure_direct)));
+    }
+    if status.sleep > 0 {
+        lines.push(format!("Sleep:         {} min", status.sleep));
+    }
+    lines.join("\n")
+}
+
+fn describe_volume(status: &GetStatus) -> String {
+    let mut text = format!("{}/{}", status.volume, status.max_volume);
+    if let Some(actual) = &status.actual_volume {
+        write!(text, " ({} {})", actual.value, actual.unit).unwrap();
+    }
+    text
+}
+
+fn describe_input(status: &GetStatus) -> String {
+    match status.input_text.as_deref() {
+        Some(name) if name != status.input.as_str() => format!("{} ({name})", status.input),
+        _ => status.input.to_string(),
+    }
+}
+
+fn describe_features(features: &GetFeatures) -> String {
+    let mut lines = Vec::new();
+    for zone in &features.zone {
+        lines.push(format!("{}:", zone.id));
+        lines.push(format!("  inputs:   {}", zone.input_list.join(", ")));
+        if !zone.sound_program_list.is_empty() {
+            lines.push(format!(
+                "  programs: {}",
+                zone.sound_program_list.join(", ")
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+fn describe_restore(plan: &RestorePlan, dry_run: bool) -> String {
+    if plan.is_empty() {
+        return "Amplifier already matches the backup".to_string();
+    }
+    let mut lines = Vec::new();
+    if !plan.calls.is_empty() {
+        lines.push(if dry_run { "Would call:" } else { "Called:" }.to_string());
+        lines.extend(plan.calls.iter().map(|call| format!("  {call}")));
+    }
+    if !plan.manual.is_empty() {
+        lines.push("To do by hand:".to_string());
+        lines.extend(plan.manual.iter().map(|item| format!("  {item}")));
+    }
+    lines.join("\n")
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use yamaha_api::testing::MockDevice;
+    use yamaha_api::{PowerState, YamahaError};
+
+    async fn run_args(mock: &MockDevice, args: &[&str]) -> anyhow::Result<Report> {
+        let host = mock.address().authority();
+        let cli = Cli::try_parse_from(["yamaha_cli", "--host", &host].iter().chain(args))?;
+        run(&cli).await
+    }
+
+    #[tokio::test]
+    async fn setters_target_the_selected_zone() {
+        let mock = MockDevice::start().await.unwrap();
+        let main_volume = mock.state().zones[&Zone::Main].volume;
+        run_args(&mock, &["power", "on", "--zone", "zone2"])
+            .await
+            .unwrap();
+        let report = run_args(&mock, &["volume", "-40.5", "--db", "--zone", "zone2"])
+            .await
+            .unwrap();
+        assert_eq!(report.json["volume"], 80);
+        assert_eq!(report.text, "Volume: 80/161 (-40.5 dB)");
+
+        let report = run_args(&mock, &["volume", "up", "--step", "4", "-z", "zone2"])
+            .await
+            .unwrap();
+        assert_eq!(report.json["volume"], 84);
+        assert_eq!(mock.state().zones[&Zone::Main].volume, main_volume);
+
+        let err = run_args(&mock, &["volume", "40.5", "-z", "zone2"])
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<UsageError>().is_some());
+
+        let report = run_args(&mock, &["zone"]).await.unwrap();
+        assert_eq!(report.json[0]["power"], "standby");
+        assert_eq!(report.json[1]["zone"], "zone2");
+        assert_eq!(report.json[1]["volume"], 84);
+    }
+
+    #[tokio::test]
+    async fn fade_reaches_the_target() {
+        let mock = MockDevice::start().await.unwrap();
+        run_args(&mock, &["power", "on"]).await.unwrap();
+        let report = run_args(&mock, &["fade", "-70", "--over", "300ms", "--curve", "log"])
+            .await
+            .unwrap();
+        assert_eq!(report.text, "Volume: -70 dB");
+        assert_eq!(mock.state().zones[&Zone::Main].volume, 21);
+    }
+
+    #[tokio::test]
+    async fn config_limits_refuse_loud_volumes() {
+        let mock = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "[devices.lobby]\nhost = \"{}\"\n\n[devices.lobby.limits]\nmax_volume = 100\n",
+                mock.address().authority()
+            ),
+        )
+        .unwrap();
+        let config = path.to_str().unwrap();
+        let run_lobby = |args: &[&str]| {
+            let cli = Cli::try_parse_from(
+                ["yamaha_cli", "--config", config, "--device", "lobby"]
+                    .iter()
+                    .chain(args),
+            )
+            .unwrap();
+            async move { run(&cli).await }
+        };
+
+        run_lobby(&["power", "on"]).await.unwrap();
+        let err = run_lobby(&["volume", "-20", "--db"]).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<YamahaError>(),
+            Some(YamahaError::VolumeLimit { requested: 121, .. })
+        ));
+        assert_eq!(mock.state().zones[&Zone::Main].volume, 40);
+
+        let report = run_lobby(&["--override-limits", "volume", "-20", "--db"])
+            .await
+            .unwrap();
+        assert_eq!(report.json["volume"], 121);
+    }
+
+    #[tokio::test]
+    async fn toggle_reads_the_current_state() {
+        let mock = MockDevice::start().await.unwrap();
+        run_args(&mock, &["power", "on"]).await.unwrap();
+
+        let report = run_args(&mock, &["mute", "toggle"]).await.unwrap();
+        assert_eq!(report.text, "Mute: on");
+        let report = run_args(&mock, &["mute", "toggle"]).await.unwrap();
+        assert_eq!(report.json["mute"], false);
+    }
+
+    #[tokio::test]
+    async fn raw_passes_parameters_through() {
+        let mock = MockDevice::start().await.unwrap();
+        run_args(
+            &mock,
+            &["raw", "system/setNameText", "id=hdmi1", "text=Apple TV"],
+        )
+        .await
+        .unwrap();
+        assert_eq!(mock.state().names["hdmi1"], "Apple TV");
+
+        let err = run_args(&mock, &["raw", "main/setMute", "enable=true"])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<YamahaError>(),
+            Some(YamahaError::Device { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn discovered_devices_are_addressed_by_name() {
+        let mock = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let config = config.to_str().unwrap();
+        let port = mock.address().port.to_string();
+
+        let cli = Cli::try_parse_from(["yamaha_cli", "status", "--config", config]).unwrap();
+        let err = run(&cli).await.unwrap_err();
+        assert!(err.is::<UsageError>());
+
+        let args = [
+            "discover",
+            "--subnet",
+            "127.0.0.1",
+            "--mask",
+            "30",
+            "--port",
+            &port,
+            "--save",
+        ];
+        let cli = Cli::try_parse_from(["yamaha_cli", "--config", config].iter().chain(&args));
+        let report = run(&cli.unwrap()).await.unwrap();
+        assert!(
+            report.text.ends_with("Saved as `rx-v6a`"),
+            "{}",
+            report.text
+        );
+
+        let cli = Cli::try_parse_from(
+            ["yamaha_cli", "-d", "rx-v6a:zone2", "--config", config]
+                .iter()
+                .chain(&["power", "on"]),
+        );
+        run(&cli.unwrap()).await.unwrap();
+        assert_eq!(mock.state().zones[&Zone::Zone2].power, PowerState::On);
+
+        let cli = Cli::try_parse_from(["yamaha_cli", "status", "--config", config]).unwrap();
+        let report = run(&cli).await.unwrap();
+        assert_eq!(report.json["power"], "standby");
+
+        let cli = Cli::try_parse_from(["yamaha_cli", "status", "-d", "office", "--config", config]);
+        let err = run(&cli.unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("known devices: rx-v6a"));
+    }
+
+    #[tokio::test]
+    async fn scenes_are_saved_next_to_the_config() {
+        let mock = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let config = config.to_str().unwrap();
+        let scene = async |args: &[&str]| {
+            let args: Vec<&str> = ["--config", config, "scene"]
+                .iter()
+                .chain(args)
+                .copied()
+                .collect();
+            run_args(&mock, &args).await
+        };
+
+        run_args(&mock, &["power", "on", "-z", "zone2"])
+            .await
+            .unwrap();
+        run_args(&mock, &["volume", "70", "-z", "zone2"])
+            .await
+            .unwrap();
+        scene(&["save", "evening", "-z", "zone2"]).await.unwrap();
+        assert!(dir.path().join("scenes/evening.json").exists());
+        assert_eq!(scene(&["list"]).await.unwrap().text, "evening");
+
+        run_args(&mock, &["volume", "20", "-z", "zone2"])
+            .await
+            .unwrap();
+        let report = scene(&["apply", "evening", "--dry-run"]).await.unwrap();
+        assert_eq!(report.json["calls"][0], "zone2/setVolume");
+        assert_eq!(mock.state().zones[&Zone::Zone2].volume, 20);
+
+        scene(&["apply", "evening"]).await.unwrap();
+        assert_eq!(mock.state().zones[&Zone::Zone2].volume, 70);
+        let report = scene(&["apply", "evening"]).await.unwrap();
+        assert_eq!(report.text, "Zone zone2 already matches scene `evening`");
+
+        scene(&["delete", "evening"]).await.unwrap();
+        assert!(scene(&["show", "evening"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn restore_dry_run_leaves_the_device_alone() {
+        let mock = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("backup.json");
+        let file = file.to_str().unwrap();
+        run_args(
+            &mock,
+            &["raw", "system/setNameText", "id=main", "text=Lobby"],
+        )
+        .await
+        .unwrap();
+        let report = run_args(&mock, &["backup", file]).await.unwrap();
+        assert_eq!(report.text, format!("Saved RX-V6A (2 zones) to {file}"));
+
+        mock.update(|device| {
+            device.names.clear();
+        });
+        let report = run_args(&mock, &["restore", file, "--dry-run"])
+            .await
+            .unwrap();
+        assert_eq!(
+            report.text,
+            "Would call:\n  system/setNameText?id=main&text=Lobby"
+        );
+        assert!(mock.state().names.is_empty());
+
+        run_args(&mock, &["restore", file]).await.unwrap();
+        assert_eq!(mock.state().names["main"], "Lobby");
+        let report = run_args(&mock, &["restore", file]).await.unwrap();
+        assert_eq!(report.text, "Amplifier already matches the backup");
+    }
+}
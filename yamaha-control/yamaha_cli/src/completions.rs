@@ -0,0 +1,192 @@
+use crate::cli::{Cli, GlobalArgs};
+use crate::commands;
+use crate::config::Config;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use yamaha_api::{AmpAddress, GetFeatures, YamahaAmpAsync};
+
+/// Features of the amplifiers, kept under `<cache dir>/yamaha_cli/features/` so that
+/// completion scripts can be generated without the device
+pub struct FeaturesCache {
+    dir: PathBuf,
+}
+
+impl FeaturesCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache in the platform's cache dir, `None` if it has none
+    pub fn user() -> Option<Self> {
+        dirs::cache_dir().map(|dir| Self::new(dir.join("yamaha_cli").join("features")))
+    }
+
+    fn path(&self, address: &AmpAddress) -> PathBuf {
+        let name = address.authority().replace(
+            |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+            "_",
+        );
+        self.dir.join(format!("{name}.json"))
+    }
+
+    pub fn load(&self, address: &AmpAddress) -> Option<GetFeatures> {
+        let text = std::fs::read_to_string(self.path(address)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    pub fn store(&self, address: &AmpAddress, features: &Value) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path(address), features.to_string())?;
+        Ok(())
+    }
+}
+
+/// Caches the features just read from `amp`, completion being a convenience not worth
+/// failing a command for
+pub fn remember_features(amp: &YamahaAmpAsync, features: &Value) {
+    if let Some(cache) = FeaturesCache::user() {
+        let _ = cache.store(&amp.address, features);
+    }
+}
+
+/// Writes the completion script of `shell`
+///
+/// # Arguments
+/// * `refresh` - Read the features of the selected device instead of using the cache
+pub async fn completions(
+    global: &GlobalArgs,
+    shell: Shell,
+    refresh: bool,
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let features = if refresh {
+        let (amp, _) = commands::connect(global).await?;
+        let raw = amp.get_features().await?;
+        remember_features(&amp, &raw);
+        Some(serde_json::from_value(raw)?)
+    } else {
+        commands::target(global)
+            .ok()
+            .zip(FeaturesCache::user())
+            .and_then(|((address, _), cache)| cache.load(&address))
+    };
+    let devices: Vec<String> = commands::config_path(global)
+        .and_then(|path| Config::load(&path))
+        .map(|config| config.devices.into_keys().collect())
+        .unwrap_or_default();
+
+    let mut command = with_values(Cli::command(), features.as_ref(), &devices);
+    clap_complete::generate(shell, &mut command, "yamaha_cli", out);
+    Ok(())
+}
+
+/// Writes the man page, or one page per subcommand into `out_dir`
+pub fn man(out_dir: Option<&Path>, out: &mut impl Write) -> anyhow::Result<()> {
+    match out_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            clap_mangen::generate_to(Cli::command(), dir)?;
+        }
+        None => clap_mangen::Man::new(Cli::command()).render(out)?,
+    }
+    Ok(())
+}
+
+/// Replaces the values of `model.rs` by those of the device, and offers the configured
+/// device names to `--device`
+fn with_values(
+    mut command: clap::Command,
+    features: Option<&GetFeatures>,
+    devices: &[String],
+) -> clap::Command {
+    if let Some(features) = features {
+        let inputs = unique(features.zone.iter().flat_map(|zone| &zone.input_list));
+        let programs = unique(
+            features
+                .zone
+                .iter()
+                .flat_map(|zone| &zone.sound_program_list),
+        );
+        command = command
+            .mut_subcommand("input", |input| {
+                input.mut_arg("input", |arg| arg.value_parser(inputs))
+            })
+            .mut_subcommand("program", |program| {
+                program.mut_arg("program", |arg| arg.value_parser(programs))
+            });
+    }
+    if !devices.is_empty() {
+        command = command.mut_arg("device", |arg| arg.value_parser(devices.to_vec()));
+    }
+    command
+}
+
+fn unique<'a>(values: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for value in values {
+        if !unique.contains(value) {
+            unique.push(value.clone());
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bash(command: clap::Command) -> String {
+        let mut command = command;
+        let mut out = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut command, "yamaha_cli", &mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn completion_offers_device_values_or_known_ones() {
+        let known = bash(with_values(Cli::command(), None, &[]));
+        assert!(known.contains("net_radio"));
+        assert!(known.contains("sci-fi"));
+
+        let features = json!({
+            "zone": [
+                { "id": "main", "input_list": ["hdmi1", "spotify"],
+                  "sound_program_list": ["straight", "7ch_stereo"] },
+                { "id": "zone2", "input_list": ["spotify", "tuner"], "sound_program_list": [] }
+            ]
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FeaturesCache::new(dir.path());
+        let address: AmpAddress = "[fd00::126]:8080".parse().unwrap();
+        assert!(cache.load(&address).is_none());
+        cache.store(&address, &features).unwrap();
+        let features = cache.load(&address).unwrap();
+
+        let script = bash(with_values(
+            Cli::command(),
+            Some(&features),
+            &["living".to_string()],
+        ));
+        assert!(script.contains("hdmi1 spotify tuner"));
+        assert!(script.contains("straight 7ch_stereo"));
+        assert!(!script.contains("net_radio"));
+        assert!(script.contains("living"));
+    }
+
+    #[test]
+    fn man_page_lists_the_subcommands() {
+        let mut page = Vec::new();
+        man(None, &mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with(".ie"));
+        assert!(page.contains("yamaha_cli\\-volume"));
+
+        let dir = tempfile::tempdir().unwrap();
+        man(Some(dir.path()), &mut Vec::new()).unwrap();
+        assert!(dir.path().join("yamaha_cli-completions.1").exists());
+    }
+}
@@ -0,0 +1,309 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use yamaha_api::{AmpAddress, VolumePolicy, Zone};
+
+/// Named devices stored in `<config dir>/yamaha_cli/config.toml`
+///
+/// ```toml
+/// default = "living"
+///
+/// [devices.living]
+/// host = "192.168.1.126"
+///
+/// [devices.kitchen]
+/// host = "kitchen-amp.lan"
+/// zone = "zone2"
+/// tags = ["downstairs"]
+///
+/// [[jobs]]
+/// name = "night"
+/// when = "every day 23:00"
+/// all = true
+/// run = ["power standby"]
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// Device used when neither `--host` nor `--device` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub devices: BTreeMap<String, Device>,
+    /// Timed jobs run by `yamaha_cli daemon`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<Job>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Device {
+    pub host: AmpAddress,
+    /// Zone used when the command line does not pick one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<Zone>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// Groups the device belongs to, selected together with `--tag`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Volume limits enforced on every request, `--override-limits` bypassing them
+    #[serde(default, skip_serializing_if = "VolumePolicy::is_empty")]
+    pub limits: VolumePolicy,
+}
+
+/// A job of the scheduler: script lines run on some devices at set times
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub name: String,
+    /// `weekdays 08:00`, `every day 23:00`, `sat,sun 09:30,18:00` or a cron expression
+    /// such as `0 8 * * 1-5`, in the host's time zone
+    pub when: String,
+    /// Script lines, as in a `run` script, e.g. `fade -35`
+    pub run: Vec<String>,
+    /// Devices to run on, optionally with a zone (`kitchen:zone2`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub devices: Vec<String>,
+    /// Tags of the devices to run on, as with `--tag`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Run on every device, as with `--all`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all: bool,
+    /// What to do with runs missed while the daemon was stopped or the host asleep
+    #[serde(default)]
+    pub missed: Missed,
+    /// How late a missed run may still be caught up, e.g. `2h` [default: no limit]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Missed {
+    /// Report the missed run and wait for the next one
+    #[default]
+    Skip,
+    /// Run once as soon as possible, however many runs were missed
+    Run,
+}
+
+impl Config {
+    /// Default location of the config file, `None` if the platform has no config dir
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("yamaha_cli").join("config.toml"))
+    }
+
+    /// Reads the config file, a missing file being an empty config
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).with_context(|| format!("invalid config {}", path.display()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("cannot read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("cannot create {}", dir.display()))?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("cannot write {}", path.display()))
+    }
+
+    /// Resolves `name` or `name:zone` to a device and the zone given after the colon
+    pub fn device(&self, spec: &str) -> Option<(&Device, Option<Zone>)> {
+        if let Some(device) = self.devices.get(spec) {
+            return Some((device, None));
+        }
+        let (name, zone) = spec.rsplit_once(':')?;
+        let zone = zone.parse().ok()?;
+        Some((self.devices.get(name)?, Some(zone)))
+    }
+
+    /// Devices having at least one of `tags`, by name
+    pub fn tagged<'a>(
+        &'a self,
+        tags: &'a [String],
+    ) -> impl Iterator<Item = (&'a String, &'a Device)> {
+        self.devices
+            .iter()
+            .filter(|(_, device)| device.tags.iter().any(|tag| tags.contains(tag)))
+    }
+
+    /// Adds a discovered device unless its host or device id is already known
+    ///
+    /// A known device id found at a new host gets that host. Returns `None` if the config
+    /// already matches the device.
+    pub fn add_discovered(&mut self, device: Device) -> Option<Discovered> {
+        let same_id = self.devices.iter_mut().find(|(_, existing)| {
+            existing.device_id.is_some() && existing.device_id == device.device_id
+        });
+        if let Some((name, existing)) = same_id {
+            if existing.host == device.host {
+                return None;
+            }
+            let from = std::mem::replace(&mut existing.host, device.host);
+            return Some(Discovered::Moved {
+                name: name.clone(),
+                from,
+            });
+        }
+        if self
+            .devices
+            .values()
+            .any(|existing| existing.host == device.host)
+        {
+            return None;
+        }
+
+        let base = device
+            .model
+            .as_deref()
+            .unwrap_or("amp")
+            .to_lowercase()
+            .replace(|c: char| !c.is_ascii_alphanumeric(), "-");
+        let name = (1..)
+            .map(|i| match i {
+                1 => base.clone(),
+                i => format!("{base}-{i}"),
+            })
+            .find(|name| !self.devices.contains_key(name))
+            .unwrap();
+        self.default.get_or_insert_with(|| name.clone());
+        self.devices.insert(name.clone(), device);
+        Some(Discovered::Added(name))
+    }
+}
+
+/// Change made to the config file by [`Config::add_discovered`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Discovered {
+    /// New device, saved under this name derived from the model name
+    Added(String),
+    /// Known device found at a new host, `from` being its previous one
+    Moved { name: String, from: AmpAddress },
+}
+
+impl std::fmt::Display for Discovered {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added(name) => write!(f, "Saved as `{name}`"),
+            Self::Moved { name, from } => write!(f, "Updated the host of `{name}` (was {from})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovered(host: &str, model: &str, device_id: &str) -> Device {
+        Device {
+            host: host.parse().unwrap(),
+            zone: None,
+            model: Some(model.to_string()),
+            device_id: Some(device_id.to_string()),
+            tags: Vec::new(),
+            limits: VolumePolicy::default(),
+        }
+    }
+
+    #[test]
+    fn device_spec_may_name_a_zone() {
+        let config: Config = toml::from_str(
+            r#"
+            default = "living"
+
+            [devices.living]
+            host = "192.168.1.126"
+
+            [devices.kitchen]
+            host = "kitchen-amp.lan:8080"
+            zone = "zone2"
+            tags = ["downstairs", "music"]
+
+            [devices.kitchen.limits]
+            max_volume = 120
+            max_step = 10
+
+            [devices.kitchen.limits.zones.zone2]
+            max_volume = 100
+
+            [devices.office]
+            host = "192.168.1.127"
+            tags = ["work"]
+            "#,
+        )
+        .unwrap();
+
+        let (kitchen, zone) = config.device("kitchen").unwrap();
+        assert_eq!(kitchen.host.port, 8080);
+        assert_eq!(kitchen.zone, Some(Zone::Zone2));
+        assert_eq!(zone, None);
+        assert_eq!(kitchen.limits.limits(&Zone::Main).max_step, Some(10));
+        assert_eq!(kitchen.limits.limits(&Zone::Zone2).max_volume, Some(100));
+        assert_eq!(kitchen.limits.limits(&Zone::Zone2).max_step, None);
+
+        let (living, zone) = config.device("living:zone3").unwrap();
+        assert_eq!(living.host.authority(), "192.168.1.126");
+        assert_eq!(zone, Some(Zone::Zone3));
+
+        assert!(config.device("living:garage").is_none());
+        assert!(config.device("garage").is_none());
+
+        let tags = ["music".to_string(), "work".to_string()];
+        let tagged: Vec<&String> = config.tagged(&tags).map(|(name, _)| name).collect();
+        assert_eq!(tagged, ["kitchen", "office"]);
+    }
+
+    #[test]
+    fn discovered_devices_get_unique_names() {
+        let mut config = Config::default();
+        let first = config.add_discovered(discovered("192.168.1.10", "RX-V6A", "A1"));
+        let second = config.add_discovered(discovered("192.168.1.11", "RX-V6A", "A2"));
+        let again = config.add_discovered(discovered("192.168.1.11", "RX-V6A", "A2"));
+
+        assert_eq!(first, Some(Discovered::Added("rx-v6a".into())));
+        assert_eq!(second, Some(Discovered::Added("rx-v6a-2".into())));
+        assert_eq!(again, None);
+        assert_eq!(config.default.as_deref(), Some("rx-v6a"));
+    }
+
+    #[test]
+    fn known_devices_follow_their_new_host() {
+        let mut config = Config::default();
+        config.add_discovered(discovered("192.168.1.10", "RX-V6A", "A1"));
+        let moved = config.add_discovered(discovered("192.168.1.12", "RX-V6A", "A1"));
+
+        assert_eq!(
+            moved,
+            Some(Discovered::Moved {
+                name: "rx-v6a".into(),
+                from: "192.168.1.10".parse().unwrap(),
+            })
+        );
+        assert_eq!(
+            moved.unwrap().to_string(),
+            "Updated the host of `rx-v6a` (was 192.168.1.10)"
+        );
+        assert_eq!(config.devices.len(), 1);
+        assert_eq!(config.devices["rx-v6a"].host.to_string(), "192.168.1.12");
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        let mut config = Config::default();
+        config.add_discovered(discovered("[fd00::126]:8080", "YAS-209", "B1"));
+        config.save(&path).unwrap();
+        assert_eq!(Config::load(&path).unwrap(), config);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::cli::{Command, GlobalArgs};
+use crate::commands::{self, UsageError};
+use crate::config::{Config, Device};
+use crate::script::Output;
+use anyhow::bail;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Write;
+use yamaha_api::{Fleet, Zone};
+
+/// Whether `--all` or `--tag` asks for several devices
+pub fn selected(global: &GlobalArgs) -> bool {
+    global.all || !global.tags.is_empty()
+}
+
+/// Devices of the config file picked by `--all` or `--tag`, by name
+pub fn devices(global: &GlobalArgs) -> anyhow::Result<Vec<(String, Device)>> {
+    let path = commands::config_path(global)?;
+    let config = Config::load(&path)?;
+    let devices: Vec<(String, Device)> = if global.all {
+        config.devices.into_iter().collect()
+    } else {
+        config
+            .tagged(&global.tags)
+            .map(|(name, device)| (name.clone(), device.clone()))
+            .collect()
+    };
+    if devices.is_empty() {
+        let wanted = match global.all {
+            true => "configured".to_string(),
+            false => format!("tagged {}", global.tags.join(" or ")),
+        };
+        return Err(UsageError(format!("no device {wanted} in {}", path.display())).into());
+    }
+    Ok(devices)
+}
+
+/// Fleet of the devices picked by `--all` or `--tag`, with the zone to control on each
+pub fn fleet(global: &GlobalArgs) -> anyhow::Result<(Fleet, HashMap<String, Zone>)> {
+    let mut fleet = Fleet::new().with_concurrency(global.parallel);
+    if let Some(deadline) = global.device_timeout {
+        fleet = fleet.with_timeout(deadline);
+    }
+    let mut zones = HashMap::new();
+    for (name, device) in devices(global)? {
+        let builder = commands::amp_builder(global, device.host, device.limits);
+        fleet.push(name.clone(), builder.build()?);
+        let zone = global.zone.clone().or(device.zone).unwrap_or_default();
+        zones.insert(name, zone);
+    }
+    Ok((fleet, zones))
+}
+
+/// Runs `command` on the devices picked by `--all` or `--tag`, a few at a time, and
+/// prints the result of each device under its name
+pub async fn run(global: &GlobalArgs, command: &Command) -> anyhow::Result<()> {
+    let (fleet, zones) = fleet(global)?;
+    let mut out = Output::stdout(None, global.json);
+    let failed = run_fleet(&fleet, &zones, command, &mut out).await;
+    if !failed.is_empty() {
+        bail!(
+            "{} of {} devices failed: {}",
+            failed.len(),
+            fleet.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Returns the names of the devices on which `command` failed
+pub async fn run_fleet<W: Write, E: Write>(
+    fleet: &Fleet,
+    zones: &HashMap<String, Zone>,
+    command: &Command,
+    out: &mut Output<W, E>,
+) -> Vec<String> {
+    let results = fleet
+        .run(|name, amp| commands::execute(amp, &zones[name], command))
+        .await;
+
+    let mut failed = Vec::new();
+    for done in results {
+        match done.result {
+            Ok(report) if out.json => {
+                out.label = None;
+                out.print(&json!({ "device": done.name, "result": report.json }).to_string());
+            }
+            Ok(report) => {
+                out.label = Some(done.name);
+                out.print(&report.text);
+            }
+            Err(e) => {
+                out.label = Some(done.name.clone());
+                out.error(&e.to_string());
+                failed.push(done.name);
+            }
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::Parser;
+    use std::time::Duration;
+    use yamaha_api::PowerState;
+    use yamaha_api::testing::{Fault, MockDevice};
+
+    #[tokio::test]
+    async fn tagged_devices_run_together() {
+        let lobby = MockDevice::start().await.unwrap();
+        let conference = MockDevice::start().await.unwrap();
+        let office = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "[devices.lobby]\nhost = \"{}\"\ntags = [\"public\"]\n\n\
+                 [devices.conference]\nhost = \"{}\"\nzone = \"zone2\"\ntags = [\"rooms\"]\n\n\
+                 [devices.office]\nhost = \"{}\"\n",
+                lobby.address().authority(),
+                conference.address().authority(),
+                office.address().authority()
+            ),
+        )
+        .unwrap();
+        let config = path.to_str().unwrap();
+
+        let cli = Cli::try_parse_from(
+            [
+                "yamaha_cli",
+                "--config",
+                config,
+                "--tag",
+                "public",
+                "--tag",
+                "rooms",
+            ]
+            .iter()
+            .chain(&["power", "on"]),
+        )
+        .unwrap();
+        run(&cli.global, &cli.command).await.unwrap();
+        assert_eq!(lobby.state().zones[&Zone::Main].power, PowerState::On);
+        assert_eq!(conference.state().zones[&Zone::Zone2].power, PowerState::On);
+        assert_eq!(office.state().zones[&Zone::Main].power, PowerState::Standby);
+
+        let cli =
+            Cli::try_parse_from(["yamaha_cli", "--config", config, "--all", "power"]).unwrap();
+        let (fleet, zones) = fleet(&cli.global).unwrap();
+        office.fail_next("main/getStatus", Fault::Code(3), 1);
+        let mut out = Output {
+            label: None,
+            json: false,
+            out: Vec::new(),
+            err: Vec::new(),
+        };
+        let failed = run_fleet(&fleet, &zones, &cli.command, &mut out).await;
+        assert_eq!(failed, ["office"]);
+        assert_eq!(
+            String::from_utf8(out.out).unwrap(),
+            "[conference] Power: on\n[lobby] Power: on\n"
+        );
+        assert!(
+            String::from_utf8(out.err)
+                .unwrap()
+                .starts_with("[office] Error: ")
+        );
+
+        let cli = Cli::try_parse_from([
+            "yamaha_cli",
+            "--config",
+            config,
+            "--all",
+            "--device-timeout",
+            "200ms",
+            "power",
+        ])
+        .unwrap();
+        let (fleet, zones) = super::fleet(&cli.global).unwrap();
+        lobby.fail_next("main/getStatus", Fault::Delay(Duration::from_secs(2)), 1);
+        let mut out = Output {
+            label: None,
+            json: false,
+            out: Vec::new(),
+            err: Vec::new(),
+        };
+        let failed = run_fleet(&fleet, &zones, &cli.command, &mut out).await;
+        assert_eq!(failed, ["lobby"]);
+
+        let cli =
+            Cli::try_parse_from(["yamaha_cli", "--config", config, "--tag", "garage", "power"])
+                .unwrap();
+        let err = run(&cli.global, &cli.command).await.unwrap_err();
+        assert!(err.is::<UsageError>());
+    }
+}
@@ -1,78 +1,59 @@
-use std::net::Ipv4Addr;
-use std::time::Duration;
-use yamaha_api::{
-    DiscoveryConfig, GetStatus, SoundProgram, YamahaAmpAsync, YamahaAmpBlocking, Zone,
-};
+mod cli;
+mod commands;
+mod completions;
+mod config;
+mod fleet;
+mod schedule;
+mod script;
+mod shell;
+mod tui;
+mod watch;
+
+use clap::Parser;
+use std::process::ExitCode;
+use yamaha_api::YamahaError;
+
+/// Exit code when the amplifier could not be reached
+const EXIT_UNREACHABLE: u8 = 3;
+/// Exit code when the amplifier rejected the request
+const EXIT_DEVICE: u8 = 4;
+/// Exit code when a volume limit of the config file refused the request
+const EXIT_VOLUME_LIMIT: u8 = 5;
 
 #[tokio::main]
-async fn main() {
-    // Optional config
-    let cfg = DiscoveryConfig {
-        subnet: Ipv4Addr::new(192, 168, 1, 0),
-        mask: 24,
-        timeout: Duration::from_millis(200),
-        max_concurrent: 50,
-    };
-
-    // Mode async
-    let amps = YamahaAmpAsync::discover(Some(cfg))
-        .await
-        .expect("Failed to discover amps async");
-    for (index, amp) in amps.iter().enumerate() {
-        println!(
-            "Async found {}. {} -> ({})",
-            index + 1,
-            amp.info.model,
-            amp.ip,
-        );
-    }
-
-    let amp = YamahaAmpAsync::connect(Ipv4Addr::new(192, 168, 1, 126))
-        .await
-        .expect("Failed to connect async");
-    if let Some(amp) = amp {
-        println!("Directly Connected async to {}", amp.info.model);
-
-        if let Ok(e) = amp.get_zone_status(Zone::Main).await {
-            match serde_json::from_value::<GetStatus>(e) {
-                Ok(e) => println!("Main actual volume: {} / {}", e.volume, e.max_volume),
-                Err(e) => eprintln!("Error: {:?}", e),
+async fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
+    match commands::run(&cli).await {
+        Ok(report) => {
+            if cli.global.json {
+                if !report.json.is_null() {
+                    println!("{}", report.json);
+                }
+            } else if !report.text.is_empty() {
+                println!("{}", report.text);
             }
+            ExitCode::SUCCESS
         }
-
-        if let Err(e) = amp.set_sound_program(SoundProgram::Straight).await {
-            eprintln!("Error: {:?}", e);
+        Err(e) => {
+            eprintln!("Error: {e}");
+            exit_code(&e)
         }
     }
+}
 
-    println!();
-
-    // Mode sync
-    tokio::task::spawn_blocking(move || {
-        let amps = YamahaAmpBlocking::discover(Some(cfg)).expect("Failed to discover amps sync");
-        for (index, amp) in amps.iter().enumerate() {
-            println!(
-                "Sync found {}. {} -> ({})",
-                index + 1,
-                amp.info.model,
-                amp.ip
-            );
-        }
-
-        let amp = YamahaAmpBlocking::connect(Ipv4Addr::new(192, 168, 1, 126))
-            .expect("Failed to connect sync");
-        if let Some(amp) = amp {
-            println!("Directly Connected sync to {}", amp.info.model);
-
-            if let Ok(e) = amp.get_zone_status(Zone::Main) {
-                println!("Main: {}", e);
-            }
-
-            if let Err(e) = amp.set_sound_program(SoundProgram::Straight) {
-                eprintln!("Error: {:?}", e);
-            }
-        }
-    })
-    .await
-    .unwrap()
+fn exit_code(error: &anyhow::Error) -> ExitCode {
+    if error.is::<commands::UsageError>() {
+        return ExitCode::from(2);
+    }
+    match error.downcast_ref::<YamahaError>() {
+        Some(
+            YamahaError::Connect { .. }
+            | YamahaError::Timeout { .. }
+            | YamahaError::Elapsed(_)
+            | YamahaError::Resolve { .. },
+        ) => ExitCode::from(EXIT_UNREACHABLE),
+        Some(YamahaError::Device { .. }) => ExitCode::from(EXIT_DEVICE),
+        Some(YamahaError::VolumeLimit { .. }) => ExitCode::from(EXIT_VOLUME_LIMIT),
+        _ => ExitCode::FAILURE,
+    }
 }
@@ -0,0 +1,582 @@
+use crate::cli::{ErrorPolicy, GlobalArgs, parse_duration};
+use crate::commands::{self, Report, UsageError};
+use crate::config::{Config, Job, Missed};
+use crate::fleet;
+use crate::script::{self, Line};
+use anyhow::Context;
+use chrono::{DateTime, Local, TimeDelta};
+use cron::Schedule;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Delay after which a run counts as missed rather than merely late
+const LATE_TOLERANCE: TimeDelta = TimeDelta::seconds(60);
+
+/// Longest sleep of the daemon, so that a clock change or a suspended host is noticed
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Bound on the missed runs counted after a long downtime
+const MAX_MISSED: usize = 10_000;
+
+const DAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Times at which a job fires, one cron schedule per time of day
+#[derive(Debug, Clone)]
+pub struct When(Vec<Schedule>);
+
+impl FromStr for When {
+    type Err = String;
+
+    /// Parses `<days> <HH:MM>[,<HH:MM>...]` or a five-field cron expression
+    ///
+    /// Days are `every day` (or `daily`), `weekdays`, `weekends`, or names and ranges
+    /// such as `mon,wed,fri` or `mon-fri`.
+    fn from_str(text: &str) -> Result<Self, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.len() == 5 && !words[0].contains(':') {
+            let days = cron_days(words[4])?;
+            let expression = format!("0 {} {days}", words[..4].join(" "));
+            return Schedule::from_str(&expression)
+                .map(|schedule| When(vec![schedule]))
+                .map_err(|e| format!("invalid cron expression `{text}`: {e}"));
+        }
+
+        let Some((times, days)) = words.split_last() else {
+            return Err("empty schedule, e.g. `weekdays 08:00`".into());
+        };
+        let days = match days.join(" ").to_lowercase().as_str() {
+            "" | "every day" | "daily" => "*".to_string(),
+            "weekdays" => "Mon-Fri".to_string(),
+            "weekends" => "Sat,Sun".to_string(),
+            list => list
+                .split(',')
+                .map(|range| {
+                    range
+                        .split('-')
+                        .map(|day| day_name(day).ok_or_else(|| format!("unknown day `{day}`")))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(|range| range.join("-"))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join(","),
+        };
+        times
+            .split(',')
+            .map(|time| {
+                let (hour, minute) = time
+                    .split_once(':')
+                    .and_then(|(hour, minute)| Some((hour.parse().ok()?, minute.parse().ok()?)))
+                    .filter(|&(hour, minute): &(u32, u32)| hour < 24 && minute < 60)
+                    .ok_or_else(|| format!("invalid time `{time}`, expected HH:MM"))?;
+                Schedule::from_str(&format!("0 {minute} {hour} * * {days}"))
+                    .map_err(|e| format!("invalid schedule `{text}`: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(When)
+    }
+}
+
+impl When {
+    /// First time strictly after `time`, `None` if the schedule never fires again
+    pub fn next_after(&self, time: &DateTime<Local>) -> Option<DateTime<Local>> {
+        self.0
+            .iter()
+            .filter_map(|schedule| schedule.after(time).next())
+            .min()
+    }
+}
+
+/// `mon`, `Monday`, ... as the three-letter name cron expects
+fn day_name(day: &str) -> Option<String> {
+    let day = day.to_lowercase();
+    let full = DAYS
+        .iter()
+        .find(|name| day.len() >= 3 && name.starts_with(&day))?;
+    let mut name = full[..3].to_string();
+    name[..1].make_ascii_uppercase();
+    Some(name)
+}
+
+/// Day-of-week field of a standard cron expression, where 0 and 7 are Sunday, turned into
+/// names since the `cron` crate counts from Sunday = 1
+fn cron_days(field: &str) -> Result<String, String> {
+    if field.chars().all(|c| !c.is_ascii_digit()) {
+        return Ok(field.to_string());
+    }
+    let day = |number: &str| match number.parse::<usize>() {
+        Ok(number @ 0..=7) => Ok(day_name(DAYS[(number + 6) % 7]).unwrap_or_default()),
+        _ => Err(format!("invalid day of week `{number}`, use 0-7 or names")),
+    };
+    field
+        .split(',')
+        .map(|part| {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (part, None),
+            };
+            let range = match range {
+                "*" => "*".to_string(),
+                range => range
+                    .split('-')
+                    .map(day)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join("-"),
+            };
+            Ok(match step {
+                Some(step) => format!("{range}/{step}"),
+                None => range,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(|parts| parts.join(","))
+}
+
+/// Whether a job fires when the daemon wakes up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Due {
+    /// Run now, `at` being the time it was scheduled for
+    Run { at: DateTime<Local>, late: bool },
+    /// `count` runs were missed, the last one `at`, and are not caught up
+    Skip { at: DateTime<Local>, count: usize },
+}
+
+/// A job of the config file, checked and ready to run
+#[derive(Debug, Clone)]
+pub struct ScheduledJob {
+    pub job: Job,
+    pub when: When,
+    script: Arc<Vec<Line>>,
+    grace: Option<TimeDelta>,
+}
+
+impl ScheduledJob {
+    pub fn new(job: Job) -> Result<Self, String> {
+        let when = job.when.parse()?;
+        let script = script::parse(&job.run.join("\n")).map_err(|e| e.0)?;
+        if script.is_empty() {
+            return Err("nothing to run".into());
+        }
+        let grace = match &job.grace {
+            Some(grace) => Some(
+                TimeDelta::from_std(parse_duration(grace)?)
+                    .map_err(|_| format!("grace `{grace}` is too long"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            job,
+            when,
+            script: Arc::new(script),
+            grace,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.job.name
+    }
+
+    /// Runs due between the last check and `now`, the last one deciding the outcome
+    pub fn due(&self, last: DateTime<Local>, now: DateTime<Local>) -> Option<Due> {
+        let mut count = 0;
+        let mut at = None;
+        let mut time = last;
+        while let Some(next) = self.when.next_after(&time)
+            && next <= now
+            && count < MAX_MISSED
+        {
+            count += 1;
+            at = Some(next);
+            time = next;
+        }
+        let at = at?;
+        let late = now - at > LATE_TOLERANCE;
+        let catch_up = self.job.missed == Missed::Run && self.grace.is_none_or(|g| now - at <= g);
+        Some(match late && !catch_up {
+            true => Due::Skip { at, count },
+            false => Due::Run { at, late },
+        })
+    }
+
+    /// Devices the job runs on, an empty list meaning the default device
+    pub fn devices(&self, global: &GlobalArgs) -> anyhow::Result<Vec<String>> {
+        let mut devices = self.job.devices.clone();
+        if self.job.all || !self.job.tags.is_empty() {
+            let mut selection = global.clone();
+            selection.all = self.job.all;
+            selection.tags = self.job.tags.clone();
+            for (name, _) in fleet::devices(&selection)? {
+                if !devices.contains(&name) {
+                    devices.push(name);
+                }
+            }
+        }
+        Ok(devices)
+    }
+
+    /// The devices as written in the config, for listings
+    fn targets(&self) -> String {
+        let mut targets = self.job.devices.clone();
+        if self.job.all {
+            targets.push("all devices".into());
+        }
+        targets.extend(self.job.tags.iter().map(|tag| format!("tag {tag}")));
+        match targets.is_empty() {
+            true => "default device".into(),
+            false => targets.join(", "),
+        }
+    }
+
+    pub async fn run(&self, global: &GlobalArgs) -> anyhow::Result<()> {
+        let devices = self.devices(global)?;
+        script::run_on(
+            global,
+            self.script.clone(),
+            &devices,
+            ErrorPolicy::Stop,
+            Some(self.name()),
+        )
+        .await
+    }
+}
+
+/// Jobs of the config file, every invalid job being reported at once
+pub fn load_jobs(global: &GlobalArgs) -> anyhow::Result<Vec<ScheduledJob>> {
+    let path = commands::config_path(global)?;
+    let config = Config::load(&path)?;
+    if config.jobs.is_empty() {
+        return Err(UsageError(format!(
+            "no job in {}, add [[jobs]] entries with name, when and run",
+            path.display()
+        ))
+        .into());
+    }
+    let mut jobs = Vec::new();
+    let mut errors = Vec::new();
+    for job in config.jobs {
+        let name = job.name.clone();
+        if jobs.iter().any(|known: &ScheduledJob| known.name() == name) {
+            errors.push(format!("job `{name}`: defined twice"));
+            continue;
+        }
+        match ScheduledJob::new(job) {
+            Ok(job) => jobs.push(job),
+            Err(e) => errors.push(format!("job `{name}`: {e}")),
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(jobs),
+        false => Err(UsageError(errors.join("\n")).into()),
+    }
+}
+
+/// Last time the daemon checked each job, kept next to the config file so that runs
+/// missed while it was stopped are noticed on the next start
+fn state_path(global: &GlobalArgs) -> anyhow::Result<PathBuf> {
+    let path = commands::config_path(global)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    Ok(dir.join("schedule-state.json"))
+}
+
+fn load_state(path: &Path) -> anyhow::Result<BTreeMap<String, DateTime<Local>>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .with_context(|| format!("invalid scheduler state {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e).with_context(|| format!("cannot read {}", path.display())),
+    }
+}
+
+fn save_state(path: &Path, state: &BTreeMap<String, DateTime<Local>>) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(state)?)
+        .with_context(|| format!("cannot write {}", path.display()))
+}
+
+fn timestamp(time: &DateTime<Local>) -> String {
+    time.format("%a %Y-%m-%d %H:%M").to_string()
+}
+
+/// Runs the jobs at their times until interrupted, each job in its own task so that a
+/// long fade does not hold back the others
+pub async fn daemon(global: &GlobalArgs) -> anyhow::Result<()> {
+    let jobs = load_jobs(global)?;
+    let state_path = state_path(global)?;
+    let mut state = load_state(&state_path)?;
+    state.retain(|name, _| jobs.iter().any(|job| job.name() == name));
+    let started = Local::now();
+    println!("Scheduler started with {} job(s)", jobs.len());
+
+    loop {
+        let now = Local::now();
+        for job in &jobs {
+            let last = state.get(job.name()).copied().unwrap_or(started);
+            match job.due(last, now) {
+                Some(Due::Run { at, late }) => {
+                    match late {
+                        true => {
+                            println!("{}: catching up the run of {}", job.name(), timestamp(&at))
+                        }
+                        false => println!("{}: running", job.name()),
+                    }
+                    let (global, job) = (global.clone(), job.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = job.run(&global).await {
+                            eprintln!("[{}] Error: {e}", job.name());
+                        }
+                    });
+                }
+                Some(Due::Skip { at, count }) => println!(
+                    "{}: skipped {count} missed run(s), the last at {}",
+                    job.name(),
+                    timestamp(&at)
+                ),
+                None => {}
+            }
+            state.insert(job.name().to_string(), now);
+        }
+        save_state(&state_path, &state)?;
+
+        let wait = match jobs
+            .iter()
+            .filter_map(|job| job.when.next_after(&now))
+            .min()
+        {
+            Some(next) => (next - Local::now()).to_std().unwrap_or_default(),
+            None => MAX_SLEEP,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(wait.min(MAX_SLEEP)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Lists what the daemon would do if started now: missed runs, then the next `count`
+/// executions
+pub fn upcoming(global: &GlobalArgs, count: usize) -> anyhow::Result<Report> {
+    let jobs = load_jobs(global)?;
+    let state = load_state(&state_path(global)?)?;
+    let now = Local::now();
+    upcoming_at(&jobs, &state, now, count)
+}
+
+fn upcoming_at(
+    jobs: &[ScheduledJob],
+    state: &BTreeMap<String, DateTime<Local>>,
+    now: DateTime<Local>,
+    count: usize,
+) -> anyhow::Result<Report> {
+    let mut missed = Vec::new();
+    let mut text = String::new();
+    for job in jobs {
+        let Some(due) = state.get(job.name()).and_then(|last| job.due(*last, now)) else {
+            continue;
+        };
+        let (at, action) = match due {
+            Due::Run { at, .. } => (at, "run now"),
+            Due::Skip { at, .. } => (at, "skipped"),
+        };
+        writeln!(text, "Missed {}  {}: {action}", timestamp(&at), job.name())?;
+        missed.push(json!({ "job": job.name(), "at": at.to_rfc3339(), "action": action }));
+    }
+
+    let mut next: Vec<(DateTime<Local>, &ScheduledJob)> = Vec::new();
+    for job in jobs {
+        let mut time = now;
+        while next
+            .iter()
+            .filter(|(_, known)| known.name() == job.name())
+            .count()
+            < count
+            && let Some(at) = job.when.next_after(&time)
+        {
+            next.push((at, job));
+            time = at;
+        }
+    }
+    next.sort_by_key(|(at, _)| *at);
+    next.truncate(count);
+
+    let width = jobs.iter().map(|job| job.name().len()).max().unwrap_or(0);
+    for (at, job) in &next {
+        writeln!(
+            text,
+            "{}  {:width$}  on {}: {}",
+            timestamp(at),
+            job.name(),
+            job.targets(),
+            job.job.run.join("; ")
+        )?;
+    }
+    let upcoming: Vec<_> = next
+        .iter()
+        .map(|(at, job)| {
+            json!({
+                "job": job.name(),
+                "at": at.to_rfc3339(),
+                "devices": job.targets(),
+                "run": job.job.run,
+            })
+        })
+        .collect();
+    Ok(Report::new(
+        json!({ "missed": missed, "upcoming": upcoming }),
+        text.trim_end(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use chrono::TimeZone;
+    use clap::Parser;
+    use yamaha_api::testing::MockDevice;
+    use yamaha_api::{Input, PowerState, Zone};
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn job(when: &str, missed: Missed, grace: Option<&str>) -> ScheduledJob {
+        ScheduledJob::new(Job {
+            name: "night".into(),
+            when: when.into(),
+            run: vec!["power standby".into()],
+            devices: Vec::new(),
+            tags: Vec::new(),
+            all: true,
+            missed,
+            grace: grace.map(str::to_string),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn when_accepts_days_times_and_cron() {
+        // 2026-10-18 is a Sunday
+        let sunday = local(18, 12, 0);
+        let next = |when: &str| {
+            let when: When = when.parse().unwrap();
+            when.next_after(&sunday).unwrap()
+        };
+        assert_eq!(next("weekdays 08:00"), local(19, 8, 0));
+        assert_eq!(next("every day 23:00"), local(18, 23, 0));
+        assert_eq!(next("daily 07:15"), local(19, 7, 15));
+        assert_eq!(next("sat,Sunday 09:30,18:00"), local(18, 18, 0));
+        assert_eq!(next("wed-fri 6:05"), local(21, 6, 5));
+        assert_eq!(next("0 8 * * 1-5"), local(19, 8, 0));
+        assert_eq!(next("30 9 * * 0"), local(25, 9, 30));
+
+        for bad in [
+            "",
+            "weekdays 25:00",
+            "someday 08:00",
+            "weekdays 8h",
+            "0 8 * * 9",
+        ] {
+            assert!(bad.parse::<When>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn missed_runs_are_skipped_or_caught_up() {
+        let skip = job("every day 23:00", Missed::Skip, None);
+        assert_eq!(skip.due(local(18, 22, 0), local(18, 22, 59)), None);
+        assert_eq!(
+            skip.due(local(18, 22, 0), local(18, 23, 0)),
+            Some(Due::Run {
+                at: local(18, 23, 0),
+                late: false
+            })
+        );
+        assert_eq!(
+            skip.due(local(16, 22, 0), local(19, 7, 0)),
+            Some(Due::Skip {
+                at: local(18, 23, 0),
+                count: 3
+            })
+        );
+
+        let catch_up = job("every day 23:00", Missed::Run, None);
+        assert_eq!(
+            catch_up.due(local(16, 22, 0), local(19, 7, 0)),
+            Some(Due::Run {
+                at: local(18, 23, 0),
+                late: true
+            })
+        );
+        let within = job("every day 23:00", Missed::Run, Some("2h"));
+        assert!(matches!(
+            within.due(local(18, 22, 0), local(19, 0, 30)),
+            Some(Due::Run { late: true, .. })
+        ));
+        assert!(matches!(
+            within.due(local(18, 22, 0), local(19, 7, 0)),
+            Some(Due::Skip { count: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn jobs_run_on_their_devices_and_list_ahead() {
+        let lobby = MockDevice::start().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "[devices.lobby]\nhost = \"{}\"\ntags = [\"public\"]\n\n\
+                 [[jobs]]\nname = \"morning\"\nwhen = \"weekdays 08:00\"\ndevices = [\"lobby\"]\n\
+                 run = [\"power on\", \"input net_radio\", \"preset 1\", \"fade -50 --over 200ms\"]\n\n\
+                 [[jobs]]\nname = \"night\"\nwhen = \"every day 23:00\"\ntags = [\"public\"]\n\
+                 run = [\"power standby\"]\nmissed = \"run\"\n",
+                lobby.address().authority()
+            ),
+        )
+        .unwrap();
+        let cli = Cli::try_parse_from(["yamaha_cli", "--config", path.to_str().unwrap(), "daemon"])
+            .unwrap();
+        let jobs = load_jobs(&cli.global).unwrap();
+
+        jobs[0].run(&cli.global).await.unwrap();
+        let state = lobby.state();
+        assert_eq!(state.zones[&Zone::Main].power, PowerState::On);
+        assert_eq!(state.zones[&Zone::Main].input, Input::NetRadio);
+        assert_eq!(state.netusb.track, "FIP");
+        assert_eq!(state.zones[&Zone::Main].volume, 61);
+        assert_eq!(jobs[1].devices(&cli.global).unwrap(), ["lobby"]);
+        jobs[1].run(&cli.global).await.unwrap();
+        assert_eq!(lobby.state().zones[&Zone::Main].power, PowerState::Standby);
+
+        let state = BTreeMap::from([("night".to_string(), local(16, 22, 0))]);
+        let report = upcoming_at(&jobs, &state, local(18, 12, 0), 3).unwrap();
+        assert_eq!(report.json["missed"][0]["action"], "run now");
+        let upcoming = &report.json["upcoming"];
+        assert_eq!(upcoming.as_array().unwrap().len(), 3);
+        assert_eq!(upcoming[0]["job"], "night");
+        assert_eq!(upcoming[1]["job"], "morning");
+        assert_eq!(upcoming[1]["devices"], "lobby");
+        assert_eq!(upcoming[2]["job"], "night");
+        assert!(
+            report
+                .text
+                .starts_with("Missed Sat 2026-10-17 23:00  night: run now\n"),
+            "{}",
+            report.text
+        );
+    }
+}
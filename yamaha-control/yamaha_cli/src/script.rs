@@ -0,0 +1,489 @@
+use crate::cli::{ErrorPolicy, GlobalArgs, parse_duration};
+use crate::commands::{self, UsageError};
+use crate::shell::{ShellLine, UNAVAILABLE};
+use anyhow::{anyhow, bail};
+use clap::Parser;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use yamaha_api::{YamahaAmpAsync, Zone};
+
+/// One line of a script, kept with its number for the messages
+#[derive(Debug)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+    pub step: Step,
+}
+
+#[derive(Debug)]
+pub enum Step {
+    /// A command as typed in the shell, e.g. `volume 60 --zone zone2`
+    Command(ShellLine),
+    /// `wait 500ms`, `wait 2s`, `wait 1m`; a bare number is in seconds
+    Wait(Duration),
+    /// `if <field> <op> <value> then <step>`, checked against the status of the zone the
+    /// guarded command targets
+    If {
+        condition: Condition,
+        then: Box<Step>,
+    },
+    /// `on-error stop|continue`, applying to the following lines
+    OnError(ErrorPolicy),
+}
+
+/// Comparison of a field of `getStatus` with a value, e.g. `volume >= 80`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub field: String,
+    pub op: Op,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl Op {
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            ">" => Self::Gt,
+            "<=" => Self::Le,
+            ">=" => Self::Ge,
+            _ => return None,
+        })
+    }
+
+    fn holds(self, ordering: std::cmp::Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Gt => ordering.is_gt(),
+            Self::Le => ordering.is_le(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+/// Parses a whole script, so that a typo on the last line is reported before the first
+/// command is sent
+pub fn parse(text: &str) -> Result<Vec<Line>, UsageError> {
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+    for (index, text) in text.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let step = shlex::split(text)
+            .ok_or_else(|| "unbalanced quotes".to_string())
+            .and_then(|words| parse_step(&words));
+        match step {
+            Ok(step) => lines.push(Line {
+                number: index + 1,
+                text: text.to_string(),
+                step,
+            }),
+            Err(e) => errors.push(format!("line {}: {e}", index + 1)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(lines)
+    } else {
+        Err(UsageError(errors.join("\n")))
+    }
+}
+
+fn parse_step(words: &[String]) -> Result<Step, String> {
+    match words.first().map(String::as_str) {
+        Some("wait") => match words {
+            [_, duration] => parse_duration(duration).map(Step::Wait),
+            _ => Err("expected `wait <duration>`, e.g. `wait 2s`".into()),
+        },
+        Some("on-error") => match words.get(1).map(String::as_str) {
+            Some("stop") if words.len() == 2 => Ok(Step::OnError(ErrorPolicy::Stop)),
+            Some("continue") if words.len() == 2 => Ok(Step::OnError(ErrorPolicy::Continue)),
+            _ => Err("expected `on-error stop` or `on-error continue`".into()),
+        },
+        Some("if") => match words {
+            [_, field, op, value, then, rest @ ..] if then == "then" && !rest.is_empty() => {
+                let op = Op::parse(op).ok_or_else(|| {
+                    format!("unknown operator `{op}`, use ==, !=, <, >, <= or >=")
+                })?;
+                Ok(Step::If {
+                    condition: Condition {
+                        field: field.clone(),
+                        op,
+                        value: value.clone(),
+                    },
+                    then: Box::new(parse_step(rest)?),
+                })
+            }
+            _ => Err("expected `if <field> <op> <value> then <command>`".into()),
+        },
+        Some(word) if UNAVAILABLE.contains(&word) => {
+            Err(format!("`{word}` cannot be used in a script"))
+        }
+        _ => ShellLine::try_parse_from(words)
+            .map(Step::Command)
+            .map_err(|e| {
+                let message = e.to_string();
+                let first = message.lines().next().unwrap_or_default();
+                first.trim_start_matches("error: ").to_string()
+            }),
+    }
+}
+
+/// Runs the script on each device given with `--on`, or on the selected device
+///
+/// Devices given with `--on` run concurrently, every output line being prefixed with
+/// the device name.
+pub async fn run(
+    global: &GlobalArgs,
+    path: &Path,
+    devices: &[String],
+    policy: ErrorPolicy,
+) -> anyhow::Result<()> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("cannot read {}: {e}", path.display()))?;
+    let script = Arc::new(parse(&text)?);
+    run_on(global, script, devices, policy, None).await
+}
+
+/// Runs a parsed script like [`run`], output lines being labelled `<prefix>/<device>`
+/// when a prefix is given
+pub async fn run_on(
+    global: &GlobalArgs,
+    script: Arc<Vec<Line>>,
+    devices: &[String],
+    policy: ErrorPolicy,
+    prefix: Option<&str>,
+) -> anyhow::Result<()> {
+    if devices.is_empty() {
+        let (amp, zone) = commands::connect(global).await?;
+        let mut out = Output::stdout(prefix.map(str::to_string), global.json);
+        let failures = run_script(&amp, &zone, &script, policy, &mut out).await;
+        if failures > 0 {
+            bail!("script failed: {failures} error(s)");
+        }
+        return Ok(());
+    }
+
+    let mut tasks = JoinSet::new();
+    for device in devices {
+        let mut global = global.clone();
+        global.host = None;
+        global.device = Some(device.clone());
+        let script = script.clone();
+        let label = match prefix {
+            Some(prefix) => format!("{prefix}/{device}"),
+            None => device.clone(),
+        };
+        let mut out = Output::stdout(Some(label), global.json);
+        tasks.spawn(async move {
+            let failures = match commands::connect(&global).await {
+                Ok((amp, zone)) => run_script(&amp, &zone, &script, policy, &mut out).await,
+                Err(e) => {
+                    out.error(&format!("cannot connect: {e}"));
+                    1
+                }
+            };
+            (out.label, failures)
+        });
+    }
+
+    let mut failed = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        let (label, failures) = result?;
+        if failures > 0 {
+            failed.push(format!(
+                "{} ({failures} error(s))",
+                label.unwrap_or_default()
+            ));
+        }
+    }
+    if !failed.is_empty() {
+        failed.sort();
+        bail!("script failed: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Where a script run prints, each line prefixed with the device name if any
+pub struct Output<W, E> {
+    pub label: Option<String>,
+    pub json: bool,
+    pub out: W,
+    pub err: E,
+}
+
+impl Output<std::io::Stdout, std::io::Stderr> {
+    pub fn stdout(label: Option<String>, json: bool) -> Self {
+        Self {
+            label,
+            json,
+            out: std::io::stdout(),
+            err: std::io::stderr(),
+        }
+    }
+}
+
+impl<W: Write, E: Write> Output<W, E> {
+    pub fn print(&mut self, text: &str) {
+        for line in text.lines() {
+            let _ = match &self.label {
+                Some(label) => writeln!(self.out, "[{label}] {line}"),
+                None => writeln!(self.out, "{line}"),
+            };
+        }
+    }
+
+    pub fn error(&mut self, text: &str) {
+        let _ = match &self.label {
+            Some(label) => writeln!(self.err, "[{label}] Error: {text}"),
+            None => writeln!(self.err, "Error: {text}"),
+        };
+    }
+}
+
+/// Runs the lines of a script on a connected amplifier, returning the number of failures
+pub async fn run_script<W: Write, E: Write>(
+    amp: &YamahaAmpAsync,
+    zone: &Zone,
+    script: &[Line],
+    mut policy: ErrorPolicy,
+    out: &mut Output<W, E>,
+) -> usize {
+    let mut failures = 0;
+    for line in script {
+        if let Err(e) = run_step(amp, zone, &line.step, &mut policy, out).await {
+            out.error(&format!("line {} `{}`: {e}", line.number, line.text));
+            failures += 1;
+            if policy == ErrorPolicy::Stop {
+                break;
+            }
+        }
+    }
+    failures
+}
+
+async fn run_step<W: Write, E: Write>(
+    amp: &YamahaAmpAsync,
+    zone: &Zone,
+    step: &Step,
+    policy: &mut ErrorPolicy,
+    out: &mut Output<W, E>,
+) -> anyhow::Result<()> {
+    match step {
+        Step::Command(line) => {
+            let zone = line.zone.as_ref().unwrap_or(zone);
+            let report = commands::execute(amp, zone, &line.command).await?;
+            if line.json || out.json {
+                out.print(&report.json.to_string());
+            } else {
+                out.print(&report.text);
+            }
+        }
+        Step::Wait(duration) => tokio::time::sleep(*duration).await,
+        Step::OnError(new_policy) => *policy = *new_policy,
+        Step::If { condition, then } => {
+            let status = amp.get_zone_status(target_zone(then, zone).clone()).await?;
+            if evaluate(condition, &status)? {
+                Box::pin(run_step(amp, zone, then, policy, out)).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Zone targeted by a step: the `--zone` of its command, or the session's zone
+fn target_zone<'a>(step: &'a Step, zone: &'a Zone) -> &'a Zone {
+    match step {
+        Step::Command(line) => line.zone.as_ref().unwrap_or(zone),
+        Step::If { then, .. } => target_zone(then, zone),
+        Step::Wait(_) | Step::OnError(_) => zone,
+    }
+}
+
+/// Checks a condition against the raw `getStatus` response of the zone
+///
+/// `program` stands for `sound_program` and `volume_db` for the volume in dB. Numbers and
+/// booleans (`on`/`off` accepted) compare by value, other fields as text.
+fn evaluate(condition: &Condition, status: &Value) -> anyhow::Result<bool> {
+    let actual = match condition.field.as_str() {
+        "program" => &status["sound_program"],
+        "volume_db" => &status["actual_volume"]["value"],
+        field => &status[field],
+    };
+    let expected = condition.value.as_str();
+    let ordering = match actual {
+        Value::Number(number) => {
+            let expected: f64 = expected
+                .parse()
+                .map_err(|_| anyhow!("`{}` is a number, not `{expected}`", condition.field))?;
+            number.as_f64().unwrap_or_default().total_cmp(&expected)
+        }
+        Value::Bool(value) => {
+            let expected = match expected {
+                "true" | "on" => true,
+                "false" | "off" => false,
+                _ => bail!("`{}` is on or off, not `{expected}`", condition.field),
+            };
+            value.cmp(&expected)
+        }
+        Value::String(value) => value.as_str().cmp(expected),
+        Value::Null => bail!("no `{}` in the status of zone", condition.field),
+        _ => bail!("`{}` cannot be compared", condition.field),
+    };
+    Ok(condition.op.holds(ordering))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Command;
+    use yamaha_api::PowerState;
+    use yamaha_api::testing::MockDevice;
+
+    #[test]
+    fn parses_steps_and_reports_every_bad_line() {
+        let script = parse(
+            "# evening\n\
+             if power == standby then power on\n\
+             wait 1.5s\n\
+             on-error continue\n\
+             volume 60 --zone zone2\n",
+        )
+        .unwrap();
+        assert_eq!(script.len(), 4);
+        assert_eq!(script[0].number, 2);
+        let Step::If { condition, then } = &script[0].step else {
+            panic!("{:?}", script[0].step);
+        };
+        assert_eq!(condition.op, Op::Eq);
+        assert!(matches!(
+            **then,
+            Step::Command(ShellLine {
+                command: Command::Power { .. },
+                ..
+            })
+        ));
+        assert!(matches!(script[1].step, Step::Wait(d) if d == Duration::from_millis(1500)));
+        assert!(matches!(
+            script[2].step,
+            Step::OnError(ErrorPolicy::Continue)
+        ));
+
+        let err =
+            parse("wait soon\nvolume\nif mute = on then mute off\nwatch\nvolumme 3").unwrap_err();
+        let lines: Vec<&str> = err.0.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", err.0);
+        assert!(lines[0].starts_with("line 1: invalid duration"));
+        assert!(lines[1].starts_with("line 3: unknown operator `=`"));
+        assert!(lines[2].starts_with("line 4: `watch` cannot"));
+        assert!(lines[3].starts_with("line 5:"));
+    }
+
+    #[test]
+    fn conditions_compare_by_type() {
+        let status = serde_json::json!({
+            "power": "standby", "volume": 40, "mute": false, "sound_program": "straight",
+            "actual_volume": { "value": -60.5, "unit": "dB" },
+        });
+        let check = |field: &str, op: &str, value: &str| {
+            let condition = Condition {
+                field: field.into(),
+                op: Op::parse(op).unwrap(),
+                value: value.into(),
+            };
+            evaluate(&condition, &status)
+        };
+        assert!(check("power", "==", "standby").unwrap());
+        assert!(check("volume", ">=", "40").unwrap());
+        assert!(!check("volume", "<", "9").unwrap());
+        assert!(check("mute", "!=", "on").unwrap());
+        assert!(check("program", "==", "straight").unwrap());
+        assert!(check("volume_db", "<", "-40").unwrap());
+        assert!(check("volume", "==", "loud").is_err());
+        assert!(check("bass", "==", "0").is_err());
+    }
+
+    #[tokio::test]
+    async fn error_policy_decides_whether_to_go_on() {
+        let mock = MockDevice::start().await.unwrap();
+        let amp = mock.builder().connect().await.unwrap();
+        let script = parse(
+            "if power == standby then power on\n\
+             if power == standby then volume 10\n\
+             wait 10ms\n\
+             raw main/setBogus\n\
+             volume 70\n",
+        )
+        .unwrap();
+
+        let mut out = Output {
+            label: Some("living".into()),
+            json: false,
+            out: Vec::new(),
+            err: Vec::new(),
+        };
+        let failures = run_script(&amp, &Zone::Main, &script, ErrorPolicy::Stop, &mut out).await;
+        assert_eq!(failures, 1);
+        assert_eq!(mock.state().zones[&Zone::Main].power, PowerState::On);
+        assert_ne!(mock.state().zones[&Zone::Main].volume, 70);
+        assert_eq!(String::from_utf8_lossy(&out.out), "[living] Power: on\n");
+        let err = String::from_utf8_lossy(&out.err);
+        assert!(
+            err.starts_with("[living] Error: line 4 `raw main/setBogus"),
+            "{err}"
+        );
+
+        let failures =
+            run_script(&amp, &Zone::Main, &script, ErrorPolicy::Continue, &mut out).await;
+        assert_eq!(failures, 1);
+        assert_eq!(mock.state().zones[&Zone::Main].volume, 70);
+    }
+
+    #[tokio::test]
+    async fn conditions_check_the_zone_of_their_command() {
+        let mock = MockDevice::start().await.unwrap();
+        let amp = mock.builder().connect().await.unwrap();
+        let script = parse(
+            "power on --zone zone2
+             if power == on then volume 30 --zone zone2
+             if power == on then volume 40
+",
+        )
+        .unwrap();
+
+        let mut out = Output {
+            label: None,
+            json: false,
+            out: Vec::new(),
+            err: Vec::new(),
+        };
+        let failures = run_script(&amp, &Zone::Main, &script, ErrorPolicy::Stop, &mut out).await;
+        assert_eq!(failures, 0);
+        let state = mock.state();
+        assert_eq!(state.zones[&Zone::Zone2].volume, 30);
+        assert_eq!(state.zones[&Zone::Main].power, PowerState::Standby);
+        assert!(
+            mock.requests()
+                .iter()
+                .all(|path| !path.starts_with("main/setVolume"))
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use crate::cli::{Command, GlobalArgs, KnownValues};
+use crate::commands::{self, UsageError};
+use crate::completions;
+use clap::{CommandFactory, Parser};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::path::PathBuf;
+use yamaha_api::{GetFeatures, Input, SoundProgram, YamahaAmpAsync, Zone};
+
+/// One line typed in the shell or read from a script: the usual subcommands, with
+/// `--zone` and `--json`
+#[derive(Debug, Parser)]
+#[command(name = "", no_binary_name = true, disable_version_flag = true)]
+pub struct ShellLine {
+    /// Zone targeted by this command only
+    #[arg(long, short, global = true, value_parser = KnownValues(Zone::ALL))]
+    pub zone: Option<Zone>,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Words handled by the shell itself
+const BUILTINS: &[&str] = &["help", "exit", "quit"];
+
+/// Subcommands needing their own session, rejected by [`commands::execute`]
+pub const UNAVAILABLE: &[&str] = &[
+    "discover",
+    "watch",
+    "shell",
+    "tui",
+    "run",
+    "scene",
+    "daemon",
+    "completions",
+    "man",
+];
+
+/// Interactive session: reads commands until `exit` or Ctrl-D
+pub async fn shell(global: &GlobalArgs) -> anyhow::Result<()> {
+    let (amp, zone) = commands::connect(global).await?;
+    let features = amp.get_features().await.ok().and_then(|json| {
+        completions::remember_features(&amp, &json);
+        serde_json::from_value::<GetFeatures>(json).ok()
+    });
+
+    let mut editor = Editor::<ShellHelper, FileHistory>::new()?;
+    editor.set_helper(Some(ShellHelper {
+        features,
+        zone: zone.clone(),
+    }));
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    println!(
+        "Connected to {} ({}), zone {zone}. Type `help` for the commands.",
+        amp.info.model,
+        amp.address.authority()
+    );
+    let prompt = format!("{}:{zone}> ", amp.info.model.to_lowercase());
+    loop {
+        let line = tokio::task::block_in_place(|| editor.readline(&prompt));
+        let line = match line {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line.as_str())?;
+        match run_line(&amp, &zone, &line).await {
+            Ok(Some(output)) => println!("{output}"),
+            Ok(None) => break,
+            Err(e) => eprintln!("Error: {e}"),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(path);
+    }
+    Ok(())
+}
+
+/// Runs one line, returning the text to print or `None` to leave the shell
+async fn run_line(amp: &YamahaAmpAsync, zone: &Zone, line: &str) -> anyhow::Result<Option<String>> {
+    let words = shlex::split(line).ok_or_else(|| UsageError("unbalanced quotes".into()))?;
+    match words.first().map(String::as_str) {
+        Some("exit" | "quit") => return Ok(None),
+        Some("help") => return Ok(Some(ShellLine::command().render_help().to_string())),
+        _ => {}
+    }
+
+    let line = match ShellLine::try_parse_from(&words) {
+        Ok(line) => line,
+        Err(e) => return Ok(Some(e.render().to_string().trim_end().to_string())),
+    };
+    let zone = line.zone.as_ref().unwrap_or(zone);
+    let report = commands::execute(amp, zone, &line.command).await?;
+    Ok(Some(if line.json {
+        report.json.to_string()
+    } else {
+        report.text
+    }))
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("yamaha_cli").join("history.txt"))
+}
+
+/// Completes subcommands, and inputs and sound programs from the amplifier's features
+///
+/// Inputs and programs are parsed leniently (see [`crate::cli::OpenValues`]), so every
+/// value the device lists is accepted as typed.
+struct ShellHelper {
+    features: Option<GetFeatures>,
+    zone: Zone,
+}
+
+impl ShellHelper {
+    /// Start of the word being completed and the matching candidates
+    fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let prefix = &line[start..];
+        let words: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let options: Vec<String> = match words.as_slice() {
+            [] => ShellLine::command()
+                .get_subcommands()
+                .map(|command| command.get_name().to_string())
+                .filter(|name| !UNAVAILABLE.contains(&name.as_str()))
+                .chain(BUILTINS.iter().map(|word| word.to_string()))
+                .collect(),
+            [.., "--zone" | "-z"] => self.zone_ids(),
+            [command, ..] => match *command {
+                "input" => self.inputs(&line_zone(&words).unwrap_or_else(|| self.zone.clone())),
+                "program" => self.programs(&line_zone(&words).unwrap_or_else(|| self.zone.clone())),
+                "power" => vec!["on".into(), "standby".into(), "toggle".into()],
+                "mute" => vec!["on".into(), "off".into(), "toggle".into()],
+                "volume" => vec!["up".into(), "down".into()],
+                _ => Vec::new(),
+            },
+        };
+        let mut matches: Vec<String> = options
+            .into_iter()
+            .filter(|option| option.starts_with(prefix))
+            .collect();
+        matches.sort();
+        matches.dedup();
+        (start, matches)
+    }
+
+    fn zone_features(&self, zone: &Zone) -> Option<&yamaha_api::GetFeaturesZone> {
+        self.features
+            .as_ref()?
+            .zone
+            .iter()
+            .find(|features| features.id == zone.as_str())
+    }
+
+    fn zone_ids(&self) -> Vec<String> {
+        match &self.features {
+            Some(features) => features.zone.iter().map(|zone| zone.id.clone()).collect(),
+            None => Zone::ALL.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn inputs(&self, zone: &Zone) -> Vec<String> {
+        match self.zone_features(zone) {
+            Some(zone) => zone.input_list.clone(),
+            None => Input::ALL.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn programs(&self, zone: &Zone) -> Vec<String> {
+        match self.zone_features(zone) {
+            Some(zone) => zone.sound_program_list.clone(),
+            None => SoundProgram::ALL.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+/// Zone given with `--zone` or `-z` among the words already typed, if any
+fn line_zone(words: &[&str]) -> Option<Zone> {
+    let value = words.iter().enumerate().find_map(|(i, word)| match *word {
+        "--zone" | "-z" => words.get(i + 1).copied(),
+        word => word.strip_prefix("--zone="),
+    })?;
+    value.parse().ok()
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use yamaha_api::YamahaError;
+    use yamaha_api::testing::MockDevice;
+
+    fn helper() -> ShellHelper {
+        let features = json!({
+            "zone": [
+                { "id": "main", "input_list": ["hdmi1", "hdmi2", "net_radio"],
+                  "sound_program_list": ["straight", "sci-fi", "standard"] },
+                { "id": "zone2", "input_list": ["tuner"], "sound_program_list": [] }
+            ]
+        });
+        ShellHelper {
+            features: Some(serde_json::from_value(features).unwrap()),
+            zone: Zone::Main,
+        }
+    }
+
+    #[test]
+    fn completes_commands_and_device_values() {
+        let helper = helper();
+        assert_eq!(helper.candidates("vo"), (0, vec!["volume".to_string()]));
+        assert_eq!(
+            helper.candidates("input hdmi"),
+            (6, vec!["hdmi1".to_string(), "hdmi2".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("program s"),
+            (
+                8,
+                vec![
+                    "sci-fi".to_string(),
+                    "standard".to_string(),
+                    "straight".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            helper.candidates("status --zone z"),
+            (14, vec!["zone2".to_string()])
+        );
+        assert!(helper.candidates("raw ").1.is_empty());
+        assert_eq!(
+            helper.candidates("input --zone zone2 t"),
+            (19, vec!["tuner".to_string()])
+        );
+        assert_eq!(
+            helper.candidates("input -z zone2 "),
+            (15, vec!["tuner".to_string()])
+        );
+        assert!(helper.candidates("program --zone=zone2 s").1.is_empty());
+
+        let mut helper = helper;
+        helper.features.as_mut().unwrap().zone[0]
+            .input_list
+            .push("hdmi9".into());
+        assert_eq!(helper.candidates("input hdmi9").1, ["hdmi9"]);
+        assert!(ShellLine::try_parse_from(["input", "hdmi9"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn lines_reuse_the_connection() {
+        let mock = MockDevice::start().await.unwrap();
+        let amp = mock.builder().connect().await.unwrap();
+
+        run_line(&amp, &Zone::Main, "power on -z zone2")
+            .await
+            .unwrap();
+        let output = run_line(&amp, &Zone::Main, "volume 70 --zone zone2 --json")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(output.contains("\"volume\":70"), "{output}");
+        assert_eq!(mock.state().zones[&Zone::Zone2].volume, 70);
+
+        let output = run_line(&amp, &Zone::Main, "input 'hdmi9").await;
+        assert!(output.unwrap_err().is::<UsageError>());
+        let err = run_line(&amp, &Zone::Main, "input hdmi9")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<YamahaError>(),
+            Some(YamahaError::Device { .. })
+        ));
+        assert_eq!(run_line(&amp, &Zone::Main, "exit").await.unwrap(), None);
+        let connections = mock
+            .requests()
+            .iter()
+            .filter(|path| path.ends_with("getDeviceInfo"))
+            .count();
+        assert_eq!(connections, 1);
+    }
+}
@@ -0,0 +1,357 @@
+use crate::cli::GlobalArgs;
+use crate::watch;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, Paragraph};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use yamaha_api::{AmpSnapshot, AmpState, GetFeatures, Input, PowerState, SoundProgram, Zone};
+
+const HELP: &str = "↑/↓ volume  PgUp/PgDn volume ×5  m mute  p power  i input  s program  q quit";
+
+/// Action bound to a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    VolumeUp(u32),
+    VolumeDown(u32),
+    ToggleMute,
+    TogglePower,
+    NextInput,
+    NextProgram,
+    Quit,
+}
+
+fn action(key: KeyEvent) -> Option<Action> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Up | KeyCode::Char('+' | 'k') => Some(Action::VolumeUp(1)),
+        KeyCode::Down | KeyCode::Char('-' | 'j') => Some(Action::VolumeDown(1)),
+        KeyCode::PageUp => Some(Action::VolumeUp(5)),
+        KeyCode::PageDown => Some(Action::VolumeDown(5)),
+        KeyCode::Char('m') => Some(Action::ToggleMute),
+        KeyCode::Char('p') => Some(Action::TogglePower),
+        KeyCode::Char('i') => Some(Action::NextInput),
+        KeyCode::Char('s') => Some(Action::NextProgram),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+/// Full-screen control of one zone, until `q`
+pub async fn tui(global: &GlobalArgs, interval: Duration) -> anyhow::Result<()> {
+    let (state, zone, tasks) = watch::follow(global, interval).await?;
+    let features = state
+        .amp()
+        .get_features()
+        .await
+        .ok()
+        .and_then(|json| serde_json::from_value::<GetFeatures>(json).ok());
+    let zone_features = features
+        .iter()
+        .flat_map(|features| &features.zone)
+        .find(|features| features.id == zone.as_str());
+    let app = App {
+        title: format!(
+            " {} ({}) - {zone} ",
+            state.amp().info.model,
+            state.amp().address.authority()
+        ),
+        inputs: zone_features.map_or_else(Vec::new, |zone| zone.input_list.clone()),
+        programs: zone_features.map_or_else(Vec::new, |zone| zone.sound_program_list.clone()),
+        state,
+        zone,
+        message: None,
+    };
+
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal).await;
+    ratatui::restore();
+    for task in tasks {
+        task.abort();
+    }
+    result
+}
+
+struct App {
+    state: Arc<AmpState>,
+    zone: Zone,
+    title: String,
+    inputs: Vec<String>,
+    programs: Vec<String>,
+    /// Result of the last action, shown above the help line
+    message: Option<String>,
+}
+
+impl App {
+    async fn run(mut self, terminal: &mut ratatui::DefaultTerminal) -> anyhow::Result<()> {
+        let mut receiver = self.state.subscribe();
+        let mut keys = spawn_key_reader();
+        loop {
+            let snapshot = receiver.borrow_and_update().clone();
+            terminal.draw(|frame| self.draw(frame, &snapshot))?;
+            tokio::select! {
+                changed = receiver.changed() => changed?,
+                key = keys.recv() => match key.and_then(action) {
+                    Some(Action::Quit) => return Ok(()),
+                    Some(action) => {
+                        let result = self.apply(action, &snapshot).await;
+                        self.message = result.err().map(|e| e.to_string());
+                    }
+                    None => {}
+                },
+            }
+        }
+    }
+
+    async fn apply(&self, action: Action, snapshot: &AmpSnapshot) -> anyhow::Result<()> {
+        let amp = self.state.amp();
+        let zone = &self.zone;
+        let status = snapshot.zone(zone);
+        match action {
+            Action::VolumeUp(step) => {
+                amp.set_zone_volume_up(zone.clone(), Some(step)).await?;
+            }
+            Action::VolumeDown(step) => {
+                amp.set_zone_volume_down(zone.clone(), Some(step)).await?;
+            }
+            Action::ToggleMute => {
+                let mute = !status.is_some_and(|status| status.mute);
+                amp.set_zone_mute(zone.clone(), mute).await?;
+            }
+            Action::TogglePower => {
+                amp.set_zone_power(zone.clone(), PowerState::Toggle).await?;
+            }
+            Action::NextInput => {
+                let current = status.map(|status| status.input.as_str());
+                if let Some(input) = next(&self.inputs, current) {
+                    amp.set_zone_input(zone.clone(), Input::from_str_lossy(input))
+                        .await?;
+                }
+            }
+            Action::NextProgram => {
+                let current = status
+                    .and_then(|status| status.sound_program.as_ref())
+                    .map(|program| program.as_str());
+                if let Some(program) = next(&self.programs, current) {
+                    amp.set_zone_sound_program(zone.clone(), SoundProgram::from_str_lossy(program))
+                        .await?;
+                }
+            }
+            Action::Quit => {}
+        }
+        self.state.refresh_zone(zone).await?;
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame, snapshot: &AmpSnapshot) {
+        let [
+            status_area,
+            volume_area,
+            playing_area,
+            message_area,
+            help_area,
+        ] = Layout::vertical([
+            Constraint::Length(7),
+            Constraint::Length(3),
+            Constraint::Min(4),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let status = snapshot.zone(&self.zone);
+
+        let mut lines = match status {
+            Some(status) => vec![
+                Line::from(format!("Power:         {}", status.power)),
+                Line::from(format!("Input:         {}", status.input)),
+                Line::from(format!(
+                    "Sound program: {}",
+                    status
+                        .sound_program
+                        .as_ref()
+                        .map_or("-".to_string(), ToString::to_string)
+                )),
+            ],
+            None => vec![Line::from("Loading status...")],
+        };
+        if let Some(signal) = snapshot
+            .signal
+            .as_ref()
+            .filter(|signal| self.zone == Zone::Main && !signal.audio.format.is_empty())
+        {
+            lines.push(Line::from(format!(
+                "Signal:        {} {}",
+                signal.audio.format, signal.audio.fs
+            )));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(self.title.as_str())),
+            status_area,
+        );
+
+        let (ratio, label, color) = match status {
+            Some(status) => {
+                let mut label = format!("{}/{}", status.volume, status.max_volume);
+                if let Some(actual) = &status.actual_volume {
+                    label.push_str(&format!(" ({} {})", actual.value, actual.unit));
+                }
+                if status.mute {
+                    label.push_str(" MUTED");
+                }
+                let ratio = status.volume as f64 / status.max_volume.max(1) as f64;
+                let color = if status.mute {
+                    Color::DarkGray
+                } else {
+                    Color::Green
+                };
+                (ratio.clamp(0.0, 1.0), label, color)
+            }
+            None => (0.0, String::new(), Color::DarkGray),
+        };
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(" Volume "))
+                .gauge_style(Style::default().fg(color))
+                .ratio(ratio)
+                .label(label),
+            volume_area,
+        );
+
+        let playing = snapshot
+            .netusb
+            .as_ref()
+            .filter(|netusb| status.is_some_and(|status| status.input == netusb.input));
+        let lines = match playing {
+            Some(netusb) => vec![
+                Line::from(format!("{} ({})", netusb.track, netusb.playback)),
+                Line::from(netusb.artist.as_str()),
+                Line::from(netusb.album.as_str()),
+            ],
+            None => vec![Line::from("-")],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" Now playing ")),
+            playing_area,
+        );
+
+        let message = self.message.as_deref().or(snapshot.last_error.as_deref());
+        frame.render_widget(
+            Paragraph::new(message.unwrap_or_default()).style(Style::default().fg(Color::Red)),
+            message_area,
+        );
+        frame.render_widget(Paragraph::new(HELP), help_area);
+    }
+}
+
+/// Value following `current` in `values`, wrapping around
+fn next<'a>(values: &'a [String], current: Option<&str>) -> Option<&'a str> {
+    let index = current
+        .and_then(|current| values.iter().position(|value| value == current))
+        .map_or(0, |index| (index + 1) % values.len());
+    values.get(index).map(String::as_str)
+}
+
+/// Reads keys on a dedicated thread, crossterm reads being blocking
+fn spawn_key_reader() -> mpsc::Receiver<KeyEvent> {
+    let (sender, receiver) = mpsc::channel(16);
+    std::thread::spawn(move || {
+        while !sender.is_closed() {
+            match event::poll(Duration::from_millis(200)) {
+                Ok(true) => {
+                    if let Ok(Event::Key(key)) = event::read()
+                        && sender.blocking_send(key).is_err()
+                    {
+                        break;
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use yamaha_api::testing::MockDevice;
+    use yamaha_api::{Input, PowerState};
+
+    #[test]
+    fn keys_map_to_actions() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(action(key(KeyCode::Up)), Some(Action::VolumeUp(1)));
+        assert_eq!(action(key(KeyCode::PageDown)), Some(Action::VolumeDown(5)));
+        assert_eq!(action(key(KeyCode::Char('m'))), Some(Action::ToggleMute));
+        assert_eq!(
+            action(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(action(key(KeyCode::Char('x'))), None);
+    }
+
+    #[test]
+    fn next_value_wraps_around() {
+        let values = vec!["hdmi1".to_string(), "hdmi2".to_string()];
+        assert_eq!(next(&values, Some("hdmi1")), Some("hdmi2"));
+        assert_eq!(next(&values, Some("hdmi2")), Some("hdmi1"));
+        assert_eq!(next(&values, Some("tuner")), Some("hdmi1"));
+        assert_eq!(next(&[], None), None);
+    }
+
+    #[tokio::test]
+    async fn actions_drive_the_device_and_the_view() {
+        let mock = MockDevice::start().await.unwrap();
+        let amp = mock.builder().connect().await.unwrap();
+        let app = App {
+            state: Arc::new(AmpState::new(amp)),
+            zone: Zone::Main,
+            title: " RX-V6A ".into(),
+            inputs: vec!["hdmi1".into(), "hdmi2".into()],
+            programs: Vec::new(),
+            message: None,
+        };
+
+        app.apply(Action::TogglePower, &app.state.snapshot())
+            .await
+            .unwrap();
+        app.apply(Action::NextInput, &app.state.snapshot())
+            .await
+            .unwrap();
+        app.apply(Action::VolumeUp(5), &app.state.snapshot())
+            .await
+            .unwrap();
+        app.apply(Action::ToggleMute, &app.state.snapshot())
+            .await
+            .unwrap();
+        let device = &mock.state().zones[&Zone::Main];
+        assert_eq!(device.power, PowerState::On);
+        assert!(device.mute);
+        assert_eq!(device.input, Input::Hdmi2);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 18)).unwrap();
+        terminal
+            .draw(|frame| app.draw(frame, &app.state.snapshot()))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Power:         on"));
+        assert!(screen.contains("MUTED"));
+        assert!(screen.contains("q quit"));
+    }
+}
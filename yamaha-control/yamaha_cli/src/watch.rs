@@ -0,0 +1,269 @@
+use crate::cli::GlobalArgs;
+use crate::commands;
+use serde_json::{Value, json};
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use yamaha_api::{AmpSnapshot, AmpState, EventListener, EventSettings, Zone};
+
+/// Polling interval kept once UDP events arrive, in case they are filtered later on
+const EVENT_MODE_POLL: Duration = Duration::from_secs(30);
+
+/// Follows the selected zone until Ctrl-C, or until `limit` changes have been printed
+///
+/// # Arguments
+/// * `ndjson` - Print one JSON object per line instead of redrawing a text view
+/// * `interval` - Polling interval until the first UDP event arrives, or for the whole
+///   run if no UDP port could be opened
+pub async fn watch(
+    global: &GlobalArgs,
+    ndjson: bool,
+    interval: Duration,
+    out: &mut impl Write,
+    limit: Option<usize>,
+) -> anyhow::Result<()> {
+    let (state, zone, tasks) = follow(global, interval).await?;
+    let mut receiver = state.subscribe();
+    let live = !ndjson && std::io::stdout().is_terminal();
+
+    let mut last = Value::Null;
+    let mut printed = 0;
+    let result = loop {
+        let view = view(&receiver.borrow_and_update(), &zone);
+        if view != last {
+            let written = if ndjson {
+                writeln!(out, "{}", with_timestamp(&view))
+            } else {
+                if live {
+                    write!(out, "\x1b[2J\x1b[H").ok();
+                }
+                writeln!(out, "{}", describe(&view))
+            };
+            if let Err(e) = written.and_then(|_| out.flush()) {
+                break Err(e.into());
+            }
+            last = view;
+            printed += 1;
+        }
+        if limit.is_some_and(|limit| printed >= limit) {
+            break Ok(());
+        }
+        tokio::select! {
+            changed = receiver.changed() => {
+                if changed.is_err() {
+                    break Ok(());
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        }
+    };
+    for task in tasks {
+        task.abort();
+    }
+    result
+}
+
+/// Connects to the selected amplifier and keeps an [`AmpState`] of its zone up to date
+///
+/// Returns the tasks feeding the state, to abort once done.
+pub async fn follow(
+    global: &GlobalArgs,
+    interval: Duration,
+) -> anyhow::Result<(Arc<AmpState>, Zone, Vec<JoinHandle<()>>)> {
+    let (mut builder, zone) = commands::builder(global)?;
+    let listener = EventListener::bind(0).await.ok();
+    if let Some(port) = listener.as_ref().and_then(|listener| listener.port().ok()) {
+        builder = builder.events(EventSettings {
+            port,
+            ..Default::default()
+        });
+    }
+    let state = Arc::new(AmpState::with_zones(
+        builder.connect().await?,
+        vec![zone.clone()],
+    ));
+    state.refresh().await?;
+
+    let mut tasks = vec![spawn_polling(&state, interval)];
+    if let Some(listener) = listener {
+        tasks.push(state.spawn_events(listener));
+    }
+    Ok((state, zone, tasks))
+}
+
+/// Polls every `interval` until the amplifier's events are seen to arrive, then every
+/// [`EVENT_MODE_POLL`]
+fn spawn_polling(state: &Arc<AmpState>, interval: Duration) -> JoinHandle<()> {
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        loop {
+            let poll = match state.has_received_events() {
+                true => EVENT_MODE_POLL,
+                false => interval,
+            };
+            tokio::time::sleep(poll).await;
+            let _ = state.refresh().await;
+        }
+    })
+}
+
+/// Fields shown by `watch`, compared to detect changes
+fn view(snapshot: &AmpSnapshot, zone: &Zone) -> Value {
+    let status = snapshot.zone(zone);
+    let signal = snapshot
+        .signal
+        .as_ref()
+        .filter(|_| *zone == Zone::Main)
+        .filter(|signal| !signal.audio.format.is_empty());
+    let playing = snapshot
+        .netusb
+        .as_ref()
+        .filter(|netusb| status.is_some_and(|status| status.input == netusb.input));
+    json!({
+        "zone": zone,
+        "power": status.map(|status| &status.power),
+        "volume": status.map(|status| status.volume),
+        "max_volume": status.map(|status| status.max_volume),
+        "volume_db": status.and_then(|status| status.actual_volume.as_ref()).map(|v| v.value),
+        "mute": status.map(|status| status.mute),
+        "input": status.map(|status| &status.input),
+        "sound_program": status.and_then(|status| status.sound_program.as_ref()),
+        "signal": signal.map(|signal| json!({
+            "format": signal.audio.format,
+            "fs": signal.audio.fs,
+            "video": signal.video.as_ref().map(|video| &video.resolution),
+        })),
+        "now_playing": playing.map(|netusb| json!({
+            "playback": netusb.playback,
+            "artist": netusb.artist,
+            "album": netusb.album,
+            "track": netusb.track,
+            "play_time": netusb.play_time,
+        })),
+        "error": snapshot.last_error,
+    })
+}
+
+fn with_timestamp(view: &Value) -> Value {
+    let mut view = view.clone();
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+    view["timestamp_ms"] = json!(millis);
+    view
+}
+
+fn describe(view: &Value) -> String {
+    let text = |key: &str| match &view[key] {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    };
+    let mut lines = vec![
+        format!("Zone:          {}", text("zone")),
+        format!("Power:         {}", text("power")),
+        format!(
+            "Volume:        {}/{}{}",
+            text("volume"),
+            text("max_volume"),
+            match view["volume_db"].as_f64() {
+                Some(db) => format!(" ({db} dB)"),
+                None => String::new(),
+            }
+        ),
+        format!(
+            "Mute:          {}",
+            if view["mute"] == true { "on" } else { "off" }
+        ),
+        format!("Input:         {}", text("input")),
+        format!("Sound program: {}", text("sound_program")),
+    ];
+    let signal = &view["signal"];
+    if !signal.is_null() {
+        let mut line = format!(
+            "Signal:        {} {}",
+            signal["format"].as_str().unwrap_or_default(),
+            signal["fs"].as_str().unwrap_or_default()
+        );
+        if let Some(video) = signal["video"].as_str() {
+            line.push_str(&format!(", {video}"));
+        }
+        lines.push(line);
+    }
+    let playing = &view["now_playing"];
+    if !playing.is_null() {
+        lines.push(format!(
+            "Now playing:   {} - {} ({})",
+            playing["artist"].as_str().unwrap_or_default(),
+            playing["track"].as_str().unwrap_or_default(),
+            playing["playback"].as_str().unwrap_or_default()
+        ));
+    }
+    if let Some(error) = view["error"].as_str() {
+        lines.push(format!("Last error:    {error}"));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::Parser;
+    use yamaha_api::PowerState;
+    use yamaha_api::testing::MockDevice;
+
+    #[tokio::test]
+    async fn ndjson_emits_one_line_per_change() {
+        let mock = MockDevice::start().await.unwrap();
+        let host = mock.address().authority();
+        let cli = Cli::try_parse_from(["yamaha_cli", "--host", &host, "watch"]).unwrap();
+
+        let changes = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            mock.update(|device| {
+                let main = device.zones.get_mut(&Zone::Main).unwrap();
+                main.power = PowerState::On;
+                main.volume = 99;
+            });
+            mock
+        });
+
+        let mut out = Vec::new();
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            watch(&cli.global, true, Duration::from_secs(1), &mut out, Some(2)),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        drop(changes.await.unwrap());
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["power"], "standby");
+        assert!(lines[0]["signal"].is_null());
+        assert_eq!(lines[1]["power"], "on");
+        assert_eq!(lines[1]["volume"], 99);
+        assert!(lines[1]["timestamp_ms"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn text_view_shows_signal_and_now_playing() {
+        let view = json!({
+            "zone": "main", "power": "on", "volume": 80, "max_volume": 161, "volume_db": -40.5,
+            "mute": false, "input": "net_radio", "sound_program": "straight",
+            "signal": { "format": "PCM", "fs": "44.1 kHz", "video": null },
+            "now_playing": { "playback": "play", "artist": "FIP", "track": "Jazz", "album": "" },
+            "error": null,
+        });
+        let text = describe(&view);
+        assert!(text.contains("Volume:        80/161 (-40.5 dB)"));
+        assert!(text.contains("Signal:        PCM 44.1 kHz\n"));
+        assert!(text.ends_with("Now playing:   FIP - Jazz (play)"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::components::{AmpControlPanel, AmpList, DiscoveryPanel};
+use crate::components::{AmpControlPanel, AmpList, DiscoveryPanel, ScenePanel};
 use crate::state::AppState;
 use eframe::egui;
 use std::sync::Arc;
@@ -62,6 +62,11 @@ impl eframe::App for YamahaApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             let control_panel = AmpControlPanel::new(state.clone(), &self.rt);
             control_panel.show(ui);
+
+            ui.separator();
+
+            let scene_panel = ScenePanel::new(state.clone(), &self.rt);
+            scene_panel.show(ui);
         });
 
         ctx.request_repaint_after(std::time::Duration::from_millis(100));
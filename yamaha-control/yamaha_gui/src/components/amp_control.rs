@@ -1,4 +1,4 @@
-use crate::state::AppState;
+use crate::state::{AmpInfo, AppState};
 use eframe::egui;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -33,17 +33,17 @@ impl AmpControlPanel {
         let selected_amp = state_guard.get_selected_amp().unwrap().clone();
         drop(state_guard);
 
-        ui.heading(&format!("Control: {}", selected_amp.model));
+        ui.heading(format!("Control: {}", selected_amp.model));
         ui.separator();
 
         ui.group(|ui| {
             ui.heading("Power");
             ui.horizontal(|ui| {
                 if ui.button("🔴 Power On").clicked() {
-                    self.send_power_command(selected_amp.ip, PowerState::On);
+                    self.send_power_command(&selected_amp, PowerState::On);
                 }
                 if ui.button("⏸ Standby").clicked() {
-                    self.send_power_command(selected_amp.ip, PowerState::Standby);
+                    self.send_power_command(&selected_amp, PowerState::Standby);
                 }
             });
         });
@@ -53,14 +53,15 @@ impl AmpControlPanel {
         ui.group(|ui| {
             ui.heading("Volume");
 
-            let mut volume = {
-                let state_guard = rt.block_on(async { state.lock().await });
-                state_guard.volume
-            };
+            let amp_state = rt.block_on(async { state.lock().await }).amp_state.clone();
+            let mut volume = amp_state
+                .as_ref()
+                .and_then(|amp_state| amp_state.snapshot().main().map(|status| status.volume))
+                .unwrap_or_default() as i32;
 
             ui.horizontal(|ui| {
                 if ui.button("🔇").clicked() {
-                    self.send_mute_command(selected_amp.ip, true);
+                    self.send_mute_command(&selected_amp, true);
                 }
 
                 let slider_response = ui.add(
@@ -70,15 +71,14 @@ impl AmpControlPanel {
                 );
 
                 if slider_response.changed() {
-                    self.send_volume_command(selected_amp.ip, volume);
-                    let state_guard = rt.block_on(async { state.lock().await });
-                    drop(state_guard);
-                    let mut state_guard = rt.block_on(async { state.lock().await });
-                    state_guard.volume = volume;
+                    self.send_volume_command(&selected_amp, volume);
+                    if let Some(amp_state) = &amp_state {
+                        amp_state.modify_zone(&Zone::Main, |status| status.volume = volume as i64);
+                    }
                 }
 
                 if ui.button("🔊").clicked() {
-                    self.send_mute_command(selected_amp.ip, false);
+                    self.send_mute_command(&selected_amp, false);
                 }
             });
 
@@ -108,7 +108,7 @@ impl AmpControlPanel {
 
                     for (i, (name, input)) in inputs.iter().enumerate() {
                         if ui.button(*name).clicked() {
-                            self.send_input_command(selected_amp.ip, *input);
+                            self.send_input_command(&selected_amp, input.clone());
                         }
 
                         if (i + 1) % 3 == 0 {
@@ -137,7 +137,7 @@ impl AmpControlPanel {
 
                     for (name, program) in &programs {
                         if ui.button(*name).clicked() {
-                            self.send_sound_program_command(selected_amp.ip, *program);
+                            self.send_sound_program_command(&selected_amp, program.clone());
                         }
                     }
                 });
@@ -149,14 +149,14 @@ impl AmpControlPanel {
         ui.group(|ui| {
             ui.heading("Status");
 
-            let state_guard = rt.block_on(async { state.lock().await });
+            let status = rt.block_on(async { state.lock().await }).main_status();
 
-            if let Some(status) = &state_guard.current_status {
+            if let Some(status) = &status {
                 egui::Grid::new("status_grid")
                     .num_columns(2)
                     .show(ui, |ui| {
                         ui.label("Power:");
-                        ui.label(&status.power);
+                        ui.label(status.power.to_string());
                         ui.end_row();
 
                         ui.label("Volume:");
@@ -168,12 +168,14 @@ impl AmpControlPanel {
                         ui.end_row();
 
                         ui.label("Input:");
-                        ui.label(&status.input);
+                        ui.label(status.input.to_string());
                         ui.end_row();
 
-                        ui.label("Sound Program:");
-                        ui.label(&status.sound_program);
-                        ui.end_row();
+                        if let Some(program) = &status.sound_program {
+                            ui.label("Sound Program:");
+                            ui.label(program.to_string());
+                            ui.end_row();
+                        }
                     });
             } else {
                 ui.label("Loading status...");
@@ -181,48 +183,48 @@ impl AmpControlPanel {
         });
     }
 
-    fn send_power_command(&self, ip: std::net::Ipv4Addr, power_state: PowerState) {
-        let rt = self.rt.clone();
-        rt.spawn(async move {
-            if let Ok(Some(amp)) = YamahaAmpAsync::connect(ip).await {
-                let _ = amp.set_power(power_state).await;
-            }
+    fn send_power_command(&self, amp: &AmpInfo, power_state: PowerState) {
+        let Ok(amp) = amp.amp() else {
+            return;
+        };
+        self.rt.spawn(async move {
+            let _ = amp.set_power(power_state).await;
         });
     }
 
-    fn send_volume_command(&self, ip: std::net::Ipv4Addr, volume: i32) {
-        let rt = self.rt.clone();
-        rt.spawn(async move {
-            if let Ok(Some(amp)) = YamahaAmpAsync::connect(ip).await {
-                let _ = amp.set_volume(volume).await;
-            }
+    fn send_volume_command(&self, amp: &AmpInfo, volume: i32) {
+        let Ok(amp) = amp.amp() else {
+            return;
+        };
+        self.rt.spawn(async move {
+            let _ = amp.set_volume(volume).await;
         });
     }
 
-    fn send_mute_command(&self, ip: std::net::Ipv4Addr, mute: bool) {
-        let rt = self.rt.clone();
-        rt.spawn(async move {
-            if let Ok(Some(amp)) = YamahaAmpAsync::connect(ip).await {
-                let _ = amp.set_mute(mute).await;
-            }
+    fn send_mute_command(&self, amp: &AmpInfo, mute: bool) {
+        let Ok(amp) = amp.amp() else {
+            return;
+        };
+        self.rt.spawn(async move {
+            let _ = amp.set_mute(mute).await;
         });
     }
 
-    fn send_input_command(&self, ip: std::net::Ipv4Addr, input: Input) {
-        let rt = self.rt.clone();
-        rt.spawn(async move {
-            if let Ok(Some(amp)) = YamahaAmpAsync::connect(ip).await {
-                let _ = amp.set_input(input).await;
-            }
+    fn send_input_command(&self, amp: &AmpInfo, input: Input) {
+        let Ok(amp) = amp.amp() else {
+            return;
+        };
+        self.rt.spawn(async move {
+            let _ = amp.set_input(input).await;
         });
     }
 
-    fn send_sound_program_command(&self, ip: std::net::Ipv4Addr, program: SoundProgram) {
-        let rt = self.rt.clone();
-        rt.spawn(async move {
-            if let Ok(Some(amp)) = YamahaAmpAsync::connect(ip).await {
-                let _ = amp.set_sound_program(program).await;
-            }
+    fn send_sound_program_command(&self, amp: &AmpInfo, program: SoundProgram) {
+        let Ok(amp) = amp.amp() else {
+            return;
+        };
+        self.rt.spawn(async move {
+            let _ = amp.set_sound_program(program).await;
         });
     }
 }
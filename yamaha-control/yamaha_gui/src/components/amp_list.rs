@@ -1,7 +1,8 @@
-use crate::state::AppState;
+use crate::state::{AppState, STATUS_POLL_INTERVAL};
 use eframe::egui;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use yamaha_api::AmpState;
 
 pub struct AmpList {
     state: Arc<Mutex<AppState>>,
@@ -36,7 +37,7 @@ impl AmpList {
                 let is_selected = selected_amp == Some(idx);
 
                 let response =
-                    ui.selectable_label(is_selected, format!("🎵 {} ({})", amp.model, amp.ip));
+                    ui.selectable_label(is_selected, format!("🎵 {} ({})", amp.model, amp.address));
 
                 if response.clicked() {
                     let mut state_guard = rt.block_on(async { state.lock().await });
@@ -58,31 +59,23 @@ impl AmpList {
         let state = self.state.clone();
 
         rt.spawn(async move {
-            let amp_ip = {
+            let amp_info = {
                 let state_guard = state.lock().await;
                 if let Some(amp) = state_guard.amplifiers.get(amp_idx) {
-                    amp.ip
+                    amp.clone()
                 } else {
                     return;
                 }
             };
 
-            if let Ok(Some(amp)) = yamaha_api::YamahaAmpAsync::connect(amp_ip).await {
-                if let Ok(status_json) = amp.get_main_status().await {
-                    if let Ok(status) = serde_json::from_value::<yamaha_api::GetStatus>(status_json)
-                    {
-                        let mut state_guard = state.lock().await;
-                        state_guard.current_status = Some(status.clone());
-                        state_guard.volume = status.volume as i32;
-                        state_guard.is_muted = status.mute;
-                        state_guard.power_state = Some(if status.power == "on" {
-                            yamaha_api::PowerState::On
-                        } else {
-                            yamaha_api::PowerState::Standby
-                        });
-                    }
-                }
-            }
+            let Ok(amp) = amp_info.amp() else {
+                return;
+            };
+            let amp_state = Arc::new(AmpState::new(amp));
+            let task = amp_state.spawn_polling(STATUS_POLL_INTERVAL);
+
+            let mut state_guard = state.lock().await;
+            state_guard.watch_amp(amp_state, task.abort_handle());
         });
     }
 }
@@ -61,10 +61,50 @@ impl DiscoveryPanel {
             if is_discovering {
                 ui.add_enabled(false, egui::Button::new("🔍 Discovering..."));
                 ui.spinner();
-            } else {
-                if ui.button("🔍 Discover Amplifiers").clicked() {
-                    self.start_discovery();
+            } else if ui.button("🔍 Discover Amplifiers").clicked() {
+                self.start_discovery();
+            }
+
+            ui.horizontal(|ui| {
+                let mut host = {
+                    let state_guard = rt.block_on(async { state.lock().await });
+                    state_guard.manual_host.clone()
+                };
+
+                ui.label("Host:");
+                if ui.text_edit_singleline(&mut host).changed() {
+                    let mut state_guard = rt.block_on(async { state.lock().await });
+                    state_guard.manual_host = host.clone();
+                }
+
+                if ui.button("➕ Add").clicked() && !host.trim().is_empty() {
+                    self.add_host(host.trim().to_string());
+                }
+            });
+        });
+    }
+
+    fn add_host(&self, host: String) {
+        let rt = self.rt.clone();
+        let state = self.state.clone();
+
+        rt.spawn(async move {
+            let address = match host.parse::<AmpAddress>() {
+                Ok(address) => address,
+                Err(e) => {
+                    eprintln!("Invalid host: {}", e);
+                    return;
+                }
+            };
+
+            match YamahaAmpAsync::connect(address).await {
+                Ok(Some(amp)) => {
+                    let mut state_guard = state.lock().await;
+                    state_guard.add_amplifier(AmpInfo::from_amp(&amp));
+                    state_guard.manual_host.clear();
                 }
+                Ok(None) => eprintln!("No amplifier found at {}", host),
+                Err(e) => eprintln!("Connection to {} failed: {}", host, e),
             }
         });
     }
@@ -89,15 +129,7 @@ impl DiscoveryPanel {
                 Ok(amps) => {
                     let mut state_guard = state.lock().await;
                     for amp in amps {
-                        let amp_info = AmpInfo {
-                            ip: amp.ip,
-                            model: amp.info.model.clone(),
-                            device_id: amp.info.device_id.clone(),
-                            api_version: amp.info.api_version,
-                            is_connected: true,
-                            last_seen: Some(std::time::SystemTime::now()),
-                        };
-                        state_guard.add_amplifier(amp_info);
+                        state_guard.add_amplifier(AmpInfo::from_amp(&amp));
                     }
                 }
                 Err(e) => {
@@ -1,7 +1,9 @@
 pub mod amp_control;
 pub mod amp_list;
 pub mod discovery;
+pub mod scenes;
 
 pub use amp_control::AmpControlPanel;
 pub use amp_list::AmpList;
 pub use discovery::DiscoveryPanel;
+pub use scenes::ScenePanel;
@@ -0,0 +1,124 @@
+use crate::state::AppState;
+use eframe::egui;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use yamaha_api::{Scene, Zone};
+
+pub struct ScenePanel {
+    state: Arc<Mutex<AppState>>,
+    rt: Arc<tokio::runtime::Runtime>,
+}
+
+impl ScenePanel {
+    pub fn new(state: Arc<Mutex<AppState>>, rt: &Arc<tokio::runtime::Runtime>) -> Self {
+        Self {
+            state,
+            rt: rt.clone(),
+        }
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        let rt = self.rt.clone();
+        let state = self.state.clone();
+
+        let mut state_guard = rt.block_on(async { state.lock().await });
+        if state_guard.selected_amp.is_none() {
+            return;
+        }
+
+        ui.group(|ui| {
+            ui.heading("Scenes");
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut state_guard.scene_name);
+                let name = state_guard.scene_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new("💾 Save"))
+                    .clicked()
+                {
+                    self.save_scene(name);
+                }
+            });
+
+            for name in state_guard.scene_names.clone() {
+                ui.horizontal(|ui| {
+                    ui.label(&name);
+                    if ui.button("▶ Apply").clicked() {
+                        self.apply_scene(name.clone());
+                    }
+                    if ui.button("🗑").clicked() {
+                        let result = state_guard.scene_store.remove(&name);
+                        state_guard.scene_message = result.err().map(|e| e.to_string());
+                        state_guard.scene_names =
+                            state_guard.scene_store.list().unwrap_or_default();
+                    }
+                });
+            }
+
+            if let Some(message) = &state_guard.scene_message {
+                ui.label(message);
+            }
+        });
+    }
+
+    /// Captures the main zone of the selected amplifier under `name`
+    fn save_scene(&self, name: String) {
+        let state = self.state.clone();
+        self.rt.spawn(async move {
+            let (amp, store) = {
+                let state_guard = state.lock().await;
+                let Some(amp) = state_guard
+                    .get_selected_amp()
+                    .and_then(|amp| amp.amp().ok())
+                else {
+                    return;
+                };
+                (amp, state_guard.scene_store.clone())
+            };
+            let result = match Scene::capture(&amp, name.as_str(), Zone::Main).await {
+                Ok(scene) => store.save(&scene),
+                Err(e) => Err(e),
+            };
+
+            let mut state_guard = state.lock().await;
+            state_guard.scene_message = Some(match result {
+                Ok(()) => format!("Saved scene \"{name}\""),
+                Err(e) => e.to_string(),
+            });
+            state_guard.scene_names = store.list().unwrap_or_default();
+        });
+    }
+
+    fn apply_scene(&self, name: String) {
+        let state = self.state.clone();
+        self.rt.spawn(async move {
+            let (amp, store, amp_state) = {
+                let state_guard = state.lock().await;
+                let Some(amp) = state_guard
+                    .get_selected_amp()
+                    .and_then(|amp| amp.amp().ok())
+                else {
+                    return;
+                };
+                (
+                    amp,
+                    state_guard.scene_store.clone(),
+                    state_guard.amp_state.clone(),
+                )
+            };
+            let result = match store.load(&name) {
+                Ok(scene) => scene.apply(&amp, &Zone::Main).await,
+                Err(e) => Err(e),
+            };
+            if let Some(amp_state) = amp_state {
+                let _ = amp_state.refresh_zone(&Zone::Main).await;
+            }
+
+            state.lock().await.scene_message = Some(match result {
+                Ok(calls) if calls.is_empty() => format!("Already in scene \"{name}\""),
+                Ok(calls) => format!("Applied scene \"{name}\" ({} changes)", calls.len()),
+                Err(e) => e.to_string(),
+            });
+        });
+    }
+}
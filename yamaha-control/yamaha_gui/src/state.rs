@@ -1,25 +1,34 @@
 use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::task::AbortHandle;
 use yamaha_api::*;
 
+/// Interval between two status refreshes of the selected amplifier
+pub const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 #[derive(Clone)]
 pub struct AppState {
     pub amplifiers: Vec<AmpInfo>,
     pub selected_amp: Option<usize>,
     pub is_discovering: bool,
     pub discovery_config: DiscoveryConfig,
-    pub current_status: Option<GetStatus>,
-    pub volume: i32,
-    pub is_muted: bool,
-    pub current_input: Option<Input>,
-    pub current_program: Option<SoundProgram>,
-    pub power_state: Option<PowerState>,
+    pub manual_host: String,
+    pub amp_state: Option<Arc<AmpState>>,
+    pub amp_state_task: Option<AbortHandle>,
+    pub scene_store: SceneStore,
+    pub scene_names: Vec<String>,
+    /// Name typed in the scene panel
+    pub scene_name: String,
+    /// Outcome of the last scene action
+    pub scene_message: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AmpInfo {
-    pub ip: Ipv4Addr,
+    pub address: AmpAddress,
     pub model: String,
     pub device_id: String,
     pub api_version: f32,
@@ -29,6 +38,11 @@ pub struct AmpInfo {
 
 impl AppState {
     pub fn new() -> Self {
+        // Same directory as `yamaha_cli scene`, so scenes are shared between both tools
+        let scene_dir = dirs::config_dir()
+            .map_or_else(|| PathBuf::from("."), |dir| dir.join("yamaha_cli"))
+            .join("scenes");
+        let scene_store = SceneStore::new(scene_dir);
         Self {
             amplifiers: Vec::new(),
             selected_amp: None,
@@ -38,18 +52,24 @@ impl AppState {
                 mask: 24,
                 timeout: Duration::from_millis(500),
                 max_concurrent: 50,
+                ..Default::default()
             },
-            current_status: None,
-            volume: 0,
-            is_muted: false,
-            current_input: None,
-            current_program: None,
-            power_state: None,
+            manual_host: String::new(),
+            amp_state: None,
+            amp_state_task: None,
+            scene_names: scene_store.list().unwrap_or_default(),
+            scene_store,
+            scene_name: String::new(),
+            scene_message: None,
         }
     }
 
     pub fn add_amplifier(&mut self, amp_info: AmpInfo) {
-        if let Some(existing) = self.amplifiers.iter_mut().find(|a| a.ip == amp_info.ip) {
+        if let Some(existing) = self
+            .amplifiers
+            .iter_mut()
+            .find(|a| a.address == amp_info.address)
+        {
             *existing = amp_info;
         } else {
             self.amplifiers.push(amp_info);
@@ -59,4 +79,43 @@ impl AppState {
     pub fn get_selected_amp(&self) -> Option<&AmpInfo> {
         self.selected_amp.and_then(|idx| self.amplifiers.get(idx))
     }
+
+    /// Replaces the mirrored amplifier, stopping the refresh task of the previous one
+    pub fn watch_amp(&mut self, amp_state: Arc<AmpState>, task: AbortHandle) {
+        if let Some(previous) = self.amp_state_task.replace(task) {
+            previous.abort();
+        }
+        self.amp_state = Some(amp_state);
+    }
+
+    /// Last known status of the selected amplifier's main zone
+    pub fn main_status(&self) -> Option<GetStatus> {
+        self.amp_state
+            .as_ref()
+            .and_then(|amp_state| amp_state.snapshot().main().cloned())
+    }
+}
+
+impl AmpInfo {
+    pub fn from_amp(amp: &YamahaAmpAsync) -> Self {
+        Self {
+            address: amp.address.clone(),
+            model: amp.info.model.clone(),
+            device_id: amp.info.device_id.clone(),
+            api_version: amp.info.api_version,
+            is_connected: true,
+            last_seen: Some(std::time::SystemTime::now()),
+        }
+    }
+
+    /// Builds a client from the cached device info, without querying the amplifier
+    pub fn amp(&self) -> Result<YamahaAmpAsync, YamahaError> {
+        YamahaAmpBuilder::from_address(self.address.clone())
+            .device_info(DeviceInfo {
+                model: self.model.clone(),
+                device_id: self.device_id.clone(),
+                api_version: self.api_version,
+            })
+            .build()
+    }
 }